@@ -7,8 +7,9 @@ use syn::parse::Parser;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
-    parse_macro_input, Attribute, Data, DeriveInput, Expr, ExprLit, GenericParam, Ident, Index,
-    Lit, Member, Meta, MetaNameValue, Token, TypeParam,
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, ExprLit, GenericArgument,
+    GenericParam, Ident, Index, Lit, Member, Meta, MetaNameValue, PathArguments, Token, Type,
+    TypeParam, TypePath,
 };
 
 #[proc_macro_derive(StarkNameDisplay)]
@@ -46,6 +47,118 @@ pub fn derive(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Implements `crate::columns_view::ColumnNames` for a columns view, naming
+/// columns by their path, like `inst.rs1_selected` or `limbs.a[3]`.
+///
+/// Fields of the struct's type parameter are single columns, arrays are
+/// indexed, `PhantomData` has no columns, and any other field has to be a
+/// columns view that implements `ColumnNames` itself.
+#[proc_macro_error]
+#[proc_macro_derive(ColumnNames)]
+pub fn derive_column_names(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let Some(column) = ast.generics.type_params().next().map(|param| &param.ident) else {
+        abort!(
+            ast,
+            "expected a type parameter for the values of the columns"
+        )
+    };
+    let Data::Struct(data) = &ast.data else {
+        abort!(ast, "only structs are supported")
+    };
+    let fields = data.fields.iter().enumerate().map(|(index, field)| {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| index.to_string(), ToString::to_string);
+        let push = push_column_names(&field.ty, column);
+        quote!({
+            let name = format!("{prefix}{}", #field_name);
+            #push
+        })
+    });
+
+    quote!(
+        /// Code generated via proc_macro `ColumnNames`
+        impl #impl_generics crate::columns_view::ColumnNames for #ident #ty_generics #where_clause {
+            fn push_column_names(prefix: &str, names: &mut Vec<String>) {
+                #(#fields)*
+            }
+        }
+    )
+    .into()
+}
+
+/// Code to push the names of the columns of a field of type `ty`, which is
+/// named `name` in the generated code.
+fn push_column_names(ty: &Type, column: &Ident) -> proc_macro2::TokenStream {
+    match ty {
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident(column) =>
+            quote!(names.push(name);),
+        Type::Path(path)
+            if path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "PhantomData") =>
+            quote!(let _ = name;),
+        Type::Array(array) => {
+            let len = &array.len;
+            let push = push_column_names(&array.elem, column);
+            quote!(
+                for index in 0..#len {
+                    let name = format!("{name}[{index}]");
+                    #push
+                }
+            )
+        }
+        Type::Path(path) => match view_over(path, column) {
+            // A view over compound values, like `XorView<[T; 32]>`: name the
+            // columns of `XorView<T>`, and expand each by the compound value.
+            Some((view, value)) => {
+                let push = push_column_names(&value, column);
+                quote!(
+                    let mut view_names = vec![];
+                    <#view as crate::columns_view::ColumnNames>::push_column_names(
+                        &format!("{name}."),
+                        &mut view_names,
+                    );
+                    for name in view_names {
+                        #push
+                    }
+                )
+            }
+            None => quote!(
+                <#ty as crate::columns_view::ColumnNames>::push_column_names(
+                    &format!("{name}."),
+                    names,
+                );
+            ),
+        },
+        _ => abort!(ty, "unsupported type of columns"),
+    }
+}
+
+/// Splits a view over compound values, like `XorView<[T; 32]>`, into the
+/// view over single columns, `XorView<T>`, and the compound value, `[T; 32]`.
+fn view_over(path: &TypePath, column: &Ident) -> Option<(TypePath, Type)> {
+    let mut view = path.clone();
+    let PathArguments::AngleBracketed(args) = &mut view.path.segments.last_mut()?.arguments else {
+        return None;
+    };
+    let GenericArgument::Type(value) = args.args.first_mut()? else {
+        return None;
+    };
+    if matches!(value, Type::Path(value) if value.path.is_ident(column)) {
+        return None;
+    }
+    let value = std::mem::replace(value, parse_quote!(#column));
+    Some((view, value))
+}
+
 fn consume_err<T, F, ErrFn>(result: Result<T, F>, err_fn: ErrFn) -> Option<T>
 where
     ErrFn: FnOnce(F), {
@@ -1,10 +1,12 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
 use crate::stark::mozak_stark::{BitshiftTable, TableWithTypedOutput};
 
 columns_view_impl!(Bitshift);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct Bitshift<T> {
     pub amount: T,
     pub multiplier: T,
@@ -22,21 +24,21 @@ impl From<u8> for Bitshift<u32> {
 make_col_map!(BitshiftView);
 columns_view_impl!(BitshiftView);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct BitshiftView<T> {
     /// Contains the `Bitshift` columns with the shift amount and the
     /// multiplier.
     pub executed: Bitshift<T>,
     /// This column tells if the row has a corresponding value row
-    /// in the CPU table. If not, then this is a padding row, used to
+    /// in the `MulDiv` table. If not, then this is a padding row, used to
     /// pad the table to a power of 2 size or a dummy row
     /// to bridge a gap in the shift amounts.
     /// For logup, this can be used to track multiplicity
     pub multiplicity: T,
 }
 
-/// Lookup from the CPU table into Bitshift stark table.
+/// Lookup from the `MulDiv` table into Bitshift stark table.
 #[must_use]
-pub fn lookup_for_cpu() -> TableWithTypedOutput<Bitshift<Column>> {
+pub fn lookup_for_mul_div() -> TableWithTypedOutput<Bitshift<Column>> {
     BitshiftTable::new(COL_MAP.executed, COL_MAP.multiplicity)
 }
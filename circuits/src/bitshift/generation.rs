@@ -1,21 +1,21 @@
 use plonky2::hash::hash_types::RichField;
 
 use crate::bitshift::columns::BitshiftView;
-use crate::cpu::columns::CpuState;
+use crate::ops::mul_div::columns::MulDiv;
 
-fn filter_shift_trace<F: RichField>(cpu_trace: &[CpuState<F>]) -> impl Iterator<Item = u64> + '_ {
-    cpu_trace
+fn filter_shift_trace<F: RichField>(mul_div_trace: &[MulDiv<F>]) -> impl Iterator<Item = u64> + '_ {
+    mul_div_trace
         .iter()
-        .filter(|row| row.inst.ops.ops_that_shift().is_one())
+        .filter(|row| row.inst.ops.shifts().is_one())
         .map(|row| row.bitshift.amount.to_noncanonical_u64())
 }
 
 #[must_use]
 pub fn generate_shift_amount_trace<F: RichField>(
-    cpu_trace: &[CpuState<F>],
+    mul_div_trace: &[MulDiv<F>],
 ) -> Vec<BitshiftView<F>> {
     let mut multiplicities = [0; 32];
-    filter_shift_trace(cpu_trace).for_each(|amount| {
+    filter_shift_trace(mul_div_trace).for_each(|amount| {
         multiplicities[usize::try_from(amount).expect("cast should succeed")] += 1;
     });
     (0..32u8)
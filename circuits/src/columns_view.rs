@@ -29,6 +29,13 @@ pub(crate) const unsafe fn transmute_ref<T, U>(t: &T) -> &U {
 
 pub trait HasNamedColumns {
     type Columns;
+
+    /// Names of the columns of the table, in column order.
+    fn column_names(&self) -> Vec<String>
+    where
+        Self::Columns: ColumnNames, {
+        column_names::<Self::Columns>()
+    }
 }
 
 pub trait NumberOfColumns {
@@ -271,3 +278,57 @@ where
     indices_arr[which] = 1;
     indices_arr.into()
 }
+
+/// Names of the columns of a columns view, like `inst.rs1_selected` or
+/// `limbs.a[3]`.
+///
+/// Derive it with `mozak_circuits_derive::ColumnNames`.
+pub trait ColumnNames {
+    /// Appends the names of the columns to `names`, in column order, each
+    /// prefixed by `prefix`.
+    fn push_column_names(prefix: &str, names: &mut Vec<String>);
+}
+
+/// Names of the columns of a `Columns` view, in column order.
+#[must_use]
+pub fn column_names<Columns: ColumnNames>() -> Vec<String> {
+    let mut names = vec![];
+    Columns::push_column_names("", &mut names);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::poseidon2::columns::Poseidon2State;
+    use crate::unstark::NoColumns;
+    use crate::xor::columns::XorColumnsView;
+
+    #[test]
+    fn names_of_nested_columns() {
+        let names = column_names::<XorColumnsView<GoldilocksField>>();
+        assert_eq!(names.len(), XorColumnsView::<()>::NUMBER_OF_COLUMNS);
+        assert_eq!(names[..5], [
+            "is_execution_row",
+            "execution.a",
+            "execution.b",
+            "execution.out",
+            "limbs.a[0]"
+        ]);
+        assert_eq!(names.last().unwrap(), "limbs.out[31]");
+    }
+
+    #[test]
+    fn names_of_array_columns() {
+        let names = column_names::<Poseidon2State<GoldilocksField>>();
+        assert_eq!(names.len(), Poseidon2State::<()>::NUMBER_OF_COLUMNS);
+        assert_eq!(names[..3], ["is_exe", "input[0]", "input[1]"]);
+    }
+
+    #[test]
+    fn phantom_data_has_no_columns() {
+        assert!(column_names::<NoColumns<GoldilocksField>>().is_empty());
+    }
+}
@@ -1,25 +1,23 @@
 use core::iter::Sum;
 use core::ops::{Add, Mul, Sub};
 
+use mozak_circuits_derive::ColumnNames;
 use mozak_runner::instruction::Op;
 
-use crate::bitshift::columns::Bitshift;
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::cpu_skeleton::columns::CpuSkeletonCtl;
 use crate::cross_table_lookup::{Column, ColumnWithTypedInput};
-use crate::memory::columns::MemoryCtl;
 use crate::poseidon2_sponge::columns::Poseidon2SpongeCtl;
 use crate::program::columns::ProgramRom;
 use crate::rangecheck::columns::RangeCheckCtl;
 use crate::register::RegisterCtl;
 use crate::stark::mozak_stark::{CpuTable, TableWithTypedOutput};
 use crate::storage_device::columns::StorageDeviceCtl;
-use crate::xor::columns::XorView;
 
 columns_view_impl!(OpSelectors);
 /// Selectors for which instruction is currently active.
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct OpSelectors<T> {
     pub add: T,
     pub sub: T,
@@ -69,7 +67,7 @@ pub struct OpSelectors<T> {
 columns_view_impl!(Instruction);
 /// Internal [Instruction] of Stark used for transition constrains
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct Instruction<T> {
     /// The original instruction (+ `imm_value`) used for program
     /// cross-table-lookup.
@@ -145,7 +143,7 @@ impl From<(u32, mozak_runner::instruction::Instruction)> for Instruction<u32> {
 columns_view_impl!(EcallSelectors);
 /// Internal [`Instruction`] of Stark used for transition constraints
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct EcallSelectors<T> {
     // We don't need all of these 'is_<some-ecall>' columns.  Because our CPU table (by itself)
     // doesn't need to be deterministic. We can assert these things in the CTL-ed
@@ -168,7 +166,7 @@ make_col_map!(CpuState);
 columns_view_impl!(CpuState);
 /// Represents the State of the CPU, which is also a row of the trace
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct CpuState<T> {
     pub clk: T,
     pub new_pc: T,
@@ -177,21 +175,9 @@ pub struct CpuState<T> {
     pub op1_value: T,
     pub op2_value_raw: T,
     /// The sum of the value of the second operand register and the
-    /// immediate value. Wrapped around to fit in a `u32`.
+    /// immediate value.
     pub op2_value: T,
-    /// The sum of the value of the second operand and the immediate value as
-    /// field elements. Ie summed without wrapping to fit into u32.
-    pub op2_value_overflowing: T,
-
-    /// `dst_value` contains "correct" (modified from `mem_access_raw` for
-    /// signed operations) value targetted towards `dst`.
     pub dst_value: T,
-    pub dst_sign_bit: T,
-
-    /// `mem_access_raw` contains values fetched or stored into the memory
-    /// table. These values are always unsigned by nature (as mem table does
-    /// not differentiate between signed and unsigned values).
-    pub mem_value_raw: T,
 
     // 0 means non-negative, 1 means negative.
     // (If number is unsigned, it is non-negative.)
@@ -210,35 +196,6 @@ pub struct CpuState<T> {
     /// We need this intermediate variable to keep the constraint degree <= 3.
     pub normalised_diff: T,
 
-    /// Linked values with the Xor Stark Table
-    pub xor: XorView<T>,
-
-    /// Linked values with the Bitshift Stark Table
-    pub bitshift: Bitshift<T>,
-
-    // Division evaluation columns
-    pub op2_value_inv: T,
-    pub quotient_value: T, // range check u32 required
-    pub quotient_sign: T,
-    pub skip_check_quotient_sign: T,
-    pub remainder_value: T, // range check u32 required
-    pub remainder_sign: T,
-    /// Value of `divisor_abs - remainder_abs - 1`
-    /// Used as a helper column to check that `remainder < divisor`.
-    pub remainder_slack: T, // range check u32 required
-
-    // Product evaluation columns
-    pub op1_abs: T,
-    pub op2_abs: T,
-    pub skip_check_product_sign: T,
-    pub product_sign: T,
-    pub product_high_limb: T, // range check u32 required
-    pub product_low_limb: T,  // range check u32 required
-    /// Used as a helper column to check that `product_high_limb != u32::MAX`
-    /// when `product_sign` is 0 and `product_high_limb != 0` when
-    /// `product_sign` is 1
-    pub product_high_limb_inv_helper: T,
-    pub mem_addr: T,
     pub io_addr: T,
     pub io_size: T,
 
@@ -286,9 +243,6 @@ where
 
     /// List of opcodes that only bump the program counter.
     pub fn is_straightline(self) -> P { self.is_running() - self.is_jumping() }
-
-    /// List of opcodes that work with memory.
-    pub fn is_mem_op(&self) -> P { self.sb + self.lb + self.sh + self.lh + self.sw + self.lw }
 }
 
 /// Expressions we need to range check
@@ -298,18 +252,9 @@ where
 #[must_use]
 pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
     let ops = &CPU.inst.ops;
-    let divs = ops.div + ops.rem + ops.srl + ops.sra;
-    let muls: ColumnWithTypedInput<CpuState<i64>> = ops.mul + ops.mulh + ops.sll;
 
     [
-        (CPU.quotient_value, divs),
-        (CPU.remainder_value, divs),
-        (CPU.remainder_slack, divs),
-        (CPU.dst_value, ops.add + ops.sub + ops.jalr),
-        (CPU.inst.pc, ops.jalr),
-        (CPU.abs_diff, ops.bge + ops.blt),
-        (CPU.product_high_limb, muls),
-        (CPU.product_low_limb, muls),
+        (CPU.dst_value, ops.sub),
         // apply range constraints for the sign bits of each operand
         // TODO(Matthias): these are a bit suspicious, because the filter also appears in the data.
         // Carefully review!
@@ -321,69 +266,12 @@ pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>>
             CPU.op2_value - CPU.op2_sign_bit * (1 << 32) + CPU.inst.is_op2_signed * (1 << 31),
             CPU.inst.is_op2_signed,
         ),
-        (CPU.dst_value - CPU.dst_sign_bit * 0xFFFF_FF00, ops.lb),
-        (CPU.dst_value - CPU.dst_sign_bit * 0xFFFF_0000, ops.lh),
     ]
     .into_iter()
     .map(|(columns, filter)| CpuTable::new(RangeCheckCtl(columns), filter))
     .collect()
 }
 
-/// Lookup for Xor stark.
-/// [`CpuTable`](crate::cross_table_lookup::CpuTable).
-#[must_use]
-pub fn lookup_for_xor() -> TableWithTypedOutput<XorView<Column>> {
-    CpuTable::new(CPU.xor, CPU.inst.ops.ops_that_use_xor())
-}
-
-/// Lookup into Memory stark.
-/// [`CpuTable`](crate::cross_table_lookup::CpuTable).
-#[must_use]
-pub fn lookup_for_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
-    CpuTable::new(
-        MemoryCtl {
-            clk: CPU.clk,
-            is_store: CPU.inst.ops.sb,
-            is_load: CPU.inst.ops.lb, // For both `LB` and `LBU`
-            addr: CPU.mem_addr,
-            value: CPU.mem_value_raw,
-        },
-        CPU.inst.ops.byte_mem_ops(),
-    )
-}
-
-/// Lookup into half word Memory stark.
-/// [`CpuTable`](crate::cross_table_lookup::CpuTable).
-#[must_use]
-pub fn lookup_for_halfword_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
-    CpuTable::new(
-        MemoryCtl {
-            clk: CPU.clk,
-            is_store: CPU.inst.ops.sh,
-            is_load: CPU.inst.ops.lh,
-            addr: CPU.mem_addr,
-            value: CPU.mem_value_raw,
-        },
-        CPU.inst.ops.halfword_mem_ops(),
-    )
-}
-
-/// Lookup into fullword Memory table.
-/// [`CpuTable`](crate::cross_table_lookup::CpuTable).
-#[must_use]
-pub fn lookup_for_fullword_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
-    CpuTable::new(
-        MemoryCtl {
-            clk: CPU.clk,
-            is_store: CPU.inst.ops.sw,
-            is_load: CPU.inst.ops.lw,
-            addr: CPU.mem_addr,
-            value: CPU.mem_value_raw,
-        },
-        CPU.inst.ops.fullword_mem_ops(),
-    )
-}
-
 /// Column containing the data to be matched against `StorageDevice` starks.
 /// [`CpuTable`](crate::cross_table_lookup::CpuTable).
 #[must_use]
@@ -408,27 +296,6 @@ pub fn lookup_for_storage_tables() -> TableWithTypedOutput<StorageDeviceCtl<Colu
     )
 }
 
-impl<T: core::ops::Add<Output = T>> OpSelectors<T> {
-    #[must_use]
-    pub fn ops_that_use_xor(self) -> T {
-        self.xor + self.or + self.and + self.srl + self.sll + self.sra
-    }
-
-    pub fn ops_that_shift(self) -> T { self.sll + self.srl + self.sra }
-
-    pub fn byte_mem_ops(self) -> T { self.sb + self.lb }
-
-    pub fn halfword_mem_ops(self) -> T { self.sh + self.lh }
-
-    pub fn fullword_mem_ops(self) -> T { self.sw + self.lw }
-}
-
-/// Lookup into `Bitshift` stark.
-#[must_use]
-pub fn lookup_for_shift_amount() -> TableWithTypedOutput<Bitshift<Column>> {
-    CpuTable::new(CPU.bitshift, CPU.inst.ops.ops_that_shift())
-}
-
 /// Columns containing the data of original instructions.
 #[must_use]
 pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
@@ -439,7 +306,8 @@ pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
             // Combine columns into a single column.
             // - ops: This is an internal opcode, not the opcode from RISC-V, and can fit within 5
             //   bits.
-            // - is_op1_signed and is_op2_signed: These fields occupy 1 bit each.
+            // - is_op1_signed and is_op2_signed: These fields occupy 1 bit each. Loads never have a
+            //   signed second operand, so they share that slot with is_dst_signed.
             // - rs1_select, rs2_select, and rd_select: These fields require 5 bits each.
             // - imm_value: This field requires 32 bits.
             // Therefore, the total bit requirement is 5 * 6 + 32 = 62 bits, which is less than the
@@ -450,7 +318,7 @@ pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
                 [
                     ColumnWithTypedInput::ascending_sum(inst.ops),
                     inst.is_op1_signed,
-                    inst.is_op2_signed,
+                    inst.is_op2_signed + inst.is_dst_signed,
                     inst.rs1_selected,
                     inst.rs2_selected,
                     inst.rd_selected,
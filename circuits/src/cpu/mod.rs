@@ -1,13 +1,6 @@
-pub mod bitwise;
-pub mod branches;
 pub mod columns;
-pub mod div;
 pub mod ecall;
 pub mod generation;
-pub mod jalr;
-pub mod memory;
-pub mod mul;
-pub mod shift;
 pub mod signed_comparison;
 pub mod stark;
 pub mod sub;
@@ -36,6 +36,47 @@ pub(crate) fn signed_constraints<'a, P: Copy>(
     cb.always((1 - lv.inst.is_op2_signed) * lv.op2_sign_bit);
 }
 
+/// Constraints for `less_than` and `normalised_diff`
+/// For `less_than`:
+///  `1` iff `r1 < r2`
+///  `0` iff `r1 >= r2`
+/// This holds when r1, r2 are signed or unsigned.
+///
+/// For `normalised_diff`:
+///  `0` iff `r1 == r2`
+///  `1` iff `r1 != r2`
+///
+/// See `ops::branches::stark` for the same constraints on branches.
+pub(crate) fn comparison_constraints<'a, P: Copy>(
+    lv: &CpuState<Expr<'a, P>>,
+    cb: &mut ConstraintBuilder<Expr<'a, P>>,
+) {
+    let lt = lv.less_than;
+    cb.always(lt.is_binary());
+
+    // We add inequality constraints, so that if:
+    // `|r1 - r2| != r1 - r2`, then lt == 0
+    // `|r1 - r2| != r2 - r1`, then lt == 1
+    // However, this is still insufficient, as if |r1 - r2| == 0,
+    // `lt` is not constrained and can also be 1, though it should only be 0.
+    cb.always((1 - lt) * (lv.abs_diff - lv.signed_diff()));
+    cb.always(lt * (lv.abs_diff + lv.signed_diff()));
+
+    // Thus, we need a constraint when |r1 - r2| == 0 -> lt == 0.
+
+    // To do so, we constrain `normalised_diff` to be
+    //  0 iff r1 == r2
+    //  1 iff r1 != r2
+    cb.always(lv.normalised_diff.is_binary());
+    cb.always(lv.signed_diff() * (1 - lv.normalised_diff));
+    cb.always(lv.signed_diff() * lv.cmp_diff_inv - lv.normalised_diff);
+
+    // Finally, we constrain so that only one of both `lt` and `normalised_diff`
+    // can equal 1 at once. There for, if `op1 == op2`, then `normalised_diff == 1`,
+    // thus `lt` can only be 0. Which means we are no longer under constrained.
+    cb.always(lt * (1 - lv.normalised_diff));
+}
+
 pub(crate) fn slt_constraints<'a, P: Copy>(
     lv: &CpuState<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
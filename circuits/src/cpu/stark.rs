@@ -12,9 +12,8 @@ use starky::evaluation_frame::StarkFrame;
 use starky::stark::Stark;
 
 use super::columns::{CpuState, OpSelectors};
-use super::{bitwise, branches, div, ecall, jalr, memory, mul, signed_comparison, sub};
+use super::{ecall, signed_comparison, sub};
 use crate::columns_view::{HasNamedColumns, NumberOfColumns};
-use crate::cpu::shift;
 use crate::expr::{build_ext, build_packed, ConstraintBuilder};
 use crate::unstark::NoColumns;
 
@@ -52,27 +51,12 @@ fn binary_selectors<'a, P: Copy>(
 }
 
 /// Constraints for values in op2, which is the sum of the value of the second
-/// operand register and the immediate value (except for branch instructions).
-/// This may overflow.
+/// operand register and the immediate value.
 fn populate_op2_value<'a, P: Copy>(
     lv: &CpuState<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
-    let ops = &lv.inst.ops;
-    let is_branch_operation = ops.beq + ops.bne + ops.blt + ops.bge;
-    let is_shift_operation = ops.sll + ops.srl + ops.sra;
-
-    cb.always(is_branch_operation * (lv.op2_value - lv.op2_value_raw));
-    cb.always(is_shift_operation * (lv.op2_value - lv.bitshift.multiplier));
-    cb.always(
-        (1 - is_branch_operation - is_shift_operation)
-            * (lv.op2_value_overflowing - lv.inst.imm_value - lv.op2_value_raw),
-    );
-    cb.always(
-        (1 - is_branch_operation - is_shift_operation)
-            * (lv.op2_value_overflowing - lv.op2_value)
-            * (lv.op2_value_overflowing - lv.op2_value - (1 << 32) * ops.is_mem_op()),
-    );
+    cb.always(lv.op2_value - lv.op2_value_raw - lv.inst.imm_value);
 }
 
 const COLUMNS: usize = CpuState::<()>::NUMBER_OF_COLUMNS;
@@ -91,19 +75,13 @@ fn generate_constraints<'a, T: Copy>(
     // Registers
     populate_op2_value(lv, &mut constraints);
 
-    // ADD is now handled by its own table.
-    constraints.always(lv.inst.ops.add);
+    // All other operations are handled by their own tables in `ops`.
+    let ops = &lv.inst.ops;
+    constraints.always(ops.is_running() - ops.sub - ops.slt - ops.ecall);
     sub::constraints(lv, &mut constraints);
-    bitwise::constraints(lv, &mut constraints);
-    branches::comparison_constraints(lv, &mut constraints);
-    branches::constraints(lv, &mut constraints);
-    memory::constraints(lv, &mut constraints);
+    signed_comparison::comparison_constraints(lv, &mut constraints);
     signed_comparison::signed_constraints(lv, &mut constraints);
     signed_comparison::slt_constraints(lv, &mut constraints);
-    shift::constraints(lv, &mut constraints);
-    div::constraints(lv, &mut constraints);
-    mul::constraints(lv, &mut constraints);
-    jalr::constraints(lv, &mut constraints);
     ecall::constraints(lv, &mut constraints);
 
     constraints
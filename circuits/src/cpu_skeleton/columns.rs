@@ -1,3 +1,5 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
 use crate::stark::mozak_stark::{SkeletonTable, TableWithTypedOutput};
@@ -5,7 +7,7 @@ use crate::stark::mozak_stark::{SkeletonTable, TableWithTypedOutput};
 columns_view_impl!(CpuSkeleton);
 make_col_map!(CpuSkeleton);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct CpuSkeleton<T> {
     pub clk: T,
     pub pc: T,
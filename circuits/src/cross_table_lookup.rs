@@ -29,8 +29,39 @@ use crate::stark::proof::{StarkProof, StarkProofTarget};
 
 #[derive(Error, Debug)]
 pub enum LookupError {
-    #[error("Inconsistency found between looking and looked tables")]
-    InconsistentTableRows,
+    #[error(
+        "Inconsistency found between looking and looked tables:\n{}",
+        .0.iter().join("\n")
+    )]
+    InconsistentTableRows(Vec<UnmatchedTuple>),
+}
+
+/// A tuple of a cross table lookup whose multiplicities in the looking and
+/// looked tables do not cancel out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnmatchedTuple {
+    pub values: Vec<u64>,
+    /// Rows of looking tables with the tuple, and its multiplicity there.
+    pub looking: Vec<(TableKind, usize, u64)>,
+    /// Rows of looked tables with the tuple, and its multiplicity there.
+    pub looked: Vec<(TableKind, usize, u64)>,
+}
+
+impl std::fmt::Display for UnmatchedTuple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = |rows: &[(TableKind, usize, u64)]| {
+            rows.iter()
+                .map(|(kind, row, multiplicity)| format!("{kind:?}[{row}] x{multiplicity}"))
+                .join(", ")
+        };
+        write!(
+            f,
+            "  {:?}: looking [{}], looked [{}]",
+            self.values,
+            rows(&self.looking),
+            rows(&self.looked)
+        )
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -75,7 +106,7 @@ pub(crate) fn verify_cross_table_lookups_and_public_sub_tables<
 ) -> Result<()> {
     let mut ctl_zs_openings = ctl_zs_lasts.each_ref().map(|v| v.iter().copied());
     for _ in 0..config.num_challenges {
-        for CrossTableLookup { looking_tables } in cross_table_lookups {
+        for CrossTableLookup { looking_tables, .. } in cross_table_lookups {
             let looking_zs_sum = looking_tables
                 .iter()
                 .map(|table| ctl_zs_openings[table.kind].next().unwrap())
@@ -119,7 +150,7 @@ pub(crate) fn verify_cross_table_lookups_and_public_sub_table_circuit<
 ) {
     let mut ctl_zs_openings = ctl_zs_lasts.each_ref().map(|v| v.iter());
     for _ in 0..config.num_challenges {
-        for CrossTableLookup { looking_tables } in cross_table_lookups {
+        for CrossTableLookup { looking_tables, .. } in cross_table_lookups {
             let looking_zs_sum = builder.add_many(
                 looking_tables
                     .iter()
@@ -158,7 +189,7 @@ pub(crate) fn cross_table_lookup_data<F: RichField, const D: usize>(
         &ctl_challenges.challenges,
         cross_table_lookups
             .iter()
-            .flat_map(|CrossTableLookup { looking_tables }| looking_tables)
+            .flat_map(|CrossTableLookup { looking_tables, .. }| looking_tables)
     )
     .collect::<Vec<_>>()
     .into_par_iter()
@@ -243,7 +274,11 @@ pub fn partial_sums<F: Field>(
 #[allow(clippy::module_name_repetitions)]
 #[derive(Clone, Debug)]
 pub struct CrossTableLookupWithTypedOutput<Row> {
+    /// The looking tables, followed by the looked tables with negated
+    /// filters.
     pub looking_tables: Vec<TableWithTypedOutput<Row>>,
+    /// How many of `looking_tables` are actually looking tables.
+    pub num_looking_tables: usize,
 }
 
 // This is a little trick, so that we can use `CrossTableLookup` as a
@@ -260,7 +295,10 @@ impl<Row: IntoIterator<Item = Column>> CrossTableLookupWithTypedOutput<Row> {
             .into_iter()
             .map(TableWithTypedOutput::to_untyped_output)
             .collect();
-        CrossTableLookup { looking_tables }
+        CrossTableLookup {
+            looking_tables,
+            num_looking_tables: self.num_looking_tables,
+        }
     }
 }
 
@@ -274,8 +312,24 @@ impl<Row> CrossTableLookupWithTypedOutput<Row> {
         mut looking_tables: Vec<TableWithTypedOutput<Row>>,
         looked_tables: Vec<TableWithTypedOutput<Row>>,
     ) -> Self {
+        let num_looking_tables = looking_tables.len();
         looking_tables.extend(looked_tables.into_iter().map(Neg::neg));
-        Self { looking_tables }
+        Self {
+            looking_tables,
+            num_looking_tables,
+        }
+    }
+
+    /// The tables that look up tuples.
+    #[must_use]
+    pub fn looking(&self) -> &[TableWithTypedOutput<Row>] {
+        &self.looking_tables[..self.num_looking_tables]
+    }
+
+    /// The tables whose tuples are looked up, with negated filters.
+    #[must_use]
+    pub fn looked(&self) -> &[TableWithTypedOutput<Row>] {
+        &self.looking_tables[self.num_looking_tables..]
     }
 
     #[must_use]
@@ -477,15 +531,26 @@ pub mod ctl_utils {
     use plonky2::field::polynomial::PolynomialValues;
     use plonky2::hash::hash_types::RichField;
 
-    use crate::cross_table_lookup::{CrossTableLookup, LookupError};
+    use crate::cross_table_lookup::{CrossTableLookup, LookupError, UnmatchedTuple};
     use crate::linear_combination::ColumnSparse;
     use crate::stark::mozak_stark::{MozakStark, Table, TableKind, TableKindArray};
 
+    /// A row of a table in which a tuple appears.
+    #[derive(Clone, Copy, Debug)]
+    struct Location<F> {
+        /// Whether the table is one of the looked tables of the lookup.
+        looked: bool,
+        kind: TableKind,
+        row: usize,
+        /// The filter of the table at `row`, negated for looked tables.
+        multiplicity: F,
+    }
+
     #[derive(Clone, Debug, Default)]
-    struct MultiSet<F>(pub BTreeMap<Vec<u64>, Vec<(TableKind, F)>>);
+    struct MultiSet<F>(pub BTreeMap<Vec<u64>, Vec<Location<F>>>);
 
     impl<F: RichField> std::ops::Deref for MultiSet<F> {
-        type Target = BTreeMap<Vec<u64>, Vec<(TableKind, F)>>;
+        type Target = BTreeMap<Vec<u64>, Vec<Location<F>>>;
 
         fn deref(&self) -> &Self::Target { &self.0 }
     }
@@ -498,6 +563,7 @@ pub mod ctl_utils {
             &mut self,
             trace_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
             table: &Table,
+            looked: bool,
         ) {
             let trace = &trace_poly_values[table.kind];
             let filter_column = table.filter_column.to_field();
@@ -514,56 +580,86 @@ pub mod ctl_utils {
                         .map(|c| c.eval_table(trace, i))
                         .map(|f| f.to_canonical_u64())
                         .collect::<Vec<_>>();
-                    self.entry(row).or_default().push((table.kind, filter));
+                    self.entry(row).or_default().push(Location {
+                        looked,
+                        kind: table.kind,
+                        row: i,
+                        multiplicity: filter,
+                    });
                 };
             }
         }
     }
+
+    /// Splits `locations` into those in looking tables and those in looked
+    /// tables, with the multiplicities of the latter negated back.
+    #[allow(clippy::type_complexity)]
+    fn split_locations<F: RichField>(
+        locations: &[Location<F>],
+    ) -> (Vec<(TableKind, usize, u64)>, Vec<(TableKind, usize, u64)>) {
+        let (looked, looking): (Vec<_>, Vec<_>) =
+            locations.iter().partition(|location| location.looked);
+        let rows = |locations: Vec<&Location<F>>, sign: F| {
+            locations
+                .into_iter()
+                .map(|location| {
+                    let multiplicity = sign * location.multiplicity;
+                    (location.kind, location.row, multiplicity.to_canonical_u64())
+                })
+                .collect()
+        };
+        (rows(looking, F::ONE), rows(looked, F::NEG_ONE))
+    }
+
+    /// Checks that every tuple appears in the looking tables of `ctl` as often
+    /// as in its looked tables.
+    ///
+    /// # Errors
+    ///
+    /// Errors with all the tuples for which that is not the case.
     pub fn check_single_ctl<F: RichField>(
         trace_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
         // TODO(Matthias): make this one work with CrossTableLookupNamed, instead of having to
         // forget the types first.  That should also help with adding better debug messages.
         ctl: &CrossTableLookup,
     ) -> Result<(), LookupError> {
-        /// Sums and compares the multiplicities of the given looking and looked
-        /// locations previously processed.
-        ///
-        /// The CTL check holds iff `looking_multiplicity ==
-        /// looked_multiplicity`.
-        fn check_multiplicities<F: RichField>(
-            row: &[u64],
-            looking_locations: &[(TableKind, F)],
-        ) -> Result<(), LookupError> {
-            let looking_multiplicity = looking_locations.iter().map(|l| l.1).sum::<F>();
-            if looking_multiplicity != F::ZERO {
-                eprintln!(
-                    "Row {row:?} has multiplicity {looking_multiplicity} != 0 in the looking tables.\n\
-                    Looking locations: {looking_locations:?}."
-                );
-                return Err(LookupError::InconsistentTableRows);
-            }
+        // Maps every tuple to the locations it appears at.
+        let mut multiset = MultiSet::<F>::default();
 
-            Ok(())
+        for looking_table in ctl.looking() {
+            multiset.process_row(trace_poly_values, looking_table, false);
         }
-
-        // Maps `m` with `(table.kind, multiplicity) in m[row]`
-        let mut looking_multiset = MultiSet::<F>::default();
-
-        for looking_table in &ctl.looking_tables {
-            looking_multiset.process_row(trace_poly_values, looking_table);
+        for looked_table in ctl.looked() {
+            multiset.process_row(trace_poly_values, looked_table, true);
         }
 
-        // Check that every row in the looking tables appears in the looked table the
-        // same number of times.
-        for (row, looking_locations) in &looking_multiset.0 {
-            check_multiplicities(row, looking_locations).map_err(|e| {
-                eprintln!("Looking multiset: {looking_multiset:?}");
-                e
-            })?;
+        // The CTL check holds iff the multiplicities of every tuple in the
+        // looking tables cancel out with those in the looked tables.
+        let unmatched = multiset
+            .iter()
+            .filter(|(_, locations)| {
+                locations
+                    .iter()
+                    .map(|location| location.multiplicity)
+                    .sum::<F>()
+                    .is_nonzero()
+            })
+            .map(|(values, locations)| {
+                let (looking, looked) = split_locations(locations);
+                UnmatchedTuple {
+                    values: values.clone(),
+                    looking,
+                    looked,
+                }
+            })
+            .collect::<Vec<_>>();
+        if unmatched.is_empty() {
+            Ok(())
+        } else {
+            Err(LookupError::InconsistentTableRows(unmatched))
         }
-
-        Ok(())
     }
+
     pub fn debug_ctl<F: RichField + Extendable<D>, const D: usize>(
         traces_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
         mozak_stark: &MozakStark<F, D>,
@@ -574,7 +670,7 @@ pub mod ctl_utils {
             .enumerate()
             .for_each(|(i, ctl)| {
                 check_single_ctl(traces_poly_values, ctl)
-                    .unwrap_or_else(|e| panic!("CTL {i} failed: {e:?}"));
+                    .unwrap_or_else(|e| panic!("CTL {i} failed: {e}"));
             });
     }
 }
@@ -1,9 +1,17 @@
+#[cfg(any(feature = "debug-constraints", test))]
+use std::cell::RefCell;
 use std::panic::Location;
 
+#[cfg(test)]
+use expr::Counting;
+#[cfg(any(feature = "debug-constraints", test))]
+use expr::DegreeEvaluator;
 pub use expr::PureEvaluator;
-use expr::{BinOp, Cached, Evaluator, Expr, UnaOp};
+use expr::{BinOp, Cached, Evaluator, Expr, Predicate, UnaOp};
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+#[cfg(any(feature = "debug-constraints", test))]
+use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
@@ -86,8 +94,8 @@ impl<E> Constraint<E> {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
-enum ConstraintType {
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub enum ConstraintType {
     FirstRow,
     #[default]
     Always,
@@ -110,35 +118,192 @@ impl<E> From<Vec<Constraint<E>>> for ConstraintBuilder<E> {
     fn from(constraints: Vec<Constraint<E>>) -> Self { Self { constraints } }
 }
 
-impl<E> ConstraintBuilder<E> {
+/// Constraints are given as [`Predicate`]s, or as plain expressions that have
+/// to be zero.  A predicate is split into as many constraints as
+/// [`Predicate::into_constraints`] gives, all with the same location.
+impl<'a, V: Copy> ConstraintBuilder<Expr<'a, V>> {
     #[track_caller]
-    fn constraint(&mut self, term: E, constraint_type: ConstraintType) {
-        self.constraints.push(Constraint {
-            constraint_type,
-            location: Location::caller(),
-            term,
-        });
+    fn constraint(
+        &mut self,
+        predicate: impl Into<Predicate<'a, V>>,
+        constraint_type: ConstraintType,
+    ) {
+        let location = Location::caller();
+        let terms = predicate.into().into_constraints();
+        self.constraints
+            .extend(terms.into_iter().map(|term| Constraint {
+                constraint_type,
+                location,
+                term,
+            }));
     }
 
     #[track_caller]
-    pub fn first_row(&mut self, constraint: E) {
+    pub fn first_row(&mut self, constraint: impl Into<Predicate<'a, V>>) {
         self.constraint(constraint, ConstraintType::FirstRow);
     }
 
     #[track_caller]
-    pub fn last_row(&mut self, constraint: E) {
+    pub fn last_row(&mut self, constraint: impl Into<Predicate<'a, V>>) {
         self.constraint(constraint, ConstraintType::LastRow);
     }
 
     #[track_caller]
-    pub fn always(&mut self, constraint: E) { self.constraint(constraint, ConstraintType::Always); }
+    pub fn always(&mut self, constraint: impl Into<Predicate<'a, V>>) {
+        self.constraint(constraint, ConstraintType::Always);
+    }
 
     #[track_caller]
-    pub fn transition(&mut self, constraint: E) {
+    pub fn transition(&mut self, constraint: impl Into<Predicate<'a, V>>) {
         self.constraint(constraint, ConstraintType::Transition);
     }
 }
 
+impl ConstraintType {
+    /// Whether constraints of this type have to hold on a row with the given
+    /// position.
+    #[must_use]
+    pub fn applies_to(self, is_first_row: bool, is_last_row: bool) -> bool {
+        match self {
+            ConstraintType::FirstRow => is_first_row,
+            ConstraintType::Always => true,
+            ConstraintType::Transition => !is_last_row,
+            ConstraintType::LastRow => is_last_row,
+        }
+    }
+}
+
+/// A constraint that did not hold, as reported by [`failing_constraints`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FailedConstraint {
+    pub constraint_type: ConstraintType,
+    pub location: &'static Location<'static>,
+}
+
+#[cfg(any(feature = "debug-constraints", test))]
+/// Row position and failures seen by [`build_packed`], while inside of
+/// [`failing_constraints`].
+struct FailureCollector {
+    is_first_row: bool,
+    is_last_row: bool,
+    failures: Vec<FailedConstraint>,
+}
+
+#[cfg(any(feature = "debug-constraints", test))]
+thread_local! {
+    static FAILURE_COLLECTOR: RefCell<Option<FailureCollector>> = const { RefCell::new(None) };
+}
+
+/// Runs `eval`, and returns every constraint passed to [`build_packed`] on
+/// this thread in the meantime that does not hold on a row with the given
+/// position.
+///
+/// This lets the debug tooling tell exactly which constraints a row violates,
+/// which the [`ConstraintConsumer`] can't.  Constraints of Starks that are not
+/// built with a [`ConstraintBuilder`] are not seen, and neither are any
+/// constraints without the `debug-constraints` feature.
+#[cfg(any(feature = "debug-constraints", test))]
+pub fn failing_constraints(
+    is_first_row: bool,
+    is_last_row: bool,
+    eval: impl FnOnce(),
+) -> Vec<FailedConstraint> {
+    let previous = FAILURE_COLLECTOR.replace(Some(FailureCollector {
+        is_first_row,
+        is_last_row,
+        failures: vec![],
+    }));
+    eval();
+    FAILURE_COLLECTOR
+        .replace(previous)
+        .map(|collector| collector.failures)
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(feature = "debug-constraints", test)))]
+pub fn failing_constraints(
+    _is_first_row: bool,
+    _is_last_row: bool,
+    eval: impl FnOnce(),
+) -> Vec<FailedConstraint> {
+    eval();
+    vec![]
+}
+
+#[cfg(any(feature = "debug-constraints", test))]
+fn collect_failures<FE: Field, P: PackedField<Scalar = FE>>(constraints: &[Constraint<P>]) {
+    FAILURE_COLLECTOR.with_borrow_mut(|collector| {
+        if let Some(collector) = collector {
+            collector.failures.extend(
+                constraints
+                    .iter()
+                    .filter(|c| {
+                        c.constraint_type
+                            .applies_to(collector.is_first_row, collector.is_last_row)
+                            && c.term.as_slice().iter().any(|x| x.is_nonzero())
+                    })
+                    .map(|c| FailedConstraint {
+                        constraint_type: c.constraint_type,
+                        location: c.location,
+                    }),
+            );
+        }
+    });
+}
+
+/// The degree of a constraint, as reported by [`constraint_degrees`].
+#[cfg(any(feature = "debug-constraints", test))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ConstraintDegree {
+    pub constraint_type: ConstraintType,
+    pub location: &'static Location<'static>,
+    pub degree: usize,
+}
+
+#[cfg(any(feature = "debug-constraints", test))]
+thread_local! {
+    static DEGREE_COLLECTOR: RefCell<Option<Vec<ConstraintDegree>>> = const { RefCell::new(None) };
+}
+
+/// Runs `eval`, and returns the degree of every constraint passed to
+/// [`build_packed`] on this thread in the meantime.
+///
+/// Like [`failing_constraints`], this only sees the constraints of Starks that
+/// are built with a [`ConstraintBuilder`].
+#[cfg(any(feature = "debug-constraints", test))]
+pub fn constraint_degrees(eval: impl FnOnce()) -> Vec<ConstraintDegree> {
+    let previous = DEGREE_COLLECTOR.replace(Some(vec![]));
+    eval();
+    DEGREE_COLLECTOR.replace(previous).unwrap_or_default()
+}
+
+#[cfg(any(feature = "debug-constraints", test))]
+fn collect_degrees<V: Copy>(constraints: &[Constraint<Expr<'_, V>>]) {
+    DEGREE_COLLECTOR.with_borrow_mut(|collector| {
+        if let Some(degrees) = collector {
+            let mut evaluator = DegreeEvaluator::default();
+            degrees.extend(constraints.iter().map(|c| ConstraintDegree {
+                constraint_type: c.constraint_type,
+                location: c.location,
+                degree: evaluator.eval(c.term),
+            }));
+        }
+    });
+}
+
+/// The number of operations and constants that [`build_ext`] would turn into
+/// gates for `cb`.  Like there, sub-trees shared between constraints are only
+/// counted once.
+#[cfg(test)]
+#[must_use]
+pub fn num_operations(cb: ConstraintBuilder<Expr<'_, i64>>) -> u64 {
+    let mut evaluator = Cached::from(Counting::<PureEvaluator<i64>>::default());
+    for constraint in cb.constraints {
+        evaluator.eval(constraint.term);
+    }
+    evaluator.evaluator().count()
+}
+
 pub fn build_ext<F, const D: usize>(
     cb: ConstraintBuilder<Expr<'_, ExtensionTarget<D>>>,
     circuit_builder: &mut CircuitBuilder<F, D>,
@@ -146,11 +311,18 @@ pub fn build_ext<F, const D: usize>(
 ) where
     F: RichField,
     F: Extendable<D>, {
-    for constraint in cb.constraints {
-        let mut evaluator = Cached::from(CircuitBuilderEvaluator {
-            builder: circuit_builder,
-        });
-        let constraint = constraint.map(|constraint| evaluator.eval(constraint));
+    // One evaluator for all constraints, so that sub-trees they share are
+    // only turned into gates once.
+    let mut evaluator = Cached::from(CircuitBuilderEvaluator {
+        builder: circuit_builder,
+    });
+    let evaluated = cb
+        .constraints
+        .into_iter()
+        .map(|c| c.map(|constraint| evaluator.eval(constraint)))
+        .collect::<Vec<_>>();
+
+    for constraint in evaluated {
         (match constraint.constraint_type {
             ConstraintType::FirstRow => RecursiveConstraintConsumer::constraint_first_row,
             ConstraintType::Always => RecursiveConstraintConsumer::constraint,
@@ -168,12 +340,16 @@ pub fn build_packed<F, FE, P, const D: usize, const D2: usize>(
     F: Extendable<D>,
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>, {
+    #[cfg(any(feature = "debug-constraints", test))]
+    collect_degrees(&cb.constraints);
     let mut evaluator = Cached::from(packed_field_evaluator());
     let evaluated = cb
         .constraints
         .into_iter()
         .map(|c| c.map(|constraint| evaluator.eval(constraint)))
         .collect::<Vec<_>>();
+    #[cfg(any(feature = "debug-constraints", test))]
+    collect_failures(&evaluated);
 
     for c in evaluated {
         (match c.constraint_type {
@@ -184,3 +360,24 @@ pub fn build_packed<F, FE, P, const D: usize, const D2: usize>(
         })(yield_constr, c.term);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, Result};
+    use starky::stark_testing::test_stark_circuit_constraints;
+
+    use crate::stark::mozak_stark::{all_starks, MozakStark};
+    use crate::test_utils::{C, D, F};
+
+    /// Every table builds the same constraints natively, via `build_packed`,
+    /// and recursively, via `build_ext`, as checked on random frames.
+    #[test]
+    fn native_and_recursive_constraints_agree() -> Result<()> {
+        let mozak_stark = MozakStark::<F, D>::default();
+        let results = all_starks!(&mozak_stark, |stark, kind| {
+            test_stark_circuit_constraints::<F, C, _, D>(*stark)
+                .with_context(|| format!("{kind:?}"))
+        });
+        results.0.into_iter().collect()
+    }
+}
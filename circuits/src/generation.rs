@@ -3,6 +3,10 @@
 
 use std::borrow::Borrow;
 use std::fmt::{Debug, Display};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::mem::size_of;
+use std::path::Path;
 
 use itertools::{izip, Itertools};
 use log::debug;
@@ -13,15 +17,18 @@ use plonky2::field::packed::PackedField;
 use plonky2::field::polynomial::PolynomialValues;
 use plonky2::hash::hash_types::RichField;
 use plonky2::util::timing::TimingTree;
-use plonky2::util::transpose;
+use plonky2::util::{log2_strict, transpose};
+use starky::config::StarkConfig;
 use starky::constraint_consumer::ConstraintConsumer;
 use starky::evaluation_frame::StarkEvaluationFrame;
 use starky::stark::Stark;
 
 use crate::bitshift::generation::generate_shift_amount_trace;
-use crate::columns_view::HasNamedColumns;
+use crate::columns_view::{ColumnNames, HasNamedColumns};
 use crate::cpu::generation::{generate_cpu_trace, generate_program_mult_trace};
 use crate::cpu_skeleton::generation::generate_cpu_skeleton_trace;
+use crate::cross_table_lookup::CrossTableLookup;
+use crate::expr::{failing_constraints, FailedConstraint};
 use crate::memory::generation::generate_memory_trace;
 use crate::memory_fullword::generation::generate_fullword_memory_trace;
 use crate::memory_halfword::generation::generate_halfword_memory_trace;
@@ -32,6 +39,7 @@ use crate::poseidon2::generation::generate_poseidon2_trace;
 use crate::poseidon2_output_bytes::generation::generate_poseidon2_output_bytes_trace;
 use crate::poseidon2_sponge::generation::generate_poseidon2_sponge_trace;
 use crate::program::generation::generate_program_rom_trace;
+use crate::public_sub_table::PublicSubTable;
 use crate::rangecheck::generation::generate_rangecheck_trace;
 use crate::rangecheck_u8::generation::generate_rangecheck_u8_trace;
 use crate::register::generation::{generate_register_init_trace, generate_register_trace};
@@ -67,8 +75,13 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
     let skeleton_rows = generate_cpu_skeleton_trace(record);
     let add_rows = ops::add::generate(record);
     let blt_taken_rows = ops::blt_taken::generate(record);
-    let xor_rows = generate_xor_trace(&cpu_rows);
-    let shift_amount_rows = generate_shift_amount_trace(&cpu_rows);
+    let mul_div_rows = ops::mul_div::generate(record);
+    let bitwise_rows = ops::bitwise::generate(record);
+    let branches_rows = ops::branches::generate(record);
+    let jalr_rows = ops::jalr::generate(record);
+    let load_store_rows = ops::load_store::generate(record);
+    let xor_rows = generate_xor_trace(&bitwise_rows, &load_store_rows);
+    let shift_amount_rows = generate_shift_amount_trace(&mul_div_rows);
     let program_rows = generate_program_rom_trace(program);
     let program_mult_rows = generate_program_mult_trace(&skeleton_rows, &program_rows);
 
@@ -113,6 +126,11 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
             &cpu_rows,
             &add_rows,
             &blt_taken_rows,
+            &mul_div_rows,
+            &bitwise_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &poseiden2_sponge_rows,
             &private_tape_rows,
             &public_tape_rows,
@@ -128,7 +146,13 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
         &cpu_rows,
         &add_rows,
         &blt_taken_rows,
+        &mul_div_rows,
+        &branches_rows,
+        &jalr_rows,
+        &load_store_rows,
         &memory_rows,
+        &halfword_memory_rows,
+        &fullword_memory_rows,
         &register_rows,
     );
     // Generate a trace of values containing 0..u8::MAX, with multiplicities to be
@@ -168,6 +192,11 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
         cpu_skeleton_stark: trace_rows_to_poly_values(skeleton_rows),
         add_stark: trace_rows_to_poly_values(add_trace),
         blt_taken_stark: trace_rows_to_poly_values(blt_trace),
+        mul_div_stark: trace_rows_to_poly_values(mul_div_rows),
+        bitwise_stark: trace_rows_to_poly_values(bitwise_rows),
+        branches_stark: trace_rows_to_poly_values(branches_rows),
+        jalr_stark: trace_rows_to_poly_values(jalr_rows),
+        load_store_stark: trace_rows_to_poly_values(load_store_rows),
         tape_commitments_stark: trace_rows_to_poly_values(tape_commitments_rows),
     }
     .build()
@@ -213,7 +242,57 @@ pub fn debug_traces<F: RichField + Extendable<D>, const D: usize>(
     });
 }
 
-pub fn debug_single_trace<
+/// A row of a table on which some of the constraints of its Stark do not hold.
+#[derive(Debug)]
+pub struct ConstraintFailure {
+    pub table: String,
+    pub lv_row: usize,
+    pub nv_row: usize,
+    /// The failing constraints, with their source location.  Empty if the
+    /// Stark does not build its constraints with a `ConstraintBuilder`, or
+    /// without the `debug-constraints` feature.
+    pub constraints: Vec<FailedConstraint>,
+    /// Values of the local row, by column name.
+    pub local_values: Vec<(String, u64)>,
+    /// Values of the next row, by column name.
+    pub next_values: Vec<(String, u64)>,
+}
+
+impl Display for ConstraintFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Constraint failed in {} at lv-row[{}], nv-row[{}]",
+            self.table, self.lv_row, self.nv_row
+        )?;
+        if self.constraints.is_empty() {
+            writeln!(
+                f,
+                "  (constraints not built with ConstraintBuilder, or debug-constraints is off)"
+            )?;
+        }
+        for FailedConstraint {
+            constraint_type,
+            location,
+        } in &self.constraints
+        {
+            writeln!(f, "  {constraint_type:?} constraint at {location}")?;
+        }
+        for (label, values) in [("lv", &self.local_values), ("nv", &self.next_values)] {
+            let values = values
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .join(", ");
+            writeln!(f, "  {label}: {values}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates the constraints of `stark` on every pair of consecutive rows of
+/// `trace_rows`, and reports the rows on which any of them fail.
+#[must_use]
+pub fn check_single_trace<
     F: RichField + Extendable<D> + Debug,
     const D: usize,
     S: Stark<F, D> + Display + HasNamedColumns,
@@ -221,24 +300,359 @@ pub fn debug_single_trace<
     stark: &S,
     trace_rows: &[PolynomialValues<F>],
     public_inputs: &[F],
-) where
-    S::Columns: FromIterator<F> + Debug, {
+) -> Vec<ConstraintFailure>
+where
+    S::Columns: ColumnNames, {
+    let names = stark.column_names();
+    let named = |row: &[F]| {
+        izip!(&names, row)
+            .map(|(name, value)| (name.clone(), value.to_canonical_u64()))
+            .collect_vec()
+    };
     transpose_polys::<F, D, S>(trace_rows.to_vec())
         .iter()
         .enumerate()
         .circular_tuple_windows()
-        .for_each(|((lv_row, lv), (nv_row, nv))| {
-            let mut consumer = ConstraintConsumer::new_debug_api(lv_row == 0, nv_row == 0);
+        .filter_map(|((lv_row, lv), (nv_row, nv))| {
+            let (is_first_row, is_last_row) = (lv_row == 0, nv_row == 0);
+            let mut consumer = ConstraintConsumer::new_debug_api(is_first_row, is_last_row);
             let vars =
                 StarkEvaluationFrame::from_values(lv.as_slice(), nv.as_slice(), public_inputs);
-            stark.eval_packed_generic(&vars, &mut consumer);
-            if consumer.debug_api_has_constraint_failed() {
-                let lv: S::Columns = lv.iter().copied().collect();
-                let nv: S::Columns = nv.iter().copied().collect();
-                log::error!("Debug constraints for {stark}");
-                log::error!("lv-row[{lv_row}] - values: {lv:?}");
-                log::error!("nv-row[{nv_row}] - values: {nv:?}");
-            }
-            assert!(!consumer.debug_api_has_constraint_failed());
+            let constraints = failing_constraints(is_first_row, is_last_row, || {
+                stark.eval_packed_generic(&vars, &mut consumer);
+            });
+            (consumer.debug_api_has_constraint_failed() || !constraints.is_empty()).then(|| {
+                ConstraintFailure {
+                    table: stark.to_string(),
+                    lv_row,
+                    nv_row,
+                    constraints,
+                    local_values: named(lv),
+                    next_values: named(nv),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Panics with a report of the failing constraints, if the constraints of
+/// `stark` do not hold on `trace_rows`.
+pub fn debug_single_trace<
+    F: RichField + Extendable<D> + Debug,
+    const D: usize,
+    S: Stark<F, D> + Display + HasNamedColumns,
+>(
+    stark: &S,
+    trace_rows: &[PolynomialValues<F>],
+    public_inputs: &[F],
+) where
+    S::Columns: ColumnNames, {
+    let failures = check_single_trace::<F, D, S>(stark, trace_rows, public_inputs);
+    for failure in &failures {
+        log::error!("{failure}");
+    }
+    if let Some(failure) = failures.first() {
+        panic!(
+            "Constraints of {stark} failed in {} row(s), the first one being:\n{failure}",
+            failures.len()
+        );
+    }
+}
+
+/// Estimated proving cost of a single table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TableCostEstimate {
+    /// Number of rows, after padding to a power of two.
+    pub num_rows: usize,
+    pub degree_bits: usize,
+    pub num_columns: usize,
+    /// Number of running-product polynomials for cross table lookups and
+    /// public sub tables.
+    pub num_ctl_zs: usize,
+    /// Number of degree `num_rows` chunks of the quotient polynomials.
+    pub num_quotient_chunks: usize,
+    /// Bytes taken up by the trace, CTL and quotient commitments, ie their
+    /// coefficients, low-degree extensions and Merkle trees.
+    pub commitment_bytes: usize,
+}
+
+/// Estimated proving cost of all tables of a [`MozakStark`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostEstimate {
+    pub tables: TableKindArray<TableCostEstimate>,
+    /// Upper bound on the memory the prover needs at once, in bytes.  All
+    /// tables are proven in parallel, so their commitments are all held at the
+    /// same time, together with the traces themselves.
+    pub peak_memory_bytes: usize,
+}
+
+/// Bytes needed by a `PolynomialBatch` committing to `num_polys` polynomials of
+/// `2^degree_bits` values each.
+fn commitment_bytes(num_polys: usize, degree_bits: usize, rate_bits: usize) -> usize {
+    const DIGEST_ELEMENTS: usize = 4;
+    let lde_size = 1 << (degree_bits + rate_bits);
+    // Coefficients, low-degree extension values, and the Merkle tree digests.
+    let elements =
+        num_polys * (1 << degree_bits) + num_polys * lde_size + 2 * lde_size * DIGEST_ELEMENTS;
+    elements * size_of::<u64>()
+}
+
+/// Estimates the cost of proving the execution of `program` in `record`,
+/// before doing any FFTs or hashing.
+///
+/// Only the traces are generated, to learn the size of every table.
+#[must_use]
+pub fn estimate_cost<F: RichField + Extendable<D>, const D: usize>(
+    program: &Program,
+    record: &ExecutionRecord<F>,
+    mozak_stark: &MozakStark<F, D>,
+    config: &StarkConfig,
+) -> CostEstimate {
+    let traces = generate_traces(program, record, &mut TimingTree::default());
+    let rate_bits = config.fri_config.rate_bits;
+    let tables = all_starks!(mozak_stark, |stark, kind| {
+        let num_columns = traces[kind].len();
+        let num_rows = traces[kind][0].len();
+        let degree_bits = log2_strict(num_rows);
+        let num_ctl_zs =
+            CrossTableLookup::num_ctl_zs(
+                &mozak_stark.cross_table_lookups,
+                kind,
+                config.num_challenges,
+            ) + PublicSubTable::num_zs(&mozak_stark.public_sub_tables, kind, config.num_challenges);
+        let num_quotient_chunks = stark.quotient_degree_factor() * config.num_challenges;
+        TableCostEstimate {
+            num_rows,
+            degree_bits,
+            num_columns,
+            num_ctl_zs,
+            num_quotient_chunks,
+            commitment_bytes: [num_columns, num_ctl_zs, num_quotient_chunks]
+                .into_iter()
+                .map(|num_polys| commitment_bytes(num_polys, degree_bits, rate_bits))
+                .sum(),
+        }
+    });
+    let peak_memory_bytes = tables
+        .iter()
+        .map(|table| table.commitment_bytes + table.num_columns * table.num_rows * size_of::<F>())
+        .sum();
+    CostEstimate {
+        tables,
+        peak_memory_bytes,
+    }
+}
+
+/// Writes `trace` as CSV, with a header row of `column_names` and one line of
+/// canonical values per row.
+///
+/// # Errors
+///
+/// Errors if writing to `writer` fails.
+pub fn write_trace_csv<F: RichField>(
+    column_names: &[String],
+    trace: &[PolynomialValues<F>],
+    mut writer: impl Write,
+) -> io::Result<()> {
+    writeln!(writer, "{}", column_names.join(","))?;
+    let columns = trace
+        .iter()
+        .map(|column| column.values.clone())
+        .collect_vec();
+    for row in transpose(&columns) {
+        writeln!(writer, "{}", row.iter().map(F::to_canonical_u64).join(","))?;
+    }
+    writer.flush()
+}
+
+/// Writes the trace of every table into `dir`, as `<TableKind>.csv`.
+///
+/// # Errors
+///
+/// Errors if any of the files cannot be written.
+pub fn export_traces_csv<F: RichField + Extendable<D>, const D: usize>(
+    traces_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
+    mozak_stark: &MozakStark<F, D>,
+    dir: &Path,
+) -> io::Result<()> {
+    all_starks!(mozak_stark, |stark, kind| {
+        let file = BufWriter::new(File::create(dir.join(format!("{kind:?}.csv")))?);
+        write_trace_csv(&stark.column_names(), &traces_poly_values[kind], file)?;
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mozak_runner::code;
+    use mozak_runner::instruction::{Args, Instruction, Op};
+    use plonky2::field::types::{Field, PrimeField64};
+
+    use super::*;
+    use crate::columns_view::column_names;
+    use crate::cross_table_lookup::ctl_utils::check_single_ctl;
+    use crate::cross_table_lookup::LookupError;
+    use crate::ops::add::columns::Add;
+    use crate::stark::mozak_stark::TableKind;
+    use crate::stark::prover::prove;
+    use crate::test_utils::{fast_test_config, undetected_mutations, C, D, F};
+    use crate::utils::from_u32;
+
+    fn add_program() -> (Program, ExecutionRecord<F>) {
+        code::execute(
+            [Instruction {
+                op: Op::ADD,
+                args: Args {
+                    rd: 5,
+                    rs1: 6,
+                    rs2: 7,
+                    ..Args::default()
+                },
+            }],
+            &[],
+            &[(6, 100), (7, 200)],
+        )
+    }
+
+    fn add_traces() -> TableKindArray<Vec<PolynomialValues<F>>> {
+        let (program, record) = add_program();
+        generate_traces::<F, D>(&program, &record, &mut TimingTree::default())
+    }
+
+    #[test]
+    fn diagnostics_name_row_location_and_tuples() {
+        let mut traces = add_traces();
+        let mozak_stark = MozakStark::<F, D>::default();
+        let dst_value = column_names::<Add<F>>()
+            .iter()
+            .position(|name| name == "dst_value")
+            .unwrap();
+        traces[TableKind::Add][dst_value].values[0] += F::ONE;
+
+        let failures =
+            check_single_trace::<F, D, _>(&mozak_stark.add_stark, &traces[TableKind::Add], &[]);
+        assert_eq!(failures.len(), 1);
+        let failure = &failures[0];
+        assert_eq!((failure.lv_row, failure.nv_row), (0, 1));
+        assert_eq!(failure.constraints.len(), 1);
+        assert!(failure.constraints[0]
+            .location
+            .file()
+            .ends_with("add/stark.rs"));
+        assert_eq!(
+            failure.local_values[dst_value],
+            ("dst_value".to_string(), 301)
+        );
+
+        let unmatched = mozak_stark
+            .cross_table_lookups
+            .iter()
+            .filter_map(|ctl| check_single_ctl(&traces, ctl).err())
+            .flat_map(|LookupError::InconsistentTableRows(unmatched)| unmatched)
+            .collect_vec();
+        assert!(unmatched
+            .iter()
+            .any(|tuple| tuple.looking == [(TableKind::Add, 0, 1)] && tuple.looked.is_empty()));
+        assert!(unmatched
+            .iter()
+            .any(|tuple| tuple.looking.is_empty() && !tuple.looked.is_empty()));
+    }
+
+    #[test]
+    fn mutations_of_constrained_cells_are_detected() {
+        let (program, record) = add_program();
+        let undetected = undetected_mutations(&program, &record, |kind| kind == TableKind::Add);
+        assert!(undetected
+            .iter()
+            .all(|mutation| mutation.table == TableKind::Add));
+        for column in ["op1_value", "op2_value", "dst_value"] {
+            assert!(
+                !undetected
+                    .iter()
+                    .any(|mutation| mutation.column.as_deref() == Some(column)),
+                "{column} is constrained"
+            );
+        }
+    }
+
+    #[test]
+    fn commitment_bytes_by_hand() {
+        // 3 polynomials of 16 values, with an LDE of 32 values each, and a
+        // Merkle tree of 2 * 32 digests of 4 elements.
+        assert_eq!(
+            commitment_bytes(3, 4, 1),
+            (3 * 16 + 3 * 32 + 2 * 32 * 4) * 8
+        );
+        assert_eq!(commitment_bytes(3, 4, 1), 3200);
+        assert_eq!(commitment_bytes(1, 0, 0), 80);
+    }
+
+    #[test]
+    fn estimate_matches_proof() {
+        let (program, record) = add_program();
+        let mozak_stark = MozakStark::<F, D>::default();
+        let config = fast_test_config();
+        let estimate = estimate_cost(&program, &record, &mozak_stark, &config);
+        let proof = prove::<F, C, D>(
+            &program,
+            &record,
+            &mozak_stark,
+            &config,
+            PublicInputs {
+                entry_point: from_u32(program.entry_point),
+            },
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+
+        for (table, proof) in izip!(estimate.tables.iter(), proof.proofs.iter()) {
+            assert_eq!(table.degree_bits, proof.recover_degree_bits(&config));
+            assert_eq!(table.num_columns, proof.openings.local_values.len());
+            assert_eq!(table.num_ctl_zs, proof.openings.ctl_zs_last.len());
+            assert_eq!(
+                table.num_quotient_chunks,
+                proof.openings.quotient_polys.len()
+            );
+        }
+
+        // A single `ADD`, padded to the minimal number of rows.  Its 10
+        // columns take part in 6 lookups, 3 of them with the register table,
+        // and the constraints of degree 3 need 2 quotient chunks, each for 2
+        // challenges.
+        assert_eq!(estimate.tables[TableKind::Add], TableCostEstimate {
+            num_rows: MIN_TRACE_LENGTH,
+            degree_bits: 3,
+            num_columns: 10,
+            num_ctl_zs: 6 * 2,
+            num_quotient_chunks: 2 * 2,
+            // With `rate_bits` of 1, every polynomial takes up 8 coefficients
+            // and 16 LDE values, and every Merkle tree 2 * 16 digests of 4
+            // elements.
+            commitment_bytes: ((10 + 12 + 4) * (8 + 16) + 3 * 2 * 16 * 4) * 8,
         });
+    }
+
+    #[test]
+    fn trace_csv_has_named_columns() {
+        let traces = add_traces();
+        let trace = &traces[TableKind::Add];
+        let mut csv = vec![];
+        write_trace_csv(&column_names::<Add<F>>(), trace, &mut csv).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap().split(',').collect_vec();
+        let rows = lines
+            .map(|line| {
+                line.split(',')
+                    .map(|v| v.parse::<u64>().unwrap())
+                    .collect_vec()
+            })
+            .collect_vec();
+        assert_eq!(header[..2], ["inst.pc", "inst.rs1_selected"]);
+        assert_eq!(header.len(), trace.len());
+        assert_eq!(rows.len(), trace[0].len());
+        let op1 = header.iter().position(|&name| name == "op1_value").unwrap();
+        assert_eq!(rows[0][op1], trace[op1].values[0].to_canonical_u64());
+    }
 }
@@ -1,6 +1,7 @@
 use core::ops::Add;
 
 use itertools::izip;
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::hash_types::RichField;
 use plonky2::hash::hashing::PlonkyPermutation;
 use plonky2::hash::poseidon2::Poseidon2Permutation;
@@ -20,7 +21,7 @@ use crate::storage_device::columns::StorageDevice;
 /// Represents a row of the memory trace that is transformed from read-only,
 /// read-write, halfword and fullword memories
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct Memory<T> {
     /// Indicates if a the memory address is writable.
     pub is_writable: T,
@@ -1,16 +1,18 @@
 use core::ops::Add;
 
 use itertools::izip;
+use mozak_circuits_derive::ColumnNames;
 
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
 use crate::cross_table_lookup::ColumnWithTypedInput;
 use crate::linear_combination::Column;
 use crate::memory::columns::MemoryCtl;
+use crate::rangecheck::columns::RangeCheckCtl;
 use crate::stark::mozak_stark::{FullWordMemoryTable, TableWithTypedOutput};
 
 /// Operations (one-hot encoded)
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, ColumnNames)]
 pub struct Ops<T> {
     // One of `is_store`, `is_load`
     // If none are `1`, it is a padding row
@@ -24,7 +26,7 @@ pub struct Ops<T> {
 // value == linear combination via range-check
 // address_limbs also linear combination + forbid  wrapping add
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct FullWordMemory<T> {
     /// Clock at memory access.
     pub clk: T,
@@ -32,6 +34,12 @@ pub struct FullWordMemory<T> {
     /// Memory addresses for the one byte limbs
     pub addrs: [T; 4],
     pub limbs: [T; 4],
+    /// `addrs[0]` divided by four and rounded down.  Range checked, so that
+    /// together with `addr_low_bits` it decomposes `addrs[0]`.
+    pub addr_quotient: T,
+    /// The two lowest bits of `addrs[0]`, which have to be zero for aligned
+    /// accesses.
+    pub addr_low_bits: [T; 2],
 }
 
 columns_view_impl!(FullWordMemory);
@@ -47,10 +55,10 @@ impl<T: Copy + Add<Output = T>> FullWordMemory<T> {
 /// Total number of columns.
 pub const NUM_HW_MEM_COLS: usize = FullWordMemory::<()>::NUMBER_OF_COLUMNS;
 
-/// Columns containing the data which are looked from the CPU table into Memory
-/// stark table.
+/// Columns containing the data which are looked from the LoadStore table into
+/// Memory stark table.
 #[must_use]
-pub fn lookup_for_cpu() -> TableWithTypedOutput<MemoryCtl<Column>> {
+pub fn lookup_for_load_store() -> TableWithTypedOutput<MemoryCtl<Column>> {
     FullWordMemoryTable::new(
         MemoryCtl {
             clk: COL_MAP.clk,
@@ -63,6 +71,15 @@ pub fn lookup_for_cpu() -> TableWithTypedOutput<MemoryCtl<Column>> {
     )
 }
 
+/// Range check for the decomposition of `addrs[0]`.
+#[must_use]
+pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+    vec![FullWordMemoryTable::new(
+        RangeCheckCtl(COL_MAP.addr_quotient),
+        COL_MAP.is_executed(),
+    )]
+}
+
 /// Lookup between fullword memory table
 /// and Memory stark table.
 pub fn lookup_for_memory_limb() -> impl Iterator<Item = TableWithTypedOutput<MemoryCtl<Column>>> {
@@ -60,6 +60,11 @@ pub fn generate_fullword_memory_trace<F: RichField>(
                         is_load: F::from_bool(matches!(op, Op::LW)),
                     },
                     limbs,
+                    addr_quotient: F::from_canonical_u32(base_addr >> 2),
+                    addr_low_bits: [
+                        F::from_canonical_u32(base_addr & 1),
+                        F::from_canonical_u32((base_addr >> 1) & 1),
+                    ],
                 }
             })
             .collect_vec(),
@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 use expr::{Expr, ExprBuilder, StarkFrameTyped};
 use itertools::izip;
 use mozak_circuits_derive::StarkNameDisplay;
+use mozak_runner::state::AlignmentPolicy;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
 use plonky2::hash::hash_types::RichField;
@@ -20,6 +21,9 @@ use crate::unstark::NoColumns;
 #[derive(Copy, Clone, Default, StarkNameDisplay)]
 #[allow(clippy::module_name_repetitions)]
 pub struct FullWordMemoryStark<F, const D: usize> {
+    /// Under [`AlignmentPolicy::Trap`], the constraints rule out misaligned
+    /// accesses, so that no proof exists for an execution the runner rejects.
+    pub alignment_policy: AlignmentPolicy,
     pub _f: PhantomData<F>,
 }
 
@@ -33,6 +37,7 @@ const PUBLIC_INPUTS: usize = 0;
 // Design description - https://docs.google.com/presentation/d/1J0BJd49BMQh3UR5TrOhe3k67plHxnohFtFVrMpDJ1oc/edit?usp=sharing
 fn generate_constraints<'a, T: Copy>(
     vars: &StarkFrameTyped<FullWordMemory<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+    alignment_policy: AlignmentPolicy,
 ) -> ConstraintBuilder<Expr<'a, T>> {
     let lv = vars.local_values;
     let mut constraints = ConstraintBuilder::default();
@@ -49,6 +54,19 @@ fn generate_constraints<'a, T: Copy>(
         constraints.always(lv.is_executed() * (addr - target) * (addr + (1 << 32) - target));
     }
 
+    // Decompose `addrs[0]`.  The quotient is range checked, so this can't
+    // wrap around.
+    for bit in lv.addr_low_bits {
+        constraints.always(bit.is_binary());
+    }
+    let [bit0, bit1] = lv.addr_low_bits;
+    constraints.always(lv.addrs[0] - (lv.addr_quotient * 4 + bit1 * 2 + bit0));
+    if alignment_policy == AlignmentPolicy::Trap {
+        for bit in lv.addr_low_bits {
+            constraints.always(lv.is_executed() * bit);
+        }
+    }
+
     constraints
 }
 
@@ -70,7 +88,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FullWordMemor
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>, {
         let eb = ExprBuilder::default();
-        let constraints = generate_constraints(&eb.to_typed_starkframe(vars));
+        let constraints =
+            generate_constraints(&eb.to_typed_starkframe(vars), self.alignment_policy);
         build_packed(constraints, consumer);
     }
 
@@ -81,7 +100,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FullWordMemor
         consumer: &mut RecursiveConstraintConsumer<F, D>,
     ) {
         let eb = ExprBuilder::default();
-        let constraints = generate_constraints(&eb.to_typed_starkframe(vars));
+        let constraints =
+            generate_constraints(&eb.to_typed_starkframe(vars), self.alignment_policy);
         build_ext(constraints, builder, consumer);
     }
 
@@ -91,19 +111,26 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FullWordMemor
 #[cfg(test)]
 mod tests {
     use mozak_runner::code;
+    use mozak_runner::elf::Program;
     use mozak_runner::instruction::{Args, Instruction, Op};
+    use mozak_runner::state::AlignmentPolicy;
     use mozak_runner::test_utils::{u32_extra, u8_extra};
+    use mozak_runner::vm::ExecutionRecord;
     use plonky2::plonk::config::Poseidon2GoldilocksConfig;
+    use plonky2::util::timing::TimingTree;
     use proptest::prelude::ProptestConfig;
     use proptest::proptest;
     use starky::stark_testing::test_stark_circuit_constraints;
 
+    use crate::generation::{check_single_trace, generate_traces};
     use crate::memory_fullword::stark::FullWordMemoryStark;
-    use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
+    use crate::stark::mozak_stark::{MozakStark, TableKind};
+    use crate::test_utils::{
+        fast_test_config, prove_and_verify_with_mozak_stark, ProveAndVerify, D, F,
+    };
 
-    pub fn prove_mem_read_write<Stark: ProveAndVerify>(offset: u32, imm: u32, content: u8) {
-        let (program, record) = code::execute(
+    fn execute_mem_read_write(offset: u32, imm: u32, content: u8) -> (Program, ExecutionRecord<F>) {
+        code::execute(
             [
                 Instruction {
                     op: Op::SW,
@@ -130,8 +157,11 @@ mod tests {
                 (imm.wrapping_add(offset).wrapping_add(3), 0),
             ],
             &[(1, content.into()), (2, offset)],
-        );
+        )
+    }
 
+    pub fn prove_mem_read_write<Stark: ProveAndVerify>(offset: u32, imm: u32, content: u8) {
+        let (program, record) = execute_mem_read_write(offset, imm, content);
         Stark::prove_and_verify(&program, &record).unwrap();
     }
     proptest! {
@@ -142,6 +172,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn prove_misaligned_mem_read_write_mozak() {
+        for offset in [1, 2, 3, u32::MAX - 1] {
+            prove_mem_read_write::<MozakStark<F, D>>(offset, 0, 0xAB);
+        }
+    }
+
+    #[test]
+    fn prove_aligned_mem_read_write_under_trap() {
+        let stark = MozakStark::default().with_alignment_policy(AlignmentPolicy::Trap);
+        let (program, record) = execute_mem_read_write(4, 0, 0xAB);
+        prove_and_verify_with_mozak_stark(&stark, &program, &record, &fast_test_config()).unwrap();
+    }
+
+    #[test]
+    fn misaligned_mem_read_write_violates_constraints_under_trap() {
+        let stark = MozakStark::<F, D>::default().with_alignment_policy(AlignmentPolicy::Trap);
+        for offset in [1, 2, 3] {
+            let (program, record) = execute_mem_read_write(offset, 0, 0xAB);
+            let traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+            let failures = check_single_trace::<F, D, _>(
+                &stark.fullword_memory_stark,
+                &traces[TableKind::FullWordMemory],
+                &[],
+            );
+            assert!(
+                !failures.is_empty(),
+                "offset {offset} satisfies the constraints"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Quotient has failed")]
+    fn misaligned_mem_read_write_fails_to_prove_under_trap() {
+        let stark = MozakStark::default().with_alignment_policy(AlignmentPolicy::Trap);
+        let (program, record) = execute_mem_read_write(1, 0, 0xAB);
+        let _ = prove_and_verify_with_mozak_stark(&stark, &program, &record, &fast_test_config());
+    }
+
     #[test]
     fn test_circuit() -> anyhow::Result<()> {
         type C = Poseidon2GoldilocksConfig;
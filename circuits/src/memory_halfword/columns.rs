@@ -1,17 +1,19 @@
 use core::ops::Add;
 
 use itertools::izip;
+use mozak_circuits_derive::ColumnNames;
 
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
 use crate::cross_table_lookup::ColumnWithTypedInput;
 use crate::linear_combination::Column;
 use crate::memory::columns::MemoryCtl;
+use crate::rangecheck::columns::RangeCheckCtl;
 use crate::stark::mozak_stark::{HalfWordMemoryTable, TableWithTypedOutput};
 // use crate::stark::mozak_stark::{HalfWordMemoryTable, Table};
 
 /// Operations (one-hot encoded)
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, ColumnNames)]
 pub struct Ops<T> {
     // One of `is_store`, `is_load_u`
     // If none are `1`, it is a padding row
@@ -27,7 +29,7 @@ columns_view_impl!(HalfWordMemory);
 // value == linear combination via range-check
 // address_limbs also linear combination + forbid  wrapping add
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct HalfWordMemory<T> {
     /// Clock at memory access.
     pub clk: T,
@@ -35,6 +37,12 @@ pub struct HalfWordMemory<T> {
     /// Memory addresses for the one byte limbs
     pub addrs: [T; 2],
     pub limbs: [T; 2],
+    /// `addrs[0]` halved and rounded down.  Range checked, so that together
+    /// with `addr_low_bit` it decomposes `addrs[0]`.
+    pub addr_quotient: T,
+    /// The lowest bit of `addrs[0]`, which has to be zero for aligned
+    /// accesses.
+    pub addr_low_bit: T,
 }
 
 impl<T: Copy + Add<Output = T>> HalfWordMemory<T> {
@@ -47,9 +55,9 @@ impl<T: Copy + Add<Output = T>> HalfWordMemory<T> {
 /// Total number of columns.
 pub const NUM_HW_MEM_COLS: usize = HalfWordMemory::<()>::NUMBER_OF_COLUMNS;
 
-/// Lookup from CPU table into halfword memory table.
+/// Lookup from LoadStore table into halfword memory table.
 #[must_use]
-pub fn lookup_for_cpu() -> TableWithTypedOutput<MemoryCtl<Column>> {
+pub fn lookup_for_load_store() -> TableWithTypedOutput<MemoryCtl<Column>> {
     HalfWordMemoryTable::new(
         MemoryCtl {
             clk: COL_MAP.clk,
@@ -62,6 +70,15 @@ pub fn lookup_for_cpu() -> TableWithTypedOutput<MemoryCtl<Column>> {
     )
 }
 
+/// Range check for the decomposition of `addrs[0]`.
+#[must_use]
+pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+    vec![HalfWordMemoryTable::new(
+        RangeCheckCtl(COL_MAP.addr_quotient),
+        COL_MAP.is_executed(),
+    )]
+}
+
 /// Lookup into Memory stark table.
 pub fn lookup_for_memory_limb() -> impl Iterator<Item = TableWithTypedOutput<MemoryCtl<Column>>> {
     izip!(COL_MAP.limbs, COL_MAP.addrs).map(|(value, addr)| {
@@ -54,6 +54,8 @@ pub fn generate_halfword_memory_trace<F: RichField>(
                         F::from_canonical_u32(s.aux.dst_val & 0xFF),
                         F::from_canonical_u32((s.aux.dst_val >> 8) & 0xFF),
                     ],
+                    addr_quotient: F::from_canonical_u32(mem_addr0 >> 1),
+                    addr_low_bit: F::from_canonical_u32(mem_addr0 & 1),
                 }
             })
             .collect_vec(),
@@ -1,6 +1,6 @@
 //! This module contains the **`Halfword-Memory` STARK Table**.
 //! This Stark is used to store the VM Memory and
-//! constrains the load and store operations by the CPU
+//! constrains the load and store operations of the `LoadStore` table
 //! using the CTL (cross table lookup) technique.
 
 pub mod columns;
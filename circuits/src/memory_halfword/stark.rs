@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 
 use expr::{Expr, ExprBuilder, StarkFrameTyped};
 use mozak_circuits_derive::StarkNameDisplay;
+use mozak_runner::state::AlignmentPolicy;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
 use plonky2::hash::hash_types::RichField;
@@ -19,6 +20,9 @@ use crate::unstark::NoColumns;
 #[derive(Copy, Clone, Default, StarkNameDisplay)]
 #[allow(clippy::module_name_repetitions)]
 pub struct HalfWordMemoryStark<F, const D: usize> {
+    /// Under [`AlignmentPolicy::Trap`], the constraints rule out misaligned
+    /// accesses, so that no proof exists for an execution the runner rejects.
+    pub alignment_policy: AlignmentPolicy,
     pub _f: PhantomData<F>,
 }
 
@@ -29,6 +33,7 @@ impl<F, const D: usize> HasNamedColumns for HalfWordMemoryStark<F, D> {
 // Design description - https://docs.google.com/presentation/d/1J0BJd49BMQh3UR5TrOhe3k67plHxnohFtFVrMpDJ1oc/edit?usp=sharing
 fn generate_constraints<'a, T: Copy>(
     vars: &StarkFrameTyped<HalfWordMemory<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+    alignment_policy: AlignmentPolicy,
 ) -> ConstraintBuilder<Expr<'a, T>> {
     let lv = vars.local_values;
     let mut constraints = ConstraintBuilder::default();
@@ -45,6 +50,14 @@ fn generate_constraints<'a, T: Copy>(
     // even for a malicious prover.
     constraints.always(lv.is_executed() * (lv.addrs[1] - added) * (lv.addrs[1] - wrapped));
 
+    // Decompose `addrs[0]`.  The quotient is range checked, so this can't
+    // wrap around.
+    constraints.always(lv.addr_low_bit.is_binary());
+    constraints.always(lv.addrs[0] - (lv.addr_quotient * 2 + lv.addr_low_bit));
+    if alignment_policy == AlignmentPolicy::Trap {
+        constraints.always(lv.is_executed() * lv.addr_low_bit);
+    }
+
     constraints
 }
 
@@ -67,7 +80,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for HalfWordMemor
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>, {
         let eb = ExprBuilder::default();
-        let constraints = generate_constraints(&eb.to_typed_starkframe(vars));
+        let constraints =
+            generate_constraints(&eb.to_typed_starkframe(vars), self.alignment_policy);
         build_packed(constraints, consumer);
     }
 
@@ -78,7 +92,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for HalfWordMemor
         consumer: &mut RecursiveConstraintConsumer<F, D>,
     ) {
         let eb = ExprBuilder::default();
-        let constraints = generate_constraints(&eb.to_typed_starkframe(vars));
+        let constraints =
+            generate_constraints(&eb.to_typed_starkframe(vars), self.alignment_policy);
         build_ext(constraints, builder, consumer);
     }
 
@@ -88,24 +103,31 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for HalfWordMemor
 #[cfg(test)]
 mod tests {
     use mozak_runner::code;
+    use mozak_runner::elf::Program;
     use mozak_runner::instruction::{Args, Instruction, Op};
+    use mozak_runner::state::AlignmentPolicy;
     use mozak_runner::test_utils::{u32_extra, u8_extra};
+    use mozak_runner::vm::ExecutionRecord;
     use plonky2::plonk::config::Poseidon2GoldilocksConfig;
+    use plonky2::util::timing::TimingTree;
     use proptest::prelude::ProptestConfig;
     use proptest::proptest;
     use starky::stark_testing::test_stark_circuit_constraints;
 
-    use crate::memory_halfword::stark::HalfWordMemoryStark;
     // use crate::cpu::stark::CpuStark;
-    use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
-    pub fn prove_mem_read_write<Stark: ProveAndVerify>(
+    use crate::generation::{check_single_trace, generate_traces};
+    use crate::memory_halfword::stark::HalfWordMemoryStark;
+    use crate::stark::mozak_stark::{MozakStark, TableKind};
+    use crate::test_utils::{
+        fast_test_config, prove_and_verify_with_mozak_stark, ProveAndVerify, D, F,
+    };
+    fn execute_mem_read_write(
         offset: u32,
         imm: u32,
         content: u8,
         is_unsigned: bool,
-    ) {
-        let (program, record) = code::execute(
+    ) -> (Program, ExecutionRecord<F>) {
+        code::execute(
             [
                 Instruction {
                     op: Op::SH,
@@ -132,8 +154,16 @@ mod tests {
                 (imm.wrapping_add(offset).wrapping_add(3), 0),
             ],
             &[(1, content.into()), (2, offset)],
-        );
+        )
+    }
 
+    pub fn prove_mem_read_write<Stark: ProveAndVerify>(
+        offset: u32,
+        imm: u32,
+        content: u8,
+        is_unsigned: bool,
+    ) {
+        let (program, record) = execute_mem_read_write(offset, imm, content, is_unsigned);
         Stark::prove_and_verify(&program, &record).unwrap();
     }
     proptest! {
@@ -144,6 +174,46 @@ mod tests {
             prove_mem_read_write::<MozakStark<F, D>>(offset, imm, content, is_unsigned);
         }
     }
+
+    #[test]
+    fn prove_misaligned_mem_read_write_mozak() {
+        for offset in [1, u32::MAX] {
+            prove_mem_read_write::<MozakStark<F, D>>(offset, 0, 0xAB, false);
+        }
+    }
+    #[test]
+    fn prove_aligned_mem_read_write_under_trap() {
+        let stark = MozakStark::default().with_alignment_policy(AlignmentPolicy::Trap);
+        let (program, record) = execute_mem_read_write(4, 0, 0xAB, false);
+        prove_and_verify_with_mozak_stark(&stark, &program, &record, &fast_test_config()).unwrap();
+    }
+
+    #[test]
+    fn misaligned_mem_read_write_violates_constraints_under_trap() {
+        let stark = MozakStark::<F, D>::default().with_alignment_policy(AlignmentPolicy::Trap);
+        for offset in [1, 3] {
+            let (program, record) = execute_mem_read_write(offset, 0, 0xAB, false);
+            let traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+            let failures = check_single_trace::<F, D, _>(
+                &stark.halfword_memory_stark,
+                &traces[TableKind::HalfWordMemory],
+                &[],
+            );
+            assert!(
+                !failures.is_empty(),
+                "offset {offset} satisfies the constraints"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Quotient has failed")]
+    fn misaligned_mem_read_write_fails_to_prove_under_trap() {
+        let stark = MozakStark::default().with_alignment_policy(AlignmentPolicy::Trap);
+        let (program, record) = execute_mem_read_write(1, 0, 0xAB, false);
+        let _ = prove_and_verify_with_mozak_stark(&stark, &program, &record, &fast_test_config());
+    }
+
     #[test]
     fn test_circuit() -> anyhow::Result<()> {
         type C = Poseidon2GoldilocksConfig;
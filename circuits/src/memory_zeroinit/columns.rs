@@ -1,3 +1,5 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
 use crate::cross_table_lookup::ColumnWithTypedInput;
 use crate::linear_combination::Column;
@@ -7,7 +9,7 @@ use crate::stark::mozak_stark::{MemoryZeroInitTable, TableWithTypedOutput};
 columns_view_impl!(MemoryZeroInit);
 make_col_map!(MemoryZeroInit);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct MemoryZeroInit<T> {
     pub addr: T,
     pub filter: T,
@@ -1,3 +1,4 @@
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::hash_types::RichField;
 
 use crate::columns_view::{columns_view_impl, make_col_map};
@@ -9,7 +10,7 @@ columns_view_impl!(MemoryInit);
 make_col_map!(MemoryInit);
 /// A Row of Memory generated from both read-only and read-write memory
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct MemoryInit<T> {
     pub address: T,
     pub value: T,
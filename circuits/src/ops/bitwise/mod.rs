@@ -0,0 +1,215 @@
+//! Bitwise operations AND, OR and XOR, proven in their own table instead of
+//! the CPU.
+
+pub mod stark;
+
+pub mod columns {
+    use core::iter::Sum;
+    use core::ops::Add;
+
+    use mozak_circuits_derive::ColumnNames;
+
+    use crate::columns_view::{columns_view_impl, make_col_map};
+    use crate::cpu::columns::OpSelectors;
+    use crate::cpu_skeleton::columns::CpuSkeletonCtl;
+    use crate::linear_combination::Column;
+    use crate::linear_combination_typed::ColumnWithTypedInput;
+    use crate::program::columns::ProgramRom;
+    use crate::register::RegisterCtl;
+    use crate::stark::mozak_stark::{BitwiseTable, TableWithTypedOutput};
+    use crate::xor::columns::XorView;
+
+    columns_view_impl!(Ops);
+    /// Selectors for which instruction is currently active.
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Ops<T> {
+        pub xor: T,
+        pub or: T,
+        pub and: T,
+    }
+
+    columns_view_impl!(Instruction);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Instruction<T> {
+        /// The original instruction (+ `imm_value`) used for program
+        /// cross-table-lookup.
+        pub pc: T,
+        /// Selects the current operation type
+        pub ops: Ops<T>,
+        /// Selects the register to use as source for `rs1`
+        pub rs1_selected: T,
+        /// Selects the register to use as source for `rs2`
+        pub rs2_selected: T,
+        /// Selects the register to use as destination for `rd`
+        pub rd_selected: T,
+        /// Special immediate value used for code constants
+        pub imm_value: T,
+    }
+
+    make_col_map!(Bitwise);
+    columns_view_impl!(Bitwise);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Bitwise<T> {
+        pub inst: Instruction<T>,
+        pub clk: T,
+
+        pub op1_value: T,
+        pub op2_value_raw: T,
+        /// The sum of the value of the second operand register and the
+        /// immediate value. Wrapped around to fit in a `u32`.
+        pub op2_value: T,
+        pub dst_value: T,
+
+        /// Linked values with the Xor Stark Table
+        pub xor: XorView<T>,
+    }
+
+    const BITWISE: Bitwise<ColumnWithTypedInput<Bitwise<i64>>> = COL_MAP;
+
+    impl<T: Copy + Add<Output = T> + Sum> Bitwise<T> {
+        pub fn is_running(&self) -> T { self.inst.ops.into_iter().sum() }
+    }
+
+    /// Lookup into the Xor stark.
+    #[must_use]
+    pub fn lookup_for_xor() -> TableWithTypedOutput<XorView<Column>> {
+        BitwiseTable::new(BITWISE.xor, BITWISE.is_running())
+    }
+
+    #[must_use]
+    pub fn register_looking() -> Vec<TableWithTypedOutput<RegisterCtl<Column>>> {
+        let is_read = ColumnWithTypedInput::constant(1);
+        let is_write = ColumnWithTypedInput::constant(2);
+
+        vec![
+            BitwiseTable::new(
+                RegisterCtl {
+                    clk: BITWISE.clk,
+                    op: is_read,
+                    addr: BITWISE.inst.rs1_selected,
+                    value: BITWISE.op1_value,
+                },
+                BITWISE.is_running(),
+            ),
+            BitwiseTable::new(
+                RegisterCtl {
+                    clk: BITWISE.clk,
+                    op: is_read,
+                    addr: BITWISE.inst.rs2_selected,
+                    value: BITWISE.op2_value_raw,
+                },
+                BITWISE.is_running(),
+            ),
+            BitwiseTable::new(
+                RegisterCtl {
+                    clk: BITWISE.clk,
+                    op: is_write,
+                    addr: BITWISE.inst.rd_selected,
+                    value: BITWISE.dst_value,
+                },
+                BITWISE.is_running(),
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn lookup_for_skeleton() -> TableWithTypedOutput<CpuSkeletonCtl<Column>> {
+        BitwiseTable::new(
+            CpuSkeletonCtl {
+                clk: BITWISE.clk,
+                pc: BITWISE.inst.pc,
+                new_pc: BITWISE.inst.pc + 4,
+                will_halt: ColumnWithTypedInput::constant(0),
+            },
+            BITWISE.is_running(),
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
+        let inst = BITWISE.inst;
+        // Our opcodes have to agree with the ones the CPU uses for the same
+        // instructions.
+        let opcode = ColumnWithTypedInput::ascending_sum(OpSelectors {
+            xor: inst.ops.xor,
+            or: inst.ops.or,
+            and: inst.ops.and,
+            ..Default::default()
+        });
+        BitwiseTable::new(
+            ProgramRom {
+                pc: inst.pc,
+                // See `cpu::columns::lookup_for_program_rom` for the layout.
+                inst_data: ColumnWithTypedInput::reduce_with_powers(
+                    [
+                        opcode,
+                        ColumnWithTypedInput::constant(0),
+                        ColumnWithTypedInput::constant(0),
+                        inst.rs1_selected,
+                        inst.rs2_selected,
+                        inst.rd_selected,
+                        inst.imm_value,
+                    ],
+                    1 << 5,
+                ),
+            },
+            BITWISE.is_running(),
+        )
+    }
+}
+
+use columns::{Bitwise, Instruction, Ops};
+use mozak_runner::instruction::Op;
+use mozak_runner::vm::{ExecutionRecord, Row};
+use plonky2::hash::hash_types::RichField;
+
+use crate::cpu::columns as cpu_cols;
+use crate::utils::{from_u32, pad_trace_with_default};
+use crate::xor::columns::XorView;
+
+#[must_use]
+pub fn generate<F: RichField>(record: &ExecutionRecord<F>) -> Vec<Bitwise<F>> {
+    let mut trace: Vec<Bitwise<F>> = vec![];
+    for Row {
+        state,
+        instruction: inst,
+        aux,
+    } in &record.executed
+    {
+        if !matches!(inst.op, Op::XOR | Op::OR | Op::AND) {
+            continue;
+        }
+        // We decode just like the CPU does, so that we agree on the program ROM.
+        let cpu_inst = cpu_cols::Instruction::from((state.get_pc(), *inst));
+        let row = Bitwise {
+            inst: Instruction {
+                pc: cpu_inst.pc,
+                ops: Ops {
+                    xor: cpu_inst.ops.xor,
+                    or: cpu_inst.ops.or,
+                    and: cpu_inst.ops.and,
+                },
+                rs1_selected: cpu_inst.rs1_selected,
+                rs2_selected: cpu_inst.rs2_selected,
+                rd_selected: cpu_inst.rd_selected,
+                imm_value: cpu_inst.imm_value,
+            },
+            clk: u32::try_from(state.clk).unwrap(),
+            op1_value: aux.op1,
+            op2_value_raw: aux.op2_raw,
+            op2_value: aux.op2,
+            dst_value: aux.dst_val,
+            xor: XorView {
+                a: aux.op1,
+                b: aux.op2,
+                out: aux.op1 ^ aux.op2,
+            },
+        }
+        .map(from_u32);
+        trace.push(row);
+    }
+    pad_trace_with_default(trace)
+}
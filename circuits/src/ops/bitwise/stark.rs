@@ -24,12 +24,38 @@
 //!  2 * (x | y) := (x + y + (x ^ y))
 //! `
 
-use expr::Expr;
+use std::marker::PhantomData;
 
-use super::columns::CpuState;
-use crate::expr::ConstraintBuilder;
+use expr::{Expr, ExprBuilder, StarkFrameTyped};
+use mozak_circuits_derive::StarkNameDisplay;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::StarkFrame;
+use starky::stark::Stark;
+
+use super::columns::Bitwise;
+use crate::columns_view::{HasNamedColumns, NumberOfColumns};
+use crate::expr::{build_ext, build_packed, ConstraintBuilder};
+use crate::unstark::NoColumns;
 use crate::xor::columns::XorView;
 
+#[derive(Copy, Clone, Default, StarkNameDisplay)]
+#[allow(clippy::module_name_repetitions)]
+pub struct BitwiseStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F, const D: usize> HasNamedColumns for BitwiseStark<F, D> {
+    type Columns = Bitwise<F>;
+}
+
+const COLUMNS: usize = Bitwise::<()>::NUMBER_OF_COLUMNS;
+const PUBLIC_INPUTS: usize = 0;
+
 /// A struct to represent the output of binary operations
 ///
 /// Implemented for AND, OR and XOR instructions.
@@ -86,14 +112,27 @@ pub(crate) fn xor_gadget<'a, P: Copy>(xor: &XorView<Expr<'a, P>>) -> BinaryOp<Ex
 
 /// Constraints for the AND, OR and XOR opcodes.
 /// As each opcode has an associated selector, we use selectors to enable only
-/// the correct opcode constraints. It can be that all selectors are not active,
-/// representing that the operation is neither AND, nor OR or XOR.
+/// the correct opcode constraints. All selectors are inactive on padding rows.
 /// The operation constraints are maintained in the corresponding gadget, and we
 /// just need to make sure the gadget gets assigned correct inputs and output.
-pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
-    cb: &mut ConstraintBuilder<Expr<'a, P>>,
-) {
+fn generate_constraints<'a, T: Copy>(
+    vars: &StarkFrameTyped<Bitwise<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+) -> ConstraintBuilder<Expr<'a, T>> {
+    let lv = &vars.local_values;
+    let mut cb = ConstraintBuilder::default();
+
+    lv.inst
+        .ops
+        .into_iter()
+        .for_each(|s| cb.always(s.is_binary()));
+    cb.always(lv.is_running().is_binary());
+
+    // Check: op2 is the wrapped sum of the operand register and the immediate.
+    // The Xor lookup only admits `u32` values, so this makes the choice
+    // deterministic.
+    let op2_sum = lv.op2_value_raw + lv.inst.imm_value;
+    cb.always((lv.op2_value - op2_sum) * (lv.op2_value - (op2_sum - (1 << 32))));
+
     let op1 = lv.op1_value;
     let op2 = lv.op2_value;
     let dst = lv.dst_value;
@@ -107,20 +146,68 @@ pub(crate) fn constraints<'a, P: Copy>(
         cb.always(selector * (gadget.input_b - op2));
         cb.always(selector * (gadget.doubled_output - 2 * dst));
     }
+
+    cb
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for BitwiseStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        constraint_consumer: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>, {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_packed(constraints, constraint_consumer);
+    }
+
+    fn constraint_degree(&self) -> usize { 3 }
+
+    fn eval_ext_circuit(
+        &self,
+        circuit_builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        constraint_consumer: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_ext(constraints, circuit_builder, constraint_consumer);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use mozak_runner::test_utils::u32_extra;
     use proptest::prelude::{any, ProptestConfig};
     use proptest::proptest;
+    use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
 
+    use super::BitwiseStark;
     use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
+    use crate::test_utils::{ProveAndVerify, C, D, F};
     use crate::xor::stark::XorStark;
 
+    type S = BitwiseStark<F, D>;
+
+    #[test]
+    fn test_degree() -> Result<()> { test_stark_low_degree(S::default()) }
+
+    #[test]
+    fn test_circuit() -> Result<()> { test_stark_circuit_constraints::<F, C, S, D>(S::default()) }
+
     fn prove_bitwise<Stark: ProveAndVerify>(a: u32, b: u32, imm: u32, use_imm: bool) {
         let (b, imm) = if use_imm { (0, imm) } else { (b, 0) };
         let code: Vec<_> = [Op::AND, Op::OR, Op::XOR]
@@ -151,6 +238,16 @@ mod tests {
         {
            prove_bitwise::<XorStark<F, D>>(a, b, imm, use_imm);
         }
+
+        #[test]
+        fn prove_bitwise_table(
+            a in u32_extra(),
+            b in u32_extra(),
+            imm in u32_extra(),
+            use_imm in any::<bool>())
+        {
+           prove_bitwise::<BitwiseStark<F, D>>(a, b, imm, use_imm);
+        }
     }
 
     proptest! {
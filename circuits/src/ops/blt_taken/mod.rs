@@ -2,6 +2,8 @@ pub mod stark;
 
 pub mod columns {
 
+    use mozak_circuits_derive::ColumnNames;
+
     use crate::columns_view::{columns_view_impl, make_col_map};
     use crate::cpu_skeleton::columns::CpuSkeletonCtl;
     use crate::linear_combination::Column;
@@ -13,7 +15,7 @@ pub mod columns {
 
     columns_view_impl!(Instruction);
     #[repr(C)]
-    #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
     pub struct Instruction<T> {
         /// The original instruction (+ `imm_value`) used for program
         /// cross-table-lookup.
@@ -29,7 +31,7 @@ pub mod columns {
     make_col_map!(BltTaken);
     columns_view_impl!(BltTaken);
     #[repr(C)]
-    #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
     pub struct BltTaken<T> {
         pub inst: Instruction<T>,
         // TODO(Matthias): could we get rid of the clk here?
@@ -0,0 +1,280 @@
+//! Conditional branches BEQ, BNE, BLT(U) and BGE(U), proven in their own table
+//! instead of the CPU.
+//!
+//! A taken BLTU is proven by `ops::blt_taken` instead.
+
+pub mod stark;
+
+pub mod columns {
+    use core::iter::Sum;
+    use core::ops::{Add, Mul, Sub};
+
+    use mozak_circuits_derive::ColumnNames;
+
+    use crate::columns_view::{columns_view_impl, make_col_map};
+    use crate::cpu::columns::OpSelectors;
+    use crate::cpu_skeleton::columns::CpuSkeletonCtl;
+    use crate::linear_combination::Column;
+    use crate::linear_combination_typed::ColumnWithTypedInput;
+    use crate::program::columns::ProgramRom;
+    use crate::rangecheck::columns::RangeCheckCtl;
+    use crate::register::RegisterCtl;
+    use crate::stark::mozak_stark::{BranchesTable, TableWithTypedOutput};
+
+    columns_view_impl!(Ops);
+    /// Selectors for which instruction is currently active.
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Ops<T> {
+        /// Branch on Equal
+        pub beq: T,
+        /// Branch on Not Equal
+        pub bne: T,
+        /// Branch Less Than
+        pub blt: T,
+        /// Branch Greater or Equal
+        pub bge: T,
+    }
+
+    columns_view_impl!(Instruction);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Instruction<T> {
+        /// The original instruction (+ `imm_value`) used for program
+        /// cross-table-lookup.
+        pub pc: T,
+        /// Selects the current operation type
+        pub ops: Ops<T>,
+        pub is_op1_signed: T,
+        pub is_op2_signed: T,
+        /// Selects the register to use as source for `rs1`
+        pub rs1_selected: T,
+        /// Selects the register to use as source for `rs2`
+        pub rs2_selected: T,
+        /// The branch target
+        pub imm_value: T,
+    }
+
+    make_col_map!(Branches);
+    columns_view_impl!(Branches);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Branches<T> {
+        pub inst: Instruction<T>,
+        pub clk: T,
+        pub new_pc: T,
+
+        pub op1_value: T,
+        pub op2_value: T,
+
+        // 0 means non-negative, 1 means negative.
+        // (If number is unsigned, it is non-negative.)
+        pub op1_sign_bit: T,
+        pub op2_sign_bit: T,
+
+        /// `|op1 - op2|`
+        pub abs_diff: T,
+        /// `1/|op1 - op2| `
+        /// It exists only if `op1 != op2`, otherwise assigned to 0.
+        pub cmp_diff_inv: T,
+        /// If `op1` < `op2`
+        pub less_than: T,
+        /// `normalised_diff` == 0 iff op1 == op2
+        /// `normalised_diff` == 1 iff op1 != op2
+        /// We need this intermediate variable to keep the constraint degree <=
+        /// 3.
+        pub normalised_diff: T,
+    }
+
+    const BRANCHES: Branches<ColumnWithTypedInput<Branches<i64>>> = COL_MAP;
+
+    impl<T> Branches<T>
+    where
+        T: Copy + Add<Output = T> + Mul<i64, Output = T> + Sub<Output = T> + Sum,
+    {
+        pub fn is_running(&self) -> T { self.inst.ops.into_iter().sum() }
+
+        /// Value of the first operand, as if converted to i64.
+        ///
+        /// So range is `i32::MIN..=u32::MAX` in Prime Field.
+        pub fn op1_full_range(&self) -> T { self.op1_value - self.op1_sign_bit * (1 << 32) }
+
+        /// Value of the second operand, as if converted to i64.
+        ///
+        /// So range is `i32::MIN..=u32::MAX` in Prime Field.
+        pub fn op2_full_range(&self) -> T { self.op2_value - self.op2_sign_bit * (1 << 32) }
+
+        /// Difference between first and second operands, which works for both
+        /// pairs of signed or pairs of unsigned values.
+        pub fn signed_diff(&self) -> T { self.op1_full_range() - self.op2_full_range() }
+    }
+
+    #[must_use]
+    pub fn register_looking() -> Vec<TableWithTypedOutput<RegisterCtl<Column>>> {
+        let is_read = ColumnWithTypedInput::constant(1);
+
+        vec![
+            BranchesTable::new(
+                RegisterCtl {
+                    clk: BRANCHES.clk,
+                    op: is_read,
+                    addr: BRANCHES.inst.rs1_selected,
+                    value: BRANCHES.op1_value,
+                },
+                BRANCHES.is_running(),
+            ),
+            BranchesTable::new(
+                RegisterCtl {
+                    clk: BRANCHES.clk,
+                    op: is_read,
+                    addr: BRANCHES.inst.rs2_selected,
+                    value: BRANCHES.op2_value,
+                },
+                BRANCHES.is_running(),
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+        let ops = BRANCHES.inst.ops;
+
+        [
+            (BRANCHES.abs_diff, ops.blt + ops.bge),
+            // apply range constraints for the sign bits of each operand
+            (
+                BRANCHES.op1_value - BRANCHES.op1_sign_bit * (1 << 32)
+                    + BRANCHES.inst.is_op1_signed * (1 << 31),
+                BRANCHES.inst.is_op1_signed,
+            ),
+            (
+                BRANCHES.op2_value - BRANCHES.op2_sign_bit * (1 << 32)
+                    + BRANCHES.inst.is_op2_signed * (1 << 31),
+                BRANCHES.inst.is_op2_signed,
+            ),
+        ]
+        .into_iter()
+        .map(|(columns, filter)| BranchesTable::new(RangeCheckCtl(columns), filter))
+        .collect()
+    }
+
+    #[must_use]
+    pub fn lookup_for_skeleton() -> TableWithTypedOutput<CpuSkeletonCtl<Column>> {
+        BranchesTable::new(
+            CpuSkeletonCtl {
+                clk: BRANCHES.clk,
+                pc: BRANCHES.inst.pc,
+                new_pc: BRANCHES.new_pc,
+                will_halt: ColumnWithTypedInput::constant(0),
+            },
+            BRANCHES.is_running(),
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
+        let inst = BRANCHES.inst;
+        // Our opcodes have to agree with the ones the CPU uses for the same
+        // instructions.
+        let opcode = ColumnWithTypedInput::ascending_sum(OpSelectors {
+            beq: inst.ops.beq,
+            bne: inst.ops.bne,
+            blt: inst.ops.blt,
+            bge: inst.ops.bge,
+            ..Default::default()
+        });
+        BranchesTable::new(
+            ProgramRom {
+                pc: inst.pc,
+                // See `cpu::columns::lookup_for_program_rom` for the layout.
+                inst_data: ColumnWithTypedInput::reduce_with_powers(
+                    [
+                        opcode,
+                        inst.is_op1_signed,
+                        inst.is_op2_signed,
+                        inst.rs1_selected,
+                        inst.rs2_selected,
+                        ColumnWithTypedInput::constant(0),
+                        inst.imm_value,
+                    ],
+                    1 << 5,
+                ),
+            },
+            BRANCHES.is_running(),
+        )
+    }
+}
+
+use columns::{Branches, Instruction, Ops};
+use expr::{Evaluator, ExprBuilder};
+use mozak_runner::instruction::Op;
+use mozak_runner::vm::{ExecutionRecord, Row};
+use plonky2::hash::hash_types::RichField;
+
+use crate::cpu::columns as cpu_cols;
+use crate::expr::PureEvaluator;
+use crate::utils::{from_u32, pad_trace_with_default, sign_extend};
+
+#[must_use]
+pub fn generate<F: RichField>(record: &ExecutionRecord<F>) -> Vec<Branches<F>> {
+    let mut trace: Vec<Branches<F>> = vec![];
+    for Row {
+        state,
+        instruction: inst,
+        aux,
+    } in &record.executed
+    {
+        if !matches!(
+            inst.op,
+            Op::BEQ | Op::BNE | Op::BLT | Op::BLTU | Op::BGE | Op::BGEU
+        ) {
+            continue;
+        }
+        // A taken BLTU is handled by `ops::blt_taken`.
+        if aux.op1 < aux.op2 && Op::BLTU == inst.op {
+            continue;
+        }
+        // We decode just like the CPU does, so that we agree on the program ROM.
+        let cpu_inst = cpu_cols::Instruction::from((state.get_pc(), *inst));
+        let op1_full_range = sign_extend(cpu_inst.is_op1_signed != 0, aux.op1);
+        let op2_full_range = sign_extend(cpu_inst.is_op2_signed != 0, aux.op2);
+        let mut row = Branches {
+            inst: Instruction {
+                pc: from_u32(cpu_inst.pc),
+                ops: Ops {
+                    beq: cpu_inst.ops.beq,
+                    bne: cpu_inst.ops.bne,
+                    blt: cpu_inst.ops.blt,
+                    bge: cpu_inst.ops.bge,
+                }
+                .map(from_u32),
+                is_op1_signed: from_u32(cpu_inst.is_op1_signed),
+                is_op2_signed: from_u32(cpu_inst.is_op2_signed),
+                rs1_selected: from_u32(cpu_inst.rs1_selected),
+                rs2_selected: from_u32(cpu_inst.rs2_selected),
+                imm_value: from_u32(cpu_inst.imm_value),
+            },
+            clk: F::from_noncanonical_u64(state.clk),
+            new_pc: from_u32(aux.new_pc),
+            op1_value: from_u32(aux.op1),
+            op2_value: from_u32(aux.op2),
+            op1_sign_bit: F::from_bool(op1_full_range < 0),
+            op2_sign_bit: F::from_bool(op2_full_range < 0),
+            abs_diff: F::from_noncanonical_u64(op1_full_range.abs_diff(op2_full_range)),
+            less_than: F::from_bool(op1_full_range < op2_full_range),
+            ..Default::default()
+        };
+        let signed_diff = signed_diff(&row);
+        row.cmp_diff_inv = signed_diff.try_inverse().unwrap_or_default();
+        row.normalised_diff = F::from_bool(signed_diff.is_nonzero());
+        trace.push(row);
+    }
+    pad_trace_with_default(trace)
+}
+
+/// This is a wrapper to make the Expr mechanics work directly with a Field.
+fn signed_diff<F: RichField>(row: &Branches<F>) -> F {
+    let expr_builder = ExprBuilder::default();
+    let row = row.map(|x| expr_builder.lit(x));
+    PureEvaluator(F::from_noncanonical_i64).eval(row.signed_diff())
+}
@@ -1,9 +1,62 @@
 //! This module implements constraints for the branch operations.
 
-use expr::Expr;
+use std::marker::PhantomData;
 
-use super::columns::CpuState;
-use crate::expr::ConstraintBuilder;
+use expr::{Expr, ExprBuilder, StarkFrameTyped};
+use mozak_circuits_derive::StarkNameDisplay;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::StarkFrame;
+use starky::stark::Stark;
+
+use super::columns::Branches;
+use crate::columns_view::{HasNamedColumns, NumberOfColumns};
+use crate::expr::{build_ext, build_packed, ConstraintBuilder};
+use crate::unstark::NoColumns;
+
+#[derive(Copy, Clone, Default, StarkNameDisplay)]
+#[allow(clippy::module_name_repetitions)]
+pub struct BranchesStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F, const D: usize> HasNamedColumns for BranchesStark<F, D> {
+    type Columns = Branches<F>;
+}
+
+const COLUMNS: usize = Branches::<()>::NUMBER_OF_COLUMNS;
+const PUBLIC_INPUTS: usize = 0;
+
+/// Selectors are binary, and at most one of them is enabled per row.
+/// Padding rows have none of them enabled.
+fn binary_selectors<'a, P: Copy>(
+    lv: &Branches<Expr<'a, P>>,
+    cb: &mut ConstraintBuilder<Expr<'a, P>>,
+) {
+    lv.inst
+        .ops
+        .into_iter()
+        .for_each(|s| cb.always(s.is_binary()));
+    cb.always(lv.is_running().is_binary());
+}
+
+/// See `cpu::signed_comparison::signed_constraints`.
+fn signed_constraints<'a, P: Copy>(
+    lv: &Branches<Expr<'a, P>>,
+    cb: &mut ConstraintBuilder<Expr<'a, P>>,
+) {
+    cb.always(lv.op1_sign_bit.is_binary());
+    cb.always(lv.op2_sign_bit.is_binary());
+
+    // When op1 is not signed as per instruction semantics, op1_sign_bit must be 0.
+    cb.always((1 - lv.inst.is_op1_signed) * lv.op1_sign_bit);
+    // When op2 is not signed as per instruction semantics, op2_sign_bit must be 0.
+    cb.always((1 - lv.inst.is_op2_signed) * lv.op2_sign_bit);
+}
 
 /// Constraints for `less_than` and `normalised_diff`
 /// For `less_than`:
@@ -14,8 +67,8 @@ use crate::expr::ConstraintBuilder;
 /// For `normalised_diff`:
 ///  `0` iff `r1 == r2`
 ///  `1` iff `r1 != r2`
-pub(crate) fn comparison_constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+fn comparison_constraints<'a, P: Copy>(
+    lv: &Branches<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
     let lt = lv.less_than;
@@ -45,8 +98,8 @@ pub(crate) fn comparison_constraints<'a, P: Copy>(
 }
 
 /// Constraints for conditional branch operations
-pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+fn branch_constraints<'a, P: Copy>(
+    lv: &Branches<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
     let ops = &lv.inst.ops;
@@ -78,19 +131,78 @@ pub(crate) fn constraints<'a, P: Copy>(
     cb.always(ops.bne * (1 - lv.normalised_diff) * (next_pc - bumped_pc));
 }
 
+fn generate_constraints<'a, T: Copy>(
+    vars: &StarkFrameTyped<Branches<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+) -> ConstraintBuilder<Expr<'a, T>> {
+    let lv = &vars.local_values;
+    let mut constraints = ConstraintBuilder::default();
+
+    binary_selectors(lv, &mut constraints);
+    signed_constraints(lv, &mut constraints);
+    comparison_constraints(lv, &mut constraints);
+    branch_constraints(lv, &mut constraints);
+
+    constraints
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for BranchesStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        constraint_consumer: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>, {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_packed(constraints, constraint_consumer);
+    }
+
+    fn constraint_degree(&self) -> usize { 3 }
+
+    fn eval_ext_circuit(
+        &self,
+        circuit_builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        constraint_consumer: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_ext(constraints, circuit_builder, constraint_consumer);
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::cast_possible_wrap)]
 mod tests {
+    use anyhow::Result;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use mozak_runner::test_utils::u32_extra;
     use proptest::prelude::ProptestConfig;
     use proptest::strategy::Just;
     use proptest::{prop_oneof, proptest};
+    use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
 
-    use crate::cpu::stark::CpuStark;
+    use super::BranchesStark;
     use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
+    use crate::test_utils::{ProveAndVerify, C, D, F};
+
+    type S = BranchesStark<F, D>;
+
+    #[test]
+    fn test_degree() -> Result<()> { test_stark_low_degree(S::default()) }
+
+    #[test]
+    fn test_circuit() -> Result<()> { test_stark_circuit_constraints::<F, C, S, D>(S::default()) }
 
     fn prove_cond_branch<Stark: ProveAndVerify>(a: u32, b: u32, op: Op) {
         let (program, record) = code::execute(
@@ -137,8 +249,8 @@ mod tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(32))]
         #[test]
-        fn prove_branch_cpu(a in u32_extra(), b in u32_extra(), op in prop_oneof![Just(Op::BLT), Just(Op::BLTU), Just(Op::BGE), Just(Op::BGEU), Just(Op::BEQ), Just(Op::BNE)]) {
-            prove_cond_branch::<CpuStark<F, D>>(a, b, op);
+        fn prove_branch_table(a in u32_extra(), b in u32_extra(), op in prop_oneof![Just(Op::BLT), Just(Op::BLTU), Just(Op::BGE), Just(Op::BGEU), Just(Op::BEQ), Just(Op::BNE)]) {
+            prove_cond_branch::<BranchesStark<F, D>>(a, b, op);
         }
     }
 
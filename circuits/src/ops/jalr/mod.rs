@@ -0,0 +1,164 @@
+//! JALR, proven in its own table instead of the CPU.
+
+pub mod stark;
+
+pub mod columns {
+    use mozak_circuits_derive::ColumnNames;
+
+    use crate::columns_view::{columns_view_impl, make_col_map};
+    use crate::cpu::columns::OpSelectors;
+    use crate::cpu_skeleton::columns::CpuSkeletonCtl;
+    use crate::linear_combination::Column;
+    use crate::linear_combination_typed::ColumnWithTypedInput;
+    use crate::program::columns::ProgramRom;
+    use crate::rangecheck::columns::RangeCheckCtl;
+    use crate::register::RegisterCtl;
+    use crate::stark::mozak_stark::{JalrTable, TableWithTypedOutput};
+
+    columns_view_impl!(Instruction);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Instruction<T> {
+        /// The original instruction (+ `imm_value`) used for program
+        /// cross-table-lookup.
+        pub pc: T,
+        /// Selects the register to use as source for `rs1`
+        pub rs1_selected: T,
+        /// Selects the register to use as source for `rs2`
+        pub rs2_selected: T,
+        /// Selects the register to use as destination for `rd`
+        pub rd_selected: T,
+        /// Special immediate value used for code constants
+        pub imm_value: T,
+    }
+
+    make_col_map!(Jalr);
+    columns_view_impl!(Jalr);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Jalr<T> {
+        pub inst: Instruction<T>,
+        pub clk: T,
+        pub new_pc: T,
+        pub op1_value: T,
+        pub dst_value: T,
+
+        pub is_running: T,
+    }
+
+    #[must_use]
+    pub fn register_looking() -> Vec<TableWithTypedOutput<RegisterCtl<Column>>> {
+        let is_read = ColumnWithTypedInput::constant(1);
+        let is_write = ColumnWithTypedInput::constant(2);
+
+        vec![
+            JalrTable::new(
+                RegisterCtl {
+                    clk: COL_MAP.clk,
+                    op: is_read,
+                    addr: COL_MAP.inst.rs1_selected,
+                    value: COL_MAP.op1_value,
+                },
+                COL_MAP.is_running,
+            ),
+            JalrTable::new(
+                RegisterCtl {
+                    clk: COL_MAP.clk,
+                    op: is_write,
+                    addr: COL_MAP.inst.rd_selected,
+                    value: COL_MAP.dst_value,
+                },
+                COL_MAP.is_running,
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+        [COL_MAP.dst_value, COL_MAP.inst.pc]
+            .into_iter()
+            .map(|column| JalrTable::new(RangeCheckCtl(column), COL_MAP.is_running))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn lookup_for_skeleton() -> TableWithTypedOutput<CpuSkeletonCtl<Column>> {
+        JalrTable::new(
+            CpuSkeletonCtl {
+                clk: COL_MAP.clk,
+                pc: COL_MAP.inst.pc,
+                new_pc: COL_MAP.new_pc,
+                will_halt: ColumnWithTypedInput::constant(0),
+            },
+            COL_MAP.is_running,
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
+        let inst = COL_MAP.inst;
+        // Our opcode has to agree with the one the CPU uses for JALR.
+        let opcode = ColumnWithTypedInput::ascending_sum(OpSelectors {
+            jalr: ColumnWithTypedInput::constant(1),
+            ..Default::default()
+        });
+        JalrTable::new(
+            ProgramRom {
+                pc: inst.pc,
+                // See `cpu::columns::lookup_for_program_rom` for the layout.
+                inst_data: ColumnWithTypedInput::reduce_with_powers(
+                    [
+                        opcode,
+                        ColumnWithTypedInput::constant(0),
+                        ColumnWithTypedInput::constant(0),
+                        inst.rs1_selected,
+                        inst.rs2_selected,
+                        inst.rd_selected,
+                        inst.imm_value,
+                    ],
+                    1 << 5,
+                ),
+            },
+            COL_MAP.is_running,
+        )
+    }
+}
+
+use columns::{Instruction, Jalr};
+use mozak_runner::instruction::Op;
+use mozak_runner::vm::{ExecutionRecord, Row};
+use plonky2::hash::hash_types::RichField;
+
+use crate::utils::{from_u32, pad_trace_with_default};
+
+#[must_use]
+pub fn generate<F: RichField>(record: &ExecutionRecord<F>) -> Vec<Jalr<F>> {
+    let mut trace: Vec<Jalr<F>> = vec![];
+    for Row {
+        state,
+        instruction: inst,
+        aux,
+    } in &record.executed
+    {
+        if Op::JALR != inst.op {
+            continue;
+        }
+        let row = Jalr {
+            inst: Instruction {
+                pc: state.get_pc(),
+                rs1_selected: u32::from(inst.args.rs1),
+                rs2_selected: u32::from(inst.args.rs2),
+                rd_selected: u32::from(inst.args.rd),
+                imm_value: inst.args.imm,
+            },
+            clk: u32::try_from(state.clk).unwrap(),
+            new_pc: aux.new_pc,
+            op1_value: aux.op1,
+            dst_value: aux.dst_val,
+            is_running: 1,
+        }
+        .map(from_u32);
+        trace.push(row);
+    }
+    pad_trace_with_default(trace)
+}
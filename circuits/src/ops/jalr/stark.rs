@@ -2,15 +2,45 @@
 //! JALR writes the address of the instruction following the jump, being pc + 4,
 //! And then sets the target address with sum of signed immediate and rs1.
 
-use expr::Expr;
+use std::marker::PhantomData;
 
-use super::columns::CpuState;
-use crate::expr::ConstraintBuilder;
+use expr::{Expr, ExprBuilder, StarkFrameTyped};
+use mozak_circuits_derive::StarkNameDisplay;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::StarkFrame;
+use starky::stark::Stark;
+
+use super::columns::Jalr;
+use crate::columns_view::{HasNamedColumns, NumberOfColumns};
+use crate::expr::{build_ext, build_packed, ConstraintBuilder};
+use crate::unstark::NoColumns;
+
+#[derive(Copy, Clone, Default, StarkNameDisplay)]
+#[allow(clippy::module_name_repetitions)]
+pub struct JalrStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F, const D: usize> HasNamedColumns for JalrStark<F, D> {
+    type Columns = Jalr<F>;
+}
+
+const COLUMNS: usize = Jalr::<()>::NUMBER_OF_COLUMNS;
+const PUBLIC_INPUTS: usize = 0;
+
+fn generate_constraints<'a, T: Copy>(
+    vars: &StarkFrameTyped<Jalr<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+) -> ConstraintBuilder<Expr<'a, T>> {
+    let lv = &vars.local_values;
+    let mut cb = ConstraintBuilder::default();
+
+    cb.always(lv.is_running.is_binary());
 
-pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
-    cb: &mut ConstraintBuilder<Expr<'a, P>>,
-) {
     // Save the address of the instruction following the jump (return address).
     let return_address = lv.inst.pc + 4;
     let wrapped_return_address = return_address - (1 << 32);
@@ -19,29 +49,76 @@ pub(crate) fn constraints<'a, P: Copy>(
     // Check: the wrapped `pc + 4` is saved to destination.
     // As values are u32 range checked, this makes the value choice deterministic.
     cb.always(
-        lv.inst.ops.jalr * (destination - return_address) * (destination - wrapped_return_address),
+        lv.is_running * (destination - return_address) * (destination - wrapped_return_address),
     );
 
-    let jump_target = lv.op1_value + lv.op2_value;
+    let jump_target = lv.op1_value + lv.inst.imm_value;
     let wrapped_jump_target = jump_target - (1 << 32);
     let new_pc = lv.new_pc;
 
-    // Check: the wrapped op1, op2 sum is set as new `pc`.
+    // Check: the wrapped sum of op1 and the immediate is set as new `pc`.
     // As values are u32 range checked, this makes the value choice deterministic.
-    cb.transition(lv.inst.ops.jalr * (new_pc - jump_target) * (new_pc - wrapped_jump_target));
+    cb.always(lv.is_running * (new_pc - jump_target) * (new_pc - wrapped_jump_target));
+
+    cb
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for JalrStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        constraint_consumer: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>, {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_packed(constraints, constraint_consumer);
+    }
+
+    fn constraint_degree(&self) -> usize { 3 }
+
+    fn eval_ext_circuit(
+        &self,
+        circuit_builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        constraint_consumer: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_ext(constraints, circuit_builder, constraint_consumer);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use mozak_runner::test_utils::{reg, u32_extra};
     use proptest::prelude::ProptestConfig;
     use proptest::proptest;
+    use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
 
-    use crate::cpu::stark::CpuStark;
+    use super::JalrStark;
     use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
+    use crate::test_utils::{ProveAndVerify, C, D, F};
+
+    type S = JalrStark<F, D>;
+
+    #[test]
+    fn test_degree() -> Result<()> { test_stark_low_degree(S::default()) }
+
+    #[test]
+    fn test_circuit() -> Result<()> { test_stark_circuit_constraints::<F, C, S, D>(S::default()) }
 
     #[test]
     fn prove_jalr_goto_no_rs1() {
@@ -59,7 +136,7 @@ mod tests {
             &[],
         );
         assert_eq!(record.last_state.get_pc(), 8);
-        CpuStark::prove_and_verify(&program, &record).unwrap();
+        JalrStark::prove_and_verify(&program, &record).unwrap();
     }
 
     #[test]
@@ -78,7 +155,7 @@ mod tests {
             &[(0x1, 0)],
         );
         assert_eq!(record.last_state.get_pc(), 8);
-        CpuStark::prove_and_verify(&program, &record).unwrap();
+        JalrStark::prove_and_verify(&program, &record).unwrap();
     }
 
     #[test]
@@ -97,7 +174,7 @@ mod tests {
             &[(0x1, 4)],
         );
         assert_eq!(record.last_state.get_pc(), 8);
-        CpuStark::prove_and_verify(&program, &record).unwrap();
+        JalrStark::prove_and_verify(&program, &record).unwrap();
     }
 
     #[test]
@@ -116,7 +193,7 @@ mod tests {
             &[(0x1, 0)],
         );
         assert_eq!(record.last_state.get_pc(), 8);
-        CpuStark::prove_and_verify(&program, &record).unwrap();
+        JalrStark::prove_and_verify(&program, &record).unwrap();
     }
 
     fn prove_triple_jalr<Stark: ProveAndVerify>() {
@@ -152,7 +229,7 @@ mod tests {
     }
 
     #[test]
-    fn prove_triple_jalr_cpu() { prove_triple_jalr::<CpuStark<F, D>>() }
+    fn prove_triple_jalr_table() { prove_triple_jalr::<JalrStark<F, D>>() }
 
     #[test]
     fn prove_triple_jalr_mozak() { prove_triple_jalr::<MozakStark<F, D>>() }
@@ -188,7 +265,7 @@ mod tests {
             );
             assert_eq!(record.executed.len(), 3);
             assert_eq!(record.state_before_final().get_register_value(rd), 4);
-            CpuStark::prove_and_verify(&program, &record).unwrap();
+            JalrStark::prove_and_verify(&program, &record).unwrap();
         }
     }
 }
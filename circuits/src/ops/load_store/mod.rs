@@ -0,0 +1,344 @@
+//! Loads and stores of bytes, halfwords and words, proven in their own table
+//! instead of the CPU.
+
+pub mod stark;
+
+pub mod columns {
+    use core::iter::Sum;
+    use core::ops::Add;
+
+    use mozak_circuits_derive::ColumnNames;
+
+    use crate::columns_view::{columns_view_impl, make_col_map};
+    use crate::cpu::columns::OpSelectors;
+    use crate::cpu_skeleton::columns::CpuSkeletonCtl;
+    use crate::linear_combination::Column;
+    use crate::linear_combination_typed::ColumnWithTypedInput;
+    use crate::memory::columns::MemoryCtl;
+    use crate::program::columns::ProgramRom;
+    use crate::rangecheck::columns::RangeCheckCtl;
+    use crate::register::RegisterCtl;
+    use crate::stark::mozak_stark::{LoadStoreTable, TableWithTypedOutput};
+    use crate::xor::columns::XorView;
+
+    columns_view_impl!(Ops);
+    /// Selectors for which instruction is currently active.
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Ops<T> {
+        /// Store Byte
+        pub sb: T,
+        /// Store Half Word
+        pub sh: T,
+        /// Store Word
+        pub sw: T,
+        /// Load Byte, signed or unsigned
+        pub lb: T,
+        /// Load Half Word, signed or unsigned
+        pub lh: T,
+        /// Load Word
+        pub lw: T,
+    }
+
+    impl<T: Add<Output = T>> Ops<T> {
+        pub fn byte_mem_ops(self) -> T { self.sb + self.lb }
+
+        pub fn halfword_mem_ops(self) -> T { self.sh + self.lh }
+
+        pub fn fullword_mem_ops(self) -> T { self.sw + self.lw }
+    }
+
+    columns_view_impl!(Instruction);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Instruction<T> {
+        /// The original instruction (+ `imm_value`) used for program
+        /// cross-table-lookup.
+        pub pc: T,
+        /// Selects the current operation type
+        pub ops: Ops<T>,
+        pub is_dst_signed: T,
+        /// Selects the register to use as source for `rs1`
+        pub rs1_selected: T,
+        /// Selects the register to use as source for `rs2`
+        pub rs2_selected: T,
+        /// Selects the register to use as destination for `rd`
+        pub rd_selected: T,
+        /// Special immediate value used for code constants
+        pub imm_value: T,
+    }
+
+    make_col_map!(LoadStore);
+    columns_view_impl!(LoadStore);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct LoadStore<T> {
+        pub inst: Instruction<T>,
+        pub clk: T,
+
+        pub op1_value: T,
+        pub op2_value_raw: T,
+        /// The sum of the value of the second operand register and the
+        /// immediate value. Wrapped around to fit in a `u32`.
+        pub mem_addr: T,
+
+        /// `dst_value` contains "correct" (modified from `mem_access_raw` for
+        /// signed operations) value targetted towards `dst`.
+        pub dst_value: T,
+        pub dst_sign_bit: T,
+
+        /// `mem_access_raw` contains values fetched or stored into the memory
+        /// table. These values are always unsigned by nature (as mem table
+        /// does not differentiate between signed and unsigned values).
+        pub mem_value_raw: T,
+
+        /// Linked values with the Xor Stark Table, used to mask the stored
+        /// value for SB and SH.
+        pub xor: XorView<T>,
+    }
+
+    const LOAD_STORE: LoadStore<ColumnWithTypedInput<LoadStore<i64>>> = COL_MAP;
+
+    impl<T: Copy + Add<Output = T> + Sum> LoadStore<T> {
+        pub fn is_running(&self) -> T { self.inst.ops.into_iter().sum() }
+    }
+
+    #[must_use]
+    pub fn register_looking() -> Vec<TableWithTypedOutput<RegisterCtl<Column>>> {
+        let is_read = ColumnWithTypedInput::constant(1);
+        let is_write = ColumnWithTypedInput::constant(2);
+
+        vec![
+            LoadStoreTable::new(
+                RegisterCtl {
+                    clk: LOAD_STORE.clk,
+                    op: is_read,
+                    addr: LOAD_STORE.inst.rs1_selected,
+                    value: LOAD_STORE.op1_value,
+                },
+                LOAD_STORE.is_running(),
+            ),
+            LoadStoreTable::new(
+                RegisterCtl {
+                    clk: LOAD_STORE.clk,
+                    op: is_read,
+                    addr: LOAD_STORE.inst.rs2_selected,
+                    value: LOAD_STORE.op2_value_raw,
+                },
+                LOAD_STORE.is_running(),
+            ),
+            LoadStoreTable::new(
+                RegisterCtl {
+                    clk: LOAD_STORE.clk,
+                    op: is_write,
+                    addr: LOAD_STORE.inst.rd_selected,
+                    value: LOAD_STORE.dst_value,
+                },
+                LOAD_STORE.is_running(),
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+        let ops = LOAD_STORE.inst.ops;
+
+        [
+            (
+                LOAD_STORE.dst_value - LOAD_STORE.dst_sign_bit * 0xFFFF_FF00,
+                ops.lb,
+            ),
+            (
+                LOAD_STORE.dst_value - LOAD_STORE.dst_sign_bit * 0xFFFF_0000,
+                ops.lh,
+            ),
+        ]
+        .into_iter()
+        .map(|(columns, filter)| LoadStoreTable::new(RangeCheckCtl(columns), filter))
+        .collect()
+    }
+
+    /// Lookup for Xor stark.
+    #[must_use]
+    pub fn lookup_for_xor() -> TableWithTypedOutput<XorView<Column>> {
+        let ops = LOAD_STORE.inst.ops;
+        LoadStoreTable::new(LOAD_STORE.xor, ops.sb + ops.sh)
+    }
+
+    /// Lookup into Memory stark.
+    #[must_use]
+    pub fn lookup_for_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
+        let ops = LOAD_STORE.inst.ops;
+        LoadStoreTable::new(
+            MemoryCtl {
+                clk: LOAD_STORE.clk,
+                is_store: ops.sb,
+                is_load: ops.lb, // For both `LB` and `LBU`
+                addr: LOAD_STORE.mem_addr,
+                value: LOAD_STORE.mem_value_raw,
+            },
+            ops.byte_mem_ops(),
+        )
+    }
+
+    /// Lookup into half word Memory stark.
+    #[must_use]
+    pub fn lookup_for_halfword_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
+        let ops = LOAD_STORE.inst.ops;
+        LoadStoreTable::new(
+            MemoryCtl {
+                clk: LOAD_STORE.clk,
+                is_store: ops.sh,
+                is_load: ops.lh,
+                addr: LOAD_STORE.mem_addr,
+                value: LOAD_STORE.mem_value_raw,
+            },
+            ops.halfword_mem_ops(),
+        )
+    }
+
+    /// Lookup into fullword Memory table.
+    #[must_use]
+    pub fn lookup_for_fullword_memory() -> TableWithTypedOutput<MemoryCtl<Column>> {
+        let ops = LOAD_STORE.inst.ops;
+        LoadStoreTable::new(
+            MemoryCtl {
+                clk: LOAD_STORE.clk,
+                is_store: ops.sw,
+                is_load: ops.lw,
+                addr: LOAD_STORE.mem_addr,
+                value: LOAD_STORE.mem_value_raw,
+            },
+            ops.fullword_mem_ops(),
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_skeleton() -> TableWithTypedOutput<CpuSkeletonCtl<Column>> {
+        LoadStoreTable::new(
+            CpuSkeletonCtl {
+                clk: LOAD_STORE.clk,
+                pc: LOAD_STORE.inst.pc,
+                new_pc: LOAD_STORE.inst.pc + 4,
+                will_halt: ColumnWithTypedInput::constant(0),
+            },
+            LOAD_STORE.is_running(),
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
+        let inst = LOAD_STORE.inst;
+        // Our opcodes have to agree with the ones the CPU uses for the same
+        // instructions.
+        let opcode = ColumnWithTypedInput::ascending_sum(OpSelectors {
+            sb: inst.ops.sb,
+            sh: inst.ops.sh,
+            sw: inst.ops.sw,
+            lb: inst.ops.lb,
+            lh: inst.ops.lh,
+            lw: inst.ops.lw,
+            ..Default::default()
+        });
+        LoadStoreTable::new(
+            ProgramRom {
+                pc: inst.pc,
+                // See `cpu::columns::lookup_for_program_rom` for the layout.
+                inst_data: ColumnWithTypedInput::reduce_with_powers(
+                    [
+                        opcode,
+                        ColumnWithTypedInput::constant(0),
+                        // Loads share the `is_op2_signed` slot with `is_dst_signed`.
+                        inst.is_dst_signed,
+                        inst.rs1_selected,
+                        inst.rs2_selected,
+                        inst.rd_selected,
+                        inst.imm_value,
+                    ],
+                    1 << 5,
+                ),
+            },
+            LOAD_STORE.is_running(),
+        )
+    }
+}
+
+use columns::{Instruction, LoadStore, Ops};
+use mozak_runner::instruction::Op;
+use mozak_runner::vm::{ExecutionRecord, Row};
+use plonky2::hash::hash_types::RichField;
+
+use crate::cpu::columns as cpu_cols;
+use crate::utils::{from_u32, pad_trace_with_default};
+use crate::xor::columns::XorView;
+
+#[must_use]
+pub fn generate<F: RichField>(record: &ExecutionRecord<F>) -> Vec<LoadStore<F>> {
+    let mut trace: Vec<LoadStore<F>> = vec![];
+    for Row {
+        state,
+        instruction: inst,
+        aux,
+    } in &record.executed
+    {
+        if !matches!(
+            inst.op,
+            Op::SB | Op::SH | Op::SW | Op::LB | Op::LBU | Op::LH | Op::LHU | Op::LW
+        ) {
+            continue;
+        }
+        // We decode just like the CPU does, so that we agree on the program ROM.
+        let cpu_inst = cpu_cols::Instruction::from((state.get_pc(), *inst));
+        let mem = aux.mem.unwrap_or_default();
+        // SB and SH only store the least significant 8 or 16 bits of `rs1`.
+        let xor = match inst.op {
+            Op::SB => XorView {
+                a: aux.op1,
+                b: 0x0000_00FF,
+                out: aux.op1 ^ 0x0000_00FF,
+            },
+            Op::SH => XorView {
+                a: aux.op1,
+                b: 0x0000_FFFF,
+                out: aux.op1 ^ 0x0000_FFFF,
+            },
+            _ => XorView::default(),
+        };
+        // sign extension needs to be from `u8` in case of `LB`
+        // sign extension needs to be from `u16` in case of `LH`
+        let dst_sign_bit = match inst.op {
+            Op::LB => aux.dst_val >= 1 << 7,
+            Op::LH => aux.dst_val >= 1 << 15,
+            _ => false,
+        };
+        let row = LoadStore {
+            inst: Instruction {
+                pc: cpu_inst.pc,
+                ops: Ops {
+                    sb: cpu_inst.ops.sb,
+                    sh: cpu_inst.ops.sh,
+                    sw: cpu_inst.ops.sw,
+                    lb: cpu_inst.ops.lb,
+                    lh: cpu_inst.ops.lh,
+                    lw: cpu_inst.ops.lw,
+                },
+                is_dst_signed: cpu_inst.is_dst_signed,
+                rs1_selected: cpu_inst.rs1_selected,
+                rs2_selected: cpu_inst.rs2_selected,
+                rd_selected: cpu_inst.rd_selected,
+                imm_value: cpu_inst.imm_value,
+            },
+            clk: u32::try_from(state.clk).unwrap(),
+            op1_value: aux.op1,
+            op2_value_raw: aux.op2_raw,
+            mem_addr: mem.addr,
+            dst_value: aux.dst_val,
+            dst_sign_bit: u32::from(dst_sign_bit),
+            mem_value_raw: mem.raw_value,
+            xor,
+        }
+        .map(from_u32);
+        trace.push(row);
+    }
+    pad_trace_with_default(trace)
+}
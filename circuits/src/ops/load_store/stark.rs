@@ -1,20 +1,47 @@
 //! This module implements constraints for memory access, both for load and
 //! store. Supported operators include: `SB` 'Save Byte', `LB` and `LBU` 'Load
-//! Byte' and 'Load Byte Unsigned'
+//! Byte' and 'Load Byte Unsigned', and their halfword and word counterparts.
 
-use expr::Expr;
+use std::marker::PhantomData;
 
-use super::bitwise::and_gadget;
-use super::columns::CpuState;
-use crate::expr::ConstraintBuilder;
+use expr::{Expr, ExprBuilder, StarkFrameTyped};
+use mozak_circuits_derive::StarkNameDisplay;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::StarkFrame;
+use starky::stark::Stark;
+
+use super::columns::LoadStore;
+use crate::columns_view::{HasNamedColumns, NumberOfColumns};
+use crate::expr::{build_ext, build_packed, ConstraintBuilder};
+use crate::ops::bitwise::stark::and_gadget;
+use crate::unstark::NoColumns;
+
+#[derive(Copy, Clone, Default, StarkNameDisplay)]
+#[allow(clippy::module_name_repetitions)]
+pub struct LoadStoreStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F, const D: usize> HasNamedColumns for LoadStoreStark<F, D> {
+    type Columns = LoadStore<F>;
+}
+
+const COLUMNS: usize = LoadStore::<()>::NUMBER_OF_COLUMNS;
+const PUBLIC_INPUTS: usize = 0;
 
 /// Ensure that `dst_value` and `mem_value_raw` only differ
 /// in case of `LB` by `0xFFFF_FF00` and for `LH` by `0xFFFF_0000`. The
 /// correctness of value presented in `dst_sign_bit` is ensured via range-check
-pub(crate) fn signed_constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+fn signed_constraints<'a, P: Copy>(
+    lv: &LoadStore<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
+    cb.always(lv.inst.is_dst_signed.is_binary());
     cb.always(lv.dst_sign_bit.is_binary());
     // When dst is not signed as per instruction semantics, dst_sign_bit must be 0.
     cb.always((1 - lv.inst.is_dst_signed) * lv.dst_sign_bit);
@@ -37,27 +64,122 @@ pub(crate) fn signed_constraints<'a, P: Copy>(
     );
 }
 
-pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
-    cb: &mut ConstraintBuilder<Expr<'a, P>>,
-) {
+fn generate_constraints<'a, T: Copy>(
+    vars: &StarkFrameTyped<LoadStore<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+) -> ConstraintBuilder<Expr<'a, T>> {
+    let lv = &vars.local_values;
+    let mut cb = ConstraintBuilder::default();
+    let ops = lv.inst.ops;
+
+    ops.into_iter().for_each(|s| cb.always(s.is_binary()));
+    cb.always(lv.is_running().is_binary());
+
     // memory address is equal to rs2-value + imm (wrapping)
-    cb.always(lv.inst.ops.is_mem_op() * (lv.mem_addr - lv.op2_value));
+    let addr = lv.op2_value_raw + lv.inst.imm_value;
+    cb.always(lv.is_running() * (lv.mem_addr - addr) * (lv.mem_addr - (addr - (1 << 32))));
+
+    // SW stores all of `rs1`, and LW loads the word unchanged.
+    cb.always(ops.sw * (lv.mem_value_raw - lv.op1_value));
+    cb.always(ops.lw * (lv.dst_value - lv.mem_value_raw));
+
     // signed memory constraints
-    signed_constraints(lv, cb);
+    signed_constraints(lv, &mut cb);
+
+    cb
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for LoadStoreStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        constraint_consumer: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>, {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_packed(constraints, constraint_consumer);
+    }
+
+    fn constraint_degree(&self) -> usize { 3 }
+
+    fn eval_ext_circuit(
+        &self,
+        circuit_builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        constraint_consumer: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_ext(constraints, circuit_builder, constraint_consumer);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use mozak_runner::test_utils::u32_extra;
+    use plonky2::field::types::Field;
+    use plonky2::util::timing::TimingTree;
     use proptest::prelude::ProptestConfig;
     use proptest::proptest;
+    use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+
+    use super::LoadStoreStark;
+    use crate::cross_table_lookup::ctl_utils::check_single_ctl;
+    use crate::generation::generate_traces;
+    use crate::ops::load_store::generate;
+    use crate::stark::mozak_stark::{InnerCpuTable, Lookups, MozakStark, TableKind};
+    use crate::stark::utils::trace_rows_to_poly_values;
+    use crate::test_utils::{ProveAndVerify, C, D, F};
 
-    use crate::cpu::stark::CpuStark;
-    use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{ProveAndVerify, D, F};
+    type S = LoadStoreStark<F, D>;
+
+    #[test]
+    fn test_degree() -> Result<()> { test_stark_low_degree(S::default()) }
+
+    #[test]
+    fn test_circuit() -> Result<()> { test_stark_circuit_constraints::<F, C, S, D>(S::default()) }
+
+    /// `LB` and `LBU` share an opcode, so only the program ROM lookup can tell
+    /// an `LBU` apart from an `LB` that claims a non-negative byte.
+    #[test]
+    fn lbu_claiming_to_be_signed_is_rejected() {
+        let (program, record) = code::execute(
+            [Instruction {
+                op: Op::LBU,
+                args: Args {
+                    rd: 1,
+                    rs2: 2,
+                    ..Args::default()
+                },
+            }],
+            &[(0x100, 0x80)],
+            &[(2, 0x100)],
+        );
+        let mut traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+        let ctl = InnerCpuTable::lookups();
+        assert!(check_single_ctl(&traces, &ctl).is_ok());
+
+        let mut rows = generate(&record);
+        let lbu = rows
+            .iter_mut()
+            .find(|row| row.inst.ops.lb == F::ONE)
+            .unwrap();
+        lbu.inst.is_dst_signed = F::ONE;
+        traces[TableKind::LoadStore] = trace_rows_to_poly_values(rows);
+        assert!(check_single_ctl(&traces, &ctl).is_err());
+    }
 
     fn prove_sb<Stark: ProveAndVerify>(a: u32, b: u32) {
         let (program, record) = code::execute(
@@ -196,13 +318,13 @@ mod tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(4))]
         #[test]
-        fn prove_sb_cpu(a in u32_extra(), b in u32_extra()) {
-            prove_sb::<CpuStark<F, D>>(a, b);
+        fn prove_sb_table(a in u32_extra(), b in u32_extra()) {
+            prove_sb::<LoadStoreStark<F, D>>(a, b);
         }
 
         #[test]
-        fn prove_lb_cpu(a in u32_extra(), b in u32_extra()) {
-            prove_lb_and_lbu::<CpuStark<F, D>>(a, b);
+        fn prove_lb_table(a in u32_extra(), b in u32_extra()) {
+            prove_lb_and_lbu::<LoadStoreStark<F, D>>(a, b);
         }
 
         #[test]
@@ -211,18 +333,18 @@ mod tests {
         }
 
         #[test]
-        fn prove_sb_lbu_cpu(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
-            prove_sb_lbu::<CpuStark<F, D>>(offset, imm, content);
+        fn prove_sb_lbu_table(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
+            prove_sb_lbu::<LoadStoreStark<F, D>>(offset, imm, content);
         }
 
         #[test]
-        fn prove_sb_lb_cpu(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
-            prove_sb_lb::<CpuStark<F, D>>(offset, imm, content);
+        fn prove_sb_lb_table(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
+            prove_sb_lb::<LoadStoreStark<F, D>>(offset, imm, content);
         }
 
         #[test]
-        fn prove_sh_lh_cpu(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
-            prove_sh_lh::<CpuStark<F, D>>(offset, imm, content);
+        fn prove_sh_lh_table(offset in u32_extra(), imm in u32_extra(), content in u32_extra()) {
+            prove_sh_lh::<LoadStoreStark<F, D>>(offset, imm, content);
         }
     }
 
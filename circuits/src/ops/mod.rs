@@ -1,2 +1,7 @@
 pub mod add;
+pub mod bitwise;
 pub mod blt_taken;
+pub mod branches;
+pub mod jalr;
+pub mod load_store;
+pub mod mul_div;
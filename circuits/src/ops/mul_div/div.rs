@@ -6,13 +6,13 @@
 
 use expr::Expr;
 
-use super::columns::CpuState;
-use crate::cpu::mul::bit_to_sign;
+use super::columns::MulDiv;
+use super::mul::bit_to_sign;
 use crate::expr::ConstraintBuilder;
 
 /// Constraints for DIV / REM / DIVU / REMU / SRL / SRA instructions
 pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+    lv: &MulDiv<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
     let ops = lv.inst.ops;
@@ -124,11 +124,13 @@ mod tests {
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use mozak_runner::test_utils::u32_extra;
+    use plonky2::field::types::Field;
     use proptest::prelude::{prop_assert_eq, ProptestConfig};
     use proptest::test_runner::TestCaseError;
     use proptest::{prop_assert, proptest};
 
-    use crate::cpu::stark::CpuStark;
+    use crate::ops::mul_div::generate;
+    use crate::ops::mul_div::stark::MulDivStark;
     use crate::stark::mozak_stark::MozakStark;
     use crate::test_utils::{inv, ProveAndVerify, D, F};
 
@@ -199,7 +201,25 @@ mod tests {
 
     #[allow(clippy::cast_sign_loss)]
     #[test]
-    fn prove_div_example() { prove_div::<CpuStark<F, D>>(i32::MIN as u32, -1_i32 as u32, 28); }
+    fn prove_div_example() { prove_div::<MulDivStark<F, D>>(i32::MIN as u32, -1_i32 as u32, 28); }
+
+    #[allow(clippy::cast_sign_loss)]
+    #[test]
+    fn div_overflow_has_negative_quotient() {
+        let (_program, record) = code::execute(div_rem_instructions(28), &[], &[
+            (1, i32::MIN as u32),
+            (2, -1_i32 as u32),
+        ]);
+        let trace = generate::<F>(&record);
+        let div_row = trace.iter().find(|row| row.inst.ops.div.is_one()).unwrap();
+        // i32::MIN / -1 overflows to i32::MIN, so the quotient is negative.
+        assert_eq!(
+            div_row.quotient_value,
+            F::from_canonical_u32(i32::MIN as u32)
+        );
+        assert_eq!(div_row.quotient_sign, F::ONE);
+        assert_eq!(div_row.skip_check_quotient_sign, F::ONE);
+    }
 
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
@@ -213,13 +233,13 @@ mod tests {
         }
 
         #[test]
-        fn prove_div_cpu(p in u32_extra(), q in u32_extra(), rd in 3_u8..32) {
-            prove_div::<CpuStark<F, D>>(p, q, rd);
+        fn prove_div_mul_div(p in u32_extra(), q in u32_extra(), rd in 3_u8..32) {
+            prove_div::<MulDivStark<F, D>>(p, q, rd);
         }
 
         #[test]
-        fn prove_divu_cpu(p in u32_extra(), q in u32_extra(), rd in 3_u8..32) {
-            prove_divu::<CpuStark<F, D>>(p, q, rd)?;
+        fn prove_divu_mul_div(p in u32_extra(), q in u32_extra(), rd in 3_u8..32) {
+            prove_divu::<MulDivStark<F, D>>(p, q, rd)?;
         }
     }
 
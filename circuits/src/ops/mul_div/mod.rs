@@ -0,0 +1,463 @@
+//! Multiplication, division and shift operations, proven in their own table
+//! instead of the CPU.
+//!
+//! SLL is proven as a multiplication by `2^shift_amount`, and SRL and SRA as
+//! a division by it, so all of them share the columns of the product and the
+//! division.
+
+pub mod div;
+pub mod mul;
+pub mod shift;
+pub mod stark;
+
+pub mod columns {
+    use core::iter::Sum;
+    use core::ops::{Add, Mul, Sub};
+
+    use mozak_circuits_derive::ColumnNames;
+
+    use crate::bitshift::columns::Bitshift;
+    use crate::columns_view::{columns_view_impl, make_col_map};
+    use crate::cpu::columns::OpSelectors;
+    use crate::cpu_skeleton::columns::CpuSkeletonCtl;
+    use crate::linear_combination::Column;
+    use crate::linear_combination_typed::ColumnWithTypedInput;
+    use crate::program::columns::ProgramRom;
+    use crate::rangecheck::columns::RangeCheckCtl;
+    use crate::register::RegisterCtl;
+    use crate::stark::mozak_stark::{MulDivTable, TableWithTypedOutput};
+
+    columns_view_impl!(Ops);
+    /// Selectors for which instruction is currently active.
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Ops<T> {
+        /// DIV and DIVU
+        pub div: T,
+        /// REM and REMU
+        pub rem: T,
+        pub mul: T,
+        /// MULH, MULHU and MULHSU
+        pub mulh: T,
+        /// Shift Left Logical by amount
+        pub sll: T,
+        /// Shift Right Logical by amount
+        pub srl: T,
+        /// Arithmetic Right Shifts
+        pub sra: T,
+    }
+
+    impl<T: Copy + Add<Output = T>> Ops<T> {
+        pub fn divs(&self) -> T { self.div + self.rem + self.srl + self.sra }
+
+        pub fn muls(&self) -> T { self.mul + self.mulh + self.sll }
+
+        pub fn shifts(&self) -> T { self.sll + self.srl + self.sra }
+    }
+
+    columns_view_impl!(Instruction);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct Instruction<T> {
+        /// The original instruction (+ `imm_value`) used for program
+        /// cross-table-lookup.
+        pub pc: T,
+        /// Selects the current operation type
+        pub ops: Ops<T>,
+        pub is_op1_signed: T,
+        pub is_op2_signed: T,
+        /// Selects the register to use as source for `rs1`
+        pub rs1_selected: T,
+        /// Selects the register to use as source for `rs2`
+        pub rs2_selected: T,
+        /// Selects the register to use as destination for `rd`
+        pub rd_selected: T,
+        /// Special immediate value used for code constants
+        pub imm_value: T,
+    }
+
+    make_col_map!(MulDiv);
+    columns_view_impl!(MulDiv);
+    #[repr(C)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
+    pub struct MulDiv<T> {
+        pub inst: Instruction<T>,
+        pub clk: T,
+
+        pub op1_value: T,
+        pub op2_value_raw: T,
+        /// The sum of the value of the second operand register and the
+        /// immediate value, or `2^shift_amount` for shifts.
+        pub op2_value: T,
+        pub dst_value: T,
+
+        // 0 means non-negative, 1 means negative.
+        // (If number is unsigned, it is non-negative.)
+        pub op1_sign_bit: T,
+        pub op2_sign_bit: T,
+
+        /// Linked values with the Bitshift Stark Table
+        pub bitshift: Bitshift<T>,
+        /// The bits of `op2_value_raw + imm_value` above the shift amount.
+        pub shift_amount_high: T, // range check u32 required
+
+        // Division evaluation columns
+        pub op2_value_inv: T,
+        pub quotient_value: T, // range check u32 required
+        pub quotient_sign: T,
+        pub skip_check_quotient_sign: T,
+        pub remainder_value: T, // range check u32 required
+        pub remainder_sign: T,
+        /// Value of `divisor_abs - remainder_abs - 1`
+        /// Used as a helper column to check that `remainder < divisor`.
+        pub remainder_slack: T, // range check u32 required
+
+        // Product evaluation columns
+        pub op1_abs: T,
+        pub op2_abs: T,
+        pub skip_check_product_sign: T,
+        pub product_sign: T,
+        pub product_high_limb: T, // range check u32 required
+        pub product_low_limb: T,  // range check u32 required
+        /// Used as a helper column to check that `product_high_limb !=
+        /// u32::MAX` when `product_sign` is 0 and `product_high_limb !=
+        /// 0` when `product_sign` is 1
+        pub product_high_limb_inv_helper: T,
+    }
+
+    const MUL_DIV: MulDiv<ColumnWithTypedInput<MulDiv<i64>>> = COL_MAP;
+
+    impl<T> MulDiv<T>
+    where
+        T: Copy + Add<Output = T> + Mul<i64, Output = T> + Sub<Output = T> + Sum,
+    {
+        pub fn is_running(&self) -> T { self.inst.ops.into_iter().sum() }
+
+        /// Value of the first operand, as if converted to i64.
+        ///
+        /// So range is `i32::MIN..=u32::MAX` in Prime Field.
+        pub fn op1_full_range(&self) -> T { self.op1_value - self.op1_sign_bit * (1 << 32) }
+
+        /// Value of the second operand, as if converted to i64.
+        ///
+        /// So range is `i32::MIN..=u32::MAX` in Prime Field.
+        pub fn op2_full_range(&self) -> T { self.op2_value - self.op2_sign_bit * (1 << 32) }
+    }
+
+    #[must_use]
+    pub fn register_looking() -> Vec<TableWithTypedOutput<RegisterCtl<Column>>> {
+        let is_read = ColumnWithTypedInput::constant(1);
+        let is_write = ColumnWithTypedInput::constant(2);
+
+        vec![
+            MulDivTable::new(
+                RegisterCtl {
+                    clk: MUL_DIV.clk,
+                    op: is_read,
+                    addr: MUL_DIV.inst.rs1_selected,
+                    value: MUL_DIV.op1_value,
+                },
+                MUL_DIV.is_running(),
+            ),
+            MulDivTable::new(
+                RegisterCtl {
+                    clk: MUL_DIV.clk,
+                    op: is_read,
+                    addr: MUL_DIV.inst.rs2_selected,
+                    value: MUL_DIV.op2_value_raw,
+                },
+                MUL_DIV.is_running(),
+            ),
+            MulDivTable::new(
+                RegisterCtl {
+                    clk: MUL_DIV.clk,
+                    op: is_write,
+                    addr: MUL_DIV.inst.rd_selected,
+                    value: MUL_DIV.dst_value,
+                },
+                MUL_DIV.is_running(),
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn rangecheck_looking() -> Vec<TableWithTypedOutput<RangeCheckCtl<Column>>> {
+        let ops = MUL_DIV.inst.ops;
+        let divs = ops.divs();
+        let muls = ops.muls();
+
+        [
+            (MUL_DIV.quotient_value, divs),
+            (MUL_DIV.remainder_value, divs),
+            (MUL_DIV.remainder_slack, divs),
+            (MUL_DIV.product_high_limb, muls),
+            (MUL_DIV.product_low_limb, muls),
+            (MUL_DIV.shift_amount_high, ops.shifts()),
+            // apply range constraints for the sign bits of each operand
+            (
+                MUL_DIV.op1_value - MUL_DIV.op1_sign_bit * (1 << 32)
+                    + MUL_DIV.inst.is_op1_signed * (1 << 31),
+                MUL_DIV.inst.is_op1_signed,
+            ),
+            (
+                MUL_DIV.op2_value - MUL_DIV.op2_sign_bit * (1 << 32)
+                    + MUL_DIV.inst.is_op2_signed * (1 << 31),
+                MUL_DIV.inst.is_op2_signed,
+            ),
+        ]
+        .into_iter()
+        .map(|(columns, filter)| MulDivTable::new(RangeCheckCtl(columns), filter))
+        .collect()
+    }
+
+    /// Lookup into `Bitshift` stark.
+    #[must_use]
+    pub fn lookup_for_shift_amount() -> TableWithTypedOutput<Bitshift<Column>> {
+        MulDivTable::new(MUL_DIV.bitshift, MUL_DIV.inst.ops.shifts())
+    }
+
+    #[must_use]
+    pub fn lookup_for_skeleton() -> TableWithTypedOutput<CpuSkeletonCtl<Column>> {
+        MulDivTable::new(
+            CpuSkeletonCtl {
+                clk: MUL_DIV.clk,
+                pc: MUL_DIV.inst.pc,
+                new_pc: MUL_DIV.inst.pc + 4,
+                will_halt: ColumnWithTypedInput::constant(0),
+            },
+            MUL_DIV.is_running(),
+        )
+    }
+
+    #[must_use]
+    pub fn lookup_for_program_rom() -> TableWithTypedOutput<ProgramRom<Column>> {
+        let inst = MUL_DIV.inst;
+        // Our opcodes have to agree with the ones the CPU uses for the same
+        // instructions.
+        let opcode = ColumnWithTypedInput::ascending_sum(OpSelectors {
+            div: inst.ops.div,
+            rem: inst.ops.rem,
+            mul: inst.ops.mul,
+            mulh: inst.ops.mulh,
+            sll: inst.ops.sll,
+            srl: inst.ops.srl,
+            sra: inst.ops.sra,
+            ..Default::default()
+        });
+        MulDivTable::new(
+            ProgramRom {
+                pc: inst.pc,
+                // See `cpu::columns::lookup_for_program_rom` for the layout.
+                inst_data: ColumnWithTypedInput::reduce_with_powers(
+                    [
+                        opcode,
+                        inst.is_op1_signed,
+                        inst.is_op2_signed,
+                        inst.rs1_selected,
+                        inst.rs2_selected,
+                        inst.rd_selected,
+                        inst.imm_value,
+                    ],
+                    1 << 5,
+                ),
+            },
+            MUL_DIV.is_running(),
+        )
+    }
+}
+
+use columns::{Instruction, MulDiv, Ops};
+use mozak_runner::instruction::Op;
+use mozak_runner::vm::{ExecutionRecord, Row};
+use plonky2::hash::hash_types::RichField;
+
+use crate::bitshift::columns::Bitshift;
+use crate::cpu::columns as cpu_cols;
+use crate::generation::MIN_TRACE_LENGTH;
+use crate::utils::{from_u32, sign_extend};
+
+#[must_use]
+pub fn pad_trace<F: RichField>(mut trace: Vec<MulDiv<F>>) -> Vec<MulDiv<F>> {
+    let len = trace.len().next_power_of_two().max(MIN_TRACE_LENGTH);
+    let padding = MulDiv {
+        product_high_limb_inv_helper: F::from_canonical_u32(u32::MAX).inverse(),
+        quotient_value: F::from_canonical_u32(u32::MAX),
+        ..Default::default()
+    };
+
+    trace.resize(len, padding);
+    trace
+}
+
+#[must_use]
+pub fn generate<F: RichField>(record: &ExecutionRecord<F>) -> Vec<MulDiv<F>> {
+    let mut trace: Vec<MulDiv<F>> = vec![];
+    for Row {
+        state,
+        instruction: inst,
+        aux,
+    } in &record.executed
+    {
+        if !matches!(
+            inst.op,
+            Op::MUL
+                | Op::MULH
+                | Op::MULHU
+                | Op::MULHSU
+                | Op::DIV
+                | Op::DIVU
+                | Op::REM
+                | Op::REMU
+                | Op::SLL
+                | Op::SRL
+                | Op::SRA
+        ) {
+            continue;
+        }
+        // We decode just like the CPU does, so that we agree on the program ROM.
+        let cpu_inst = cpu_cols::Instruction::from((state.get_pc(), *inst));
+        let shift = u64::from(aux.op2_raw) + u64::from(inst.args.imm);
+        let is_shift = matches!(inst.op, Op::SLL | Op::SRL | Op::SRA);
+        let shift_amount = if is_shift {
+            u8::try_from(shift & 0b1_1111).unwrap()
+        } else {
+            0
+        };
+        let mut row = MulDiv {
+            inst: Instruction {
+                pc: cpu_inst.pc,
+                ops: Ops {
+                    div: cpu_inst.ops.div,
+                    rem: cpu_inst.ops.rem,
+                    mul: cpu_inst.ops.mul,
+                    mulh: cpu_inst.ops.mulh,
+                    sll: cpu_inst.ops.sll,
+                    srl: cpu_inst.ops.srl,
+                    sra: cpu_inst.ops.sra,
+                },
+                is_op1_signed: cpu_inst.is_op1_signed,
+                is_op2_signed: cpu_inst.is_op2_signed,
+                rs1_selected: cpu_inst.rs1_selected,
+                rs2_selected: cpu_inst.rs2_selected,
+                rd_selected: cpu_inst.rd_selected,
+                imm_value: cpu_inst.imm_value,
+            },
+            clk: u32::try_from(state.clk).unwrap(),
+            op1_value: aux.op1,
+            op2_value_raw: aux.op2_raw,
+            op2_value: aux.op2,
+            dst_value: aux.dst_val,
+            bitshift: Bitshift::from(shift_amount),
+            shift_amount_high: if is_shift {
+                u32::try_from(shift >> 5).unwrap()
+            } else {
+                0
+            },
+            ..Default::default()
+        }
+        .map(from_u32);
+
+        operands_sign_handling(&mut row, aux.op1, aux.op2);
+        generate_mul_row(&mut row, aux.op1, aux.op2);
+        generate_div_row(&mut row, inst.op, aux.op1, aux.op2);
+        trace.push(row);
+    }
+    pad_trace(trace)
+}
+
+fn operands_sign_handling<F: RichField>(row: &mut MulDiv<F>, op1: u32, op2: u32) {
+    row.op1_sign_bit = F::from_bool(sign_extend(row.inst.is_op1_signed.is_nonzero(), op1) < 0);
+    row.op2_sign_bit = F::from_bool(sign_extend(row.inst.is_op2_signed.is_nonzero(), op2) < 0);
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn generate_mul_row<F: RichField>(row: &mut MulDiv<F>, op1: u32, op2: u32) {
+    // Helper function to determine sign and absolute value.
+    let compute_sign_and_abs: fn(bool, u32) -> (bool, u32) = |is_signed, value| {
+        let full_range = sign_extend(is_signed, value);
+        let is_negative = full_range.is_negative();
+        let absolute_value = full_range.unsigned_abs() as u32;
+        (is_negative, absolute_value)
+    };
+    let (is_op2_negative, op2_abs) = compute_sign_and_abs(row.inst.is_op2_signed.is_nonzero(), op2);
+    let (is_op1_negative, op1_abs) = compute_sign_and_abs(row.inst.is_op1_signed.is_nonzero(), op1);
+
+    // Determine product sign and absolute value.
+    let mut product_sign = is_op1_negative ^ is_op2_negative;
+    let op1_mul_op2_abs = u64::from(op1_abs) * u64::from(op2_abs);
+
+    row.skip_check_product_sign = if op1_mul_op2_abs == 0 {
+        product_sign = false;
+        F::ONE
+    } else {
+        F::ZERO
+    };
+
+    row.product_sign = if product_sign { F::ONE } else { F::ZERO };
+    row.op1_abs = from_u32(op1_abs);
+    row.op2_abs = from_u32(op2_abs);
+
+    // Compute the product limbs based on sign.
+    let prod = if product_sign {
+        u64::MAX - op1_mul_op2_abs + 1
+    } else {
+        op1_mul_op2_abs
+    };
+
+    let low = (prod & 0xffff_ffff) as u32;
+    let high = (prod >> 32) as u32;
+    row.product_low_limb = from_u32(low);
+    row.product_high_limb = from_u32(high);
+
+    // Calculate the product high limb inverse helper.
+    let inv_helper_val = if product_sign {
+        high
+    } else {
+        0xffff_ffff - high
+    };
+    row.product_high_limb_inv_helper = from_u32::<F>(inv_helper_val)
+        .try_inverse()
+        .unwrap_or_default();
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn generate_div_row<F: RichField>(row: &mut MulDiv<F>, op: Op, op1: u32, op2: u32) {
+    let dividend_full_range = sign_extend(row.inst.is_op1_signed.is_nonzero(), op1);
+    let divisor_full_range = sign_extend(row.inst.is_op2_signed.is_nonzero(), op2);
+
+    if divisor_full_range == 0 {
+        row.quotient_value = from_u32(0xFFFF_FFFF);
+        row.quotient_sign = if row.inst.is_op2_signed.is_nonzero() {
+            F::ONE
+        } else {
+            F::ZERO
+        };
+        row.remainder_value = from_u32(op1);
+        row.remainder_slack = F::ZERO;
+        row.remainder_sign = F::from_bool(dividend_full_range.is_negative());
+        row.skip_check_quotient_sign = F::ONE;
+    } else {
+        let quotient_full_range = if matches!(op, Op::SRA) {
+            dividend_full_range.div_euclid(divisor_full_range)
+        } else {
+            dividend_full_range / divisor_full_range
+        };
+        row.quotient_value = from_u32(quotient_full_range as u32);
+        row.quotient_sign = F::from_bool(quotient_full_range.is_negative());
+        row.skip_check_quotient_sign = F::from_bool(quotient_full_range == 0);
+        if dividend_full_range == i64::from(i32::MIN) && divisor_full_range == -1 {
+            // Special case for dividend == -2^31, divisor == -1:
+            // quotient_sign == 1 (quotient = -2^31).
+            row.skip_check_quotient_sign = F::ONE;
+            row.quotient_sign = F::ONE;
+        }
+        let remainder = dividend_full_range - quotient_full_range * divisor_full_range;
+        let remainder_abs = remainder.unsigned_abs();
+        row.remainder_value = from_u32(remainder as u32);
+        row.remainder_slack =
+            F::from_noncanonical_u64(divisor_full_range.unsigned_abs() - 1 - remainder_abs);
+        row.remainder_sign = F::from_bool(remainder.is_negative());
+    }
+    row.op2_value_inv = from_u32::<F>(op2).try_inverse().unwrap_or_default();
+}
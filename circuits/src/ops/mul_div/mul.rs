@@ -6,7 +6,7 @@
 
 use expr::Expr;
 
-use super::columns::CpuState;
+use super::columns::MulDiv;
 use crate::expr::ConstraintBuilder;
 
 /// Converts from a sign-bit to a multiplicative sign.
@@ -18,7 +18,7 @@ use crate::expr::ConstraintBuilder;
 pub fn bit_to_sign<P: Copy>(sign_bit: Expr<'_, P>) -> Expr<'_, P> { 1 - 2 * sign_bit }
 
 pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+    lv: &MulDiv<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
     let op1_abs = lv.op1_abs;
@@ -107,15 +107,15 @@ mod tests {
     use starky::prover::prove as prove_table;
     use starky::verifier::verify_stark_proof;
 
-    use crate::cpu::generation::generate_cpu_trace;
-    use crate::cpu::stark::CpuStark;
+    use crate::ops::mul_div::generate;
+    use crate::ops::mul_div::stark::MulDivStark;
     use crate::stark::mozak_stark::MozakStark;
     use crate::stark::utils::trace_rows_to_poly_values;
     use crate::test_utils::{fast_test_config, ProveAndVerify, C, D, F};
     #[allow(clippy::cast_sign_loss)]
     #[test]
     fn prove_mulhsu_example() {
-        type S = CpuStark<F, D>;
+        type S = MulDivStark<F, D>;
         let config = fast_test_config();
         let a = -2_147_451_028_i32;
         let b = 2_147_483_648_u32;
@@ -135,23 +135,23 @@ mod tests {
         let res = i64::from(a).wrapping_mul(i64::from(b));
         assert_eq!(record.executed[0].aux.dst_val, (res >> 32) as u32);
         let mut timing = TimingTree::new("mulhsu", log::Level::Debug);
-        let cpu_trace = timed!(timing, "generate_cpu_trace", generate_cpu_trace(&record));
+        let mul_div_trace = timed!(timing, "generate mul_div trace", generate(&record));
         let trace_poly_values = timed!(
             timing,
             "trace to poly",
-            trace_rows_to_poly_values(cpu_trace)
+            trace_rows_to_poly_values(mul_div_trace)
         );
         let stark = S::default();
 
         let proof = timed!(
             timing,
-            "cpu proof",
+            "mul_div proof",
             prove_table::<F, C, S, D>(stark, &config, trace_poly_values, &[], &mut timing,)
         );
         let proof = proof.unwrap();
         let verification_res = timed!(
             timing,
-            "cpu verification",
+            "mul_div verification",
             verify_stark_proof(stark, proof, &config)
         );
         verification_res.unwrap();
@@ -244,20 +244,20 @@ mod tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
         #[test]
-        fn prove_mul_cpu(a in u32_extra(), b in u32_extra()) {
-            prove_mul::<CpuStark<F, D>>(a, b)?;
+        fn prove_mul_mul_div(a in u32_extra(), b in u32_extra()) {
+            prove_mul::<MulDivStark<F, D>>(a, b)?;
         }
         #[test]
-        fn prove_mulhu_cpu(a in u32_extra(), b in u32_extra()) {
-            prove_mulhu::<CpuStark<F, D>>(a, b)?;
+        fn prove_mulhu_mul_div(a in u32_extra(), b in u32_extra()) {
+            prove_mulhu::<MulDivStark<F, D>>(a, b)?;
         }
         #[test]
-        fn prove_mulh_cpu(a in i32_extra(), b in i32_extra()) {
-            prove_mulh::<CpuStark<F, D>>(a, b)?;
+        fn prove_mulh_mul_div(a in i32_extra(), b in i32_extra()) {
+            prove_mulh::<MulDivStark<F, D>>(a, b)?;
         }
         #[test]
-        fn prove_mulhsu_cpu(a in i32_extra(), b in u32_extra()) {
-            prove_mulhsu::<CpuStark<F, D>>(a, b)?;
+        fn prove_mulhsu_mul_div(a in i32_extra(), b in u32_extra()) {
+            prove_mulhsu::<MulDivStark<F, D>>(a, b)?;
         }
 
     }
@@ -6,26 +6,31 @@
 
 use expr::Expr;
 
-use super::bitwise::and_gadget;
-use super::columns::CpuState;
+use super::columns::MulDiv;
 use crate::expr::ConstraintBuilder;
 
 pub(crate) fn constraints<'a, P: Copy>(
-    lv: &CpuState<Expr<'a, P>>,
+    lv: &MulDiv<Expr<'a, P>>,
     cb: &mut ConstraintBuilder<Expr<'a, P>>,
 ) {
-    let is_shift = lv.inst.ops.sll + lv.inst.ops.srl + lv.inst.ops.sra;
+    let is_shift = lv.inst.ops.shifts();
     // Check: multiplier is assigned as `2^(rs2 value & 0b1_111)`.
     // We only take lowest 5 bits of the rs2 for the shift amount.
     // This is following the RISC-V specification.
-    // Below we use the And gadget to calculate the shift amount, and then use
-    // Bitshift table to retrieve the corresponding power of 2, that we will assign
-    // to the multiplier.
-    let and_gadget = and_gadget(&lv.xor);
-    cb.always(is_shift * (and_gadget.input_a - 0b1_1111));
-    cb.always(is_shift * (and_gadget.input_b - lv.op2_value_raw - lv.inst.imm_value));
+    // The Bitshift table only contains amounts below 32 together with their
+    // power of 2, and `shift_amount_high` is range checked, so splitting the
+    // operand as below picks out exactly its lowest 5 bits.
+    cb.always(
+        is_shift
+            * (lv.op2_value_raw + lv.inst.imm_value
+                - lv.bitshift.amount
+                - lv.shift_amount_high * 32),
+    );
+    cb.always(is_shift * (lv.op2_value - lv.bitshift.multiplier));
 
-    cb.always(is_shift * (and_gadget.doubled_output - 2 * lv.bitshift.amount));
+    // All other operations use the sum of the operand register and the immediate,
+    // which can't overflow for the RISC-V M extension, as it has no immediates.
+    cb.always((1 - is_shift) * (lv.op2_value - lv.op2_value_raw - lv.inst.imm_value));
 }
 
 #[cfg(test)]
@@ -38,7 +43,7 @@ mod tests {
     use proptest::test_runner::TestCaseError;
     use proptest::{prop_assert_eq, proptest};
 
-    use crate::cpu::stark::CpuStark;
+    use crate::ops::mul_div::stark::MulDivStark;
     use crate::stark::mozak_stark::MozakStark;
     use crate::test_utils::{ProveAndVerify, D, F};
 
@@ -161,16 +166,16 @@ mod tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
         #[test]
-        fn prove_sll_cpu(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
-            prove_sll::<CpuStark<F, D>>(p, q, rs1, rs2, rd)?;
+        fn prove_sll_mul_div(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
+            prove_sll::<MulDivStark<F, D>>(p, q, rs1, rs2, rd)?;
         }
         #[test]
-        fn prove_srl_cpu(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
-            prove_srl::<CpuStark<F, D>>(p, q, rs1, rs2, rd)?;
+        fn prove_srl_mul_div(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
+            prove_srl::<MulDivStark<F, D>>(p, q, rs1, rs2, rd)?;
         }
         #[test]
-        fn prove_sra_cpu(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
-            prove_sra::<CpuStark<F, D>>(p, q, rs1, rs2, rd)?;
+        fn prove_sra_mul_div(p in u32_extra(), q in u32_extra(), rs1 in reg(), rs2 in reg(), rd in reg()) {
+            prove_sra::<MulDivStark<F, D>>(p, q, rs1, rs2, rd)?;
         }
     }
 
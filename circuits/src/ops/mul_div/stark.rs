@@ -0,0 +1,126 @@
+use std::marker::PhantomData;
+
+use expr::{Expr, ExprBuilder, StarkFrameTyped};
+use mozak_circuits_derive::StarkNameDisplay;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::evaluation_frame::StarkFrame;
+use starky::stark::Stark;
+
+use super::columns::MulDiv;
+use super::{div, mul, shift};
+use crate::columns_view::{HasNamedColumns, NumberOfColumns};
+use crate::expr::{build_ext, build_packed, ConstraintBuilder};
+use crate::unstark::NoColumns;
+
+/// Multiplication, division and shift instructions.
+#[derive(Copy, Clone, Default, StarkNameDisplay)]
+#[allow(clippy::module_name_repetitions)]
+pub struct MulDivStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F, const D: usize> HasNamedColumns for MulDivStark<F, D> {
+    type Columns = MulDiv<F>;
+}
+
+const COLUMNS: usize = MulDiv::<()>::NUMBER_OF_COLUMNS;
+const PUBLIC_INPUTS: usize = 0;
+
+/// Selectors are binary, and at most one of them is enabled per row.
+/// Padding rows have none of them enabled.
+fn binary_selectors<'a, P: Copy>(
+    lv: &MulDiv<Expr<'a, P>>,
+    cb: &mut ConstraintBuilder<Expr<'a, P>>,
+) {
+    lv.inst
+        .ops
+        .into_iter()
+        .for_each(|s| cb.always(s.is_binary()));
+    cb.always(lv.is_running().is_binary());
+}
+
+/// See `cpu::signed_comparison::signed_constraints`.
+fn signed_constraints<'a, P: Copy>(
+    lv: &MulDiv<Expr<'a, P>>,
+    cb: &mut ConstraintBuilder<Expr<'a, P>>,
+) {
+    cb.always(lv.op1_sign_bit.is_binary());
+    cb.always(lv.op2_sign_bit.is_binary());
+
+    // When op1 is not signed as per instruction semantics, op1_sign_bit must be 0.
+    cb.always((1 - lv.inst.is_op1_signed) * lv.op1_sign_bit);
+    // When op2 is not signed as per instruction semantics, op2_sign_bit must be 0.
+    cb.always((1 - lv.inst.is_op2_signed) * lv.op2_sign_bit);
+}
+
+fn generate_constraints<'a, T: Copy>(
+    vars: &StarkFrameTyped<MulDiv<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
+) -> ConstraintBuilder<Expr<'a, T>> {
+    let lv = &vars.local_values;
+    let mut constraints = ConstraintBuilder::default();
+
+    binary_selectors(lv, &mut constraints);
+    signed_constraints(lv, &mut constraints);
+    shift::constraints(lv, &mut constraints);
+    mul::constraints(lv, &mut constraints);
+    div::constraints(lv, &mut constraints);
+
+    constraints
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MulDivStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        constraint_consumer: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>, {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_packed(constraints, constraint_consumer);
+    }
+
+    fn constraint_degree(&self) -> usize { 3 }
+
+    fn eval_ext_circuit(
+        &self,
+        circuit_builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        constraint_consumer: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let expr_builder = ExprBuilder::default();
+        let constraints = generate_constraints(&expr_builder.to_typed_starkframe(vars));
+        build_ext(constraints, circuit_builder, constraint_consumer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+
+    use super::MulDivStark;
+    use crate::test_utils::{C, D, F};
+
+    type S = MulDivStark<F, D>;
+
+    #[test]
+    fn test_degree() -> Result<()> { test_stark_low_degree(S::default()) }
+
+    #[test]
+    fn test_circuit() -> Result<()> { test_stark_circuit_constraints::<F, C, S, D>(S::default()) }
+}
@@ -1,3 +1,4 @@
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::poseidon2::{ROUND_F_END, ROUND_P, WIDTH};
 
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
@@ -17,7 +18,7 @@ pub(crate) const STATE_SIZE_AFTER: usize = STATE_SIZE * (ROUNDS_F / 2);
 columns_view_impl!(Poseidon2State);
 make_col_map!(Poseidon2State);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct Poseidon2State<F> {
     pub is_exe: F,
     pub input: [F; STATE_SIZE],
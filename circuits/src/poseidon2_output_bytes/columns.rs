@@ -1,4 +1,5 @@
 use itertools::izip;
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::hash_types::{HashOut, RichField};
 use plonky2::plonk::config::GenericHashOut;
 
@@ -15,7 +16,7 @@ pub const BYTES_COUNT: usize = 32;
 columns_view_impl!(Poseidon2OutputBytes);
 make_col_map!(Poseidon2OutputBytes);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct Poseidon2OutputBytes<F> {
     pub is_executed: F,
     pub clk: F,
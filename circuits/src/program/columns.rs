@@ -1,4 +1,5 @@
 use itertools::izip;
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::hash_types::RichField;
 
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
@@ -11,7 +12,7 @@ use crate::stark::mozak_stark::{ProgramTable, TableWithTypedOutput};
 columns_view_impl!(ProgramRom);
 make_col_map!(ProgramRom);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 /// A Row of ROM generated from read-only memory
 pub struct ProgramRom<T> {
     // Design doc for CPU <> Program cross-table-lookup:
@@ -19,7 +20,7 @@ pub struct ProgramRom<T> {
     pub pc: T,
     /// `inst_data` include:
     /// - ops: This is an internal opcode, not the opcode from RISC-V
-    /// - `is_op1_signed` and `is_op2_signed`
+    /// - `is_op1_signed` and `is_op2_signed` (or `is_dst_signed` for loads)
     /// - `rs1_select`, `rs2_select`, and `rd_select`
     /// - `imm_value`
     pub inst_data: T,
@@ -42,7 +43,7 @@ impl<F: RichField> From<Instruction<F>> for ProgramRom<F> {
                 [
                     ascending_sum(inst.ops),
                     inst.is_op1_signed,
-                    inst.is_op2_signed,
+                    inst.is_op2_signed + inst.is_dst_signed,
                     inst.rs1_selected,
                     inst.rs2_selected,
                     inst.rd_selected,
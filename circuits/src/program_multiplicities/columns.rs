@@ -1,3 +1,5 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
 use crate::linear_combination_typed::ColumnWithTypedInput;
@@ -8,7 +10,7 @@ columns_view_impl!(ProgramMult);
 make_col_map!(ProgramMult);
 /// A Row of ROM generated from read-only memory
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct ProgramMult<T> {
     pub rom_row: ProgramRom<T>,
     pub mult_in_cpu: T,
@@ -11,26 +11,87 @@
 //! CTL to `combine`, since the procedure is preceded by commitment to trace
 //! polynomials already
 #![allow(clippy::module_name_repetitions)]
+use std::fmt::Debug;
+
+use anyhow::{ensure, Result};
 use itertools::{iproduct, Itertools};
 use plonky2::field::extension::Extendable;
 use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
-use plonky2::iop::target::Target;
+use plonky2::iop::target::{BoolTarget, Target};
+use plonky2::iop::witness::WitnessWrite;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::plonk_common::reduce_with_powers_circuit;
 
-use crate::cross_table_lookup::{partial_sums, CtlData, CtlZData};
-use crate::stark::mozak_stark::{all_kind, Table, TableKind, TableKindArray};
+use crate::cross_table_lookup::{partial_sums, Column, CtlData, CtlZData};
+use crate::stark::mozak_stark::{all_kind, TableKind, TableKindArray, TableWithTypedOutput};
 use crate::stark::permutation::challenge::{GrandProductChallenge, GrandProductChallengeSet};
 
 /// Specifies a Subtable with `table.columns` and `table.filter_column`
 /// which the prover wants to make public. We include `num_rows` since
 /// it cannot be computed from `table` alone.
+///
+/// `Row` is the type of the public rows, like `ProgramRom<Column>`, so that
+/// their values can be decoded into the same type, see
+/// [`MakePublic::decode`].
 #[derive(Clone, Debug)]
-pub struct PublicSubTable {
-    pub table: Table,
-    pub num_rows: usize,
+pub struct PublicSubTableWithTypedOutput<Row> {
+    pub table: TableWithTypedOutput<Row>,
+    /// `None` for a subtable of the rows of its table where the filter is 1,
+    /// whose number varies from proof to proof.  The recursive circuits allow
+    /// for one row per row of the table, and pad the subtable with absent
+    /// rows.  To tell them apart, the first column of such a subtable has to
+    /// be its filter.
+    pub num_rows: Option<usize>,
+}
+
+pub type PublicSubTable = PublicSubTableWithTypedOutput<Vec<Column>>;
+
+impl<Row: IntoIterator<Item = Column>> PublicSubTableWithTypedOutput<Row> {
+    #[must_use]
+    pub fn to_untyped_output(self) -> PublicSubTable {
+        PublicSubTable {
+            table: self.table.to_untyped_output(),
+            num_rows: self.num_rows,
+        }
+    }
+}
+
+/// A subtable that [`MozakStark`](crate::stark::mozak_stark::MozakStark)
+/// makes public.
+pub trait MakePublic {
+    type Row<T: Debug>: FromIterator<T> + IntoIterator<Item = T>;
+
+    fn public_sub_table_with_typed_output() -> PublicSubTableWithTypedOutput<Self::Row<Column>>;
+
+    #[must_use]
+    fn public_sub_table() -> PublicSubTable {
+        Self::public_sub_table_with_typed_output().to_untyped_output()
+    }
+
+    /// Decodes the values of the subtable into typed rows.  The values can be
+    /// field elements, as found in a proof, or the targets of a recursive
+    /// circuit.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a row does not have as many values as the subtable has
+    /// columns.
+    fn decode<T: Copy + Debug>(values: &[Vec<T>]) -> Result<Vec<Self::Row<T>>> {
+        let num_columns = Self::public_sub_table().table.columns.len();
+        values
+            .iter()
+            .map(|row| {
+                ensure!(
+                    row.len() == num_columns,
+                    "public sub table row has {} values instead of {num_columns}",
+                    row.len()
+                );
+                Ok(row.iter().copied().collect())
+            })
+            .collect()
+    }
 }
 /// Actual values, as field elements, of the entries
 /// of `PublicSubTable`
@@ -100,18 +161,61 @@ impl PublicSubTable {
         }
     }
 
+    /// Number of rows of the subtable, or the number of rows its targets make
+    /// room for if it varies, for a table of `1 << degree_bits` rows.
+    #[must_use]
+    pub fn num_rows(&self, degree_bits: usize) -> usize {
+        self.num_rows.unwrap_or(1 << degree_bits)
+    }
+
     /// Returns virtual targets corresponding to `PublicSubTableValues`
+    ///
+    /// For a subtable with a varying number of rows, the first column of each
+    /// row tells whether the row is present, and present rows come first.
     pub fn to_targets<F: RichField + Extendable<D>, const D: usize>(
         &self,
         builder: &mut CircuitBuilder<F, D>,
+        degree_bits: &TableKindArray<usize>,
     ) -> PublicSubTableValuesTarget {
-        (0..self.num_rows)
+        let targets = (0..self.num_rows(degree_bits[self.table.kind]))
             .map(|_| {
                 (0..self.table.columns.len())
                     .map(|_| builder.add_virtual_target())
                     .collect_vec()
             })
-            .collect_vec()
+            .collect_vec();
+        if self.num_rows.is_none() {
+            let zero = builder.zero();
+            for row in &targets {
+                builder.assert_bool(BoolTarget::new_unsafe(row[0]));
+            }
+            for (row, next_row) in targets.iter().tuple_windows() {
+                // A row can only be present if the one before it is.
+                let absent_after_present = builder.mul_sub(next_row[0], row[0], next_row[0]);
+                builder.connect(absent_after_present, zero);
+            }
+        }
+        targets
+    }
+
+    /// Sets the `targets` of the subtable to its `values`, padded with absent
+    /// rows of zeros if the number of rows varies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more `values` than `targets`.
+    pub fn set_targets<F: Field>(
+        witness: &mut impl WitnessWrite<F>,
+        targets: &PublicSubTableValuesTarget,
+        values: &PublicSubTableValues<F>,
+    ) {
+        assert!(values.len() <= targets.len(), "Too many public rows");
+        for (i, row_targets) in targets.iter().enumerate() {
+            let row = values.get(i);
+            for (j, &target) in row_targets.iter().enumerate() {
+                witness.set_target(target, row.map_or(F::ZERO, |row| row[j]));
+            }
+        }
     }
 }
 
@@ -169,17 +273,25 @@ pub fn reduce_public_sub_tables_values<F: Field>(
     })
 }
 
+/// Circuit version of [`reduce_public_sub_tables_values`] for a single
+/// subtable.  With `padded`, only the rows whose first column is 1 count.
 pub fn reduce_public_sub_table_targets<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     challenge: &GrandProductChallenge<Target>,
     targets: &PublicSubTableValuesTarget,
+    padded: bool,
 ) -> Target {
     let all_targets = targets
         .iter()
         .map(|row| {
             let mut combined = reduce_with_powers_circuit(builder, row, challenge.beta);
             combined = builder.add(combined, challenge.gamma);
-            builder.inverse(combined)
+            let inverse = builder.inverse(combined);
+            if padded {
+                builder.mul(row[0], inverse)
+            } else {
+                inverse
+            }
         })
         .collect_vec();
     builder.add_many(all_targets)
@@ -188,6 +300,7 @@ pub fn reduce_public_sub_table_targets<F: RichField + Extendable<D>, const D: us
 pub fn public_sub_table_values_and_reduced_targets<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     public_sub_tables: &[PublicSubTable],
+    degree_bits: &TableKindArray<usize>,
     ctl_challenges: &GrandProductChallengeSet<Target>,
 ) -> (
     TableKindArray<Vec<PublicSubTableValuesTarget>>,
@@ -195,7 +308,7 @@ pub fn public_sub_table_values_and_reduced_targets<F: RichField + Extendable<D>,
 ) {
     let mut public_sub_table_values_targets = TableKindArray::<Vec<_>>::default();
     for public_sub_table in public_sub_tables {
-        let targets = public_sub_table.to_targets(builder);
+        let targets = public_sub_table.to_targets(builder, degree_bits);
         public_sub_table_values_targets[public_sub_table.table.kind].push(targets);
     }
 
@@ -209,8 +322,14 @@ pub fn public_sub_table_values_and_reduced_targets<F: RichField + Extendable<D>,
             let targets = public_sub_table_values_targets_iter[public_sub_table.table.kind]
                 .next()
                 .unwrap();
-            reduced_public_sub_table_targets[public_sub_table.table.kind]
-                .push(reduce_public_sub_table_targets(builder, challenge, targets));
+            reduced_public_sub_table_targets[public_sub_table.table.kind].push(
+                reduce_public_sub_table_targets(
+                    builder,
+                    challenge,
+                    targets,
+                    public_sub_table.num_rows.is_none(),
+                ),
+            );
         }
     }
     (
@@ -218,3 +337,61 @@ pub fn public_sub_table_values_and_reduced_targets<F: RichField + Extendable<D>,
         reduced_public_sub_table_targets,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use mozak_runner::code;
+    use mozak_runner::instruction::{Args, Instruction, Op};
+
+    use super::*;
+    use crate::program::columns::{lookup_for_ctl, ProgramRom};
+    use crate::program::generation::generate_program_rom_trace;
+    use crate::stark::utils::trace_rows_to_poly_values;
+    use crate::test_utils::F;
+
+    /// Makes every row of the program ROM public.
+    struct PublicProgramRom;
+
+    impl MakePublic for PublicProgramRom {
+        type Row<T: Debug> = ProgramRom<T>;
+
+        fn public_sub_table_with_typed_output() -> PublicSubTableWithTypedOutput<Self::Row<Column>>
+        {
+            PublicSubTableWithTypedOutput {
+                table: lookup_for_ctl(),
+                num_rows: None,
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_values_into_typed_rows() {
+        let (program, _record) = code::execute(
+            [Instruction {
+                op: Op::ADD,
+                args: Args {
+                    rd: 5,
+                    rs1: 6,
+                    rs2: 7,
+                    ..Args::default()
+                },
+            }],
+            &[],
+            &[(6, 100), (7, 200)],
+        );
+        let rows = generate_program_rom_trace::<F>(&program);
+        let mut trace = TableKindArray::<Vec<PolynomialValues<F>>>::default();
+        trace[TableKind::Program] = trace_rows_to_poly_values(rows.clone());
+
+        let values = PublicProgramRom::public_sub_table().get_values(&trace);
+        assert_eq!(PublicProgramRom::decode(&values).unwrap(), rows);
+
+        // Rows with missing or extra values are rejected.
+        let mut short = values.clone();
+        short[0].pop();
+        assert!(PublicProgramRom::decode(&short).is_err());
+        let mut long = values;
+        long[0].push(F::ZERO);
+        assert!(PublicProgramRom::decode(&long).is_err());
+    }
+}
@@ -5,8 +5,14 @@ use plonky2::hash::hash_types::RichField;
 
 use crate::cpu::columns::CpuState;
 use crate::memory::columns::Memory;
+use crate::memory_fullword::columns::FullWordMemory;
+use crate::memory_halfword::columns::HalfWordMemory;
 use crate::ops::add::columns::Add;
 use crate::ops::blt_taken::columns::BltTaken;
+use crate::ops::branches::columns::Branches;
+use crate::ops::jalr::columns::Jalr;
+use crate::ops::load_store::columns::LoadStore;
+use crate::ops::mul_div::columns::MulDiv;
 use crate::rangecheck::columns::RangeCheckColumnsView;
 use crate::register::general::columns::Register;
 use crate::stark::mozak_stark::{Lookups, RangecheckTable, Table, TableKind};
@@ -49,11 +55,18 @@ where
 /// 2. trace width does not match the number of columns,
 /// 3. attempting to range check tuples instead of single values.
 #[must_use]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn generate_rangecheck_trace<F: RichField>(
     cpu_trace: &[CpuState<F>],
     add_trace: &[Add<F>],
     blt_taken_trace: &[BltTaken<F>],
+    mul_div_trace: &[MulDiv<F>],
+    branches_trace: &[Branches<F>],
+    jalr_trace: &[Jalr<F>],
+    load_store_trace: &[LoadStore<F>],
     memory_trace: &[Memory<F>],
+    halfword_memory_trace: &[HalfWordMemory<F>],
+    fullword_memory_trace: &[FullWordMemory<F>],
     register_trace: &[Register<F>],
 ) -> Vec<RangeCheckColumnsView<F>> {
     pad_trace_with_default(
@@ -64,9 +77,17 @@ pub(crate) fn generate_rangecheck_trace<F: RichField>(
                 match looking_table.kind {
                     TableKind::Cpu => extract_with_mul(cpu_trace, &looking_table),
                     TableKind::Memory => extract_with_mul(memory_trace, &looking_table),
+                    TableKind::HalfWordMemory =>
+                        extract_with_mul(halfword_memory_trace, &looking_table),
+                    TableKind::FullWordMemory =>
+                        extract_with_mul(fullword_memory_trace, &looking_table),
                     TableKind::Register => extract_with_mul(register_trace, &looking_table),
                     TableKind::Add => extract_with_mul(add_trace, &looking_table),
                     TableKind::BltTaken => extract_with_mul(blt_taken_trace, &looking_table),
+                    TableKind::MulDiv => extract_with_mul(mul_div_trace, &looking_table),
+                    TableKind::Branches => extract_with_mul(branches_trace, &looking_table),
+                    TableKind::Jalr => extract_with_mul(jalr_trace, &looking_table),
+                    TableKind::LoadStore => extract_with_mul(load_store_trace, &looking_table),
                     // We are trying to build the RangeCheck table, so we have to ignore it here.
                     TableKind::RangeCheck => vec![],
                     other => unimplemented!("Can't range check {other:#?} tables"),
@@ -133,6 +154,11 @@ mod tests {
         let cpu_rows = generate_cpu_trace::<F>(&record);
         let add_rows = ops::add::generate(&record);
         let blt_rows = blt_taken::generate(&record);
+        let mul_div_rows = ops::mul_div::generate(&record);
+        let bitwise_rows = ops::bitwise::generate(&record);
+        let branches_rows = ops::branches::generate(&record);
+        let jalr_rows = ops::jalr::generate(&record);
+        let load_store_rows = ops::load_store::generate(&record);
 
         let memory_init = generate_memory_init_trace(&program);
         let memory_zeroinit_rows = generate_memory_zero_init_trace(&record.executed, &program);
@@ -170,6 +196,11 @@ mod tests {
             &cpu_rows,
             &add_rows,
             &blt_rows,
+            &mul_div_rows,
+            &bitwise_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &poseidon2_sponge_trace,
             &private_tape_rows,
             &public_tape_rows,
@@ -184,7 +215,13 @@ mod tests {
             &cpu_rows,
             &add_rows,
             &blt_rows,
+            &mul_div_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &memory_rows,
+            &halfword_memory,
+            &fullword_memory,
             &register_rows,
         );
         assert_eq!(
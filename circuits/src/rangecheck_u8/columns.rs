@@ -1,10 +1,12 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
 use crate::rangecheck::columns::RangeCheckCtl;
 use crate::stark::mozak_stark::{RangeCheckU8Table, TableWithTypedOutput};
 
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct RangeCheckU8<T> {
     /// The u8 value to be range checked
     pub value: T,
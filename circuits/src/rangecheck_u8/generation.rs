@@ -81,6 +81,11 @@ mod tests {
         let cpu_rows = generate_cpu_trace::<F>(&record);
         let add_rows = ops::add::generate(&record);
         let blt_rows = ops::blt_taken::generate(&record);
+        let mul_div_rows = ops::mul_div::generate(&record);
+        let bitwise_rows = ops::bitwise::generate(&record);
+        let branches_rows = ops::branches::generate(&record);
+        let jalr_rows = ops::jalr::generate(&record);
+        let load_store_rows = ops::load_store::generate(&record);
 
         let memory_init = generate_memory_init_trace(&program);
         let memory_zeroinit_rows = generate_memory_zero_init_trace(&record.executed, &program);
@@ -118,6 +123,11 @@ mod tests {
             &cpu_rows,
             &add_rows,
             &blt_rows,
+            &mul_div_rows,
+            &bitwise_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &poseidon2_sponge_trace,
             &private_tape,
             &public_tape,
@@ -132,7 +142,13 @@ mod tests {
             &cpu_rows,
             &add_rows,
             &blt_rows,
+            &mul_div_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &memory_rows,
+            &halfword_memory,
+            &fullword_memory,
             &register_rows,
         );
 
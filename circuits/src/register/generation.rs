@@ -88,6 +88,11 @@ pub fn generate_register_trace<F: RichField>(
     cpu_trace: &[CpuState<F>],
     add_trace: &[ops::add::columns::Add<F>],
     blt_trace: &[ops::blt_taken::columns::BltTaken<F>],
+    mul_div_trace: &[ops::mul_div::columns::MulDiv<F>],
+    bitwise_trace: &[ops::bitwise::columns::Bitwise<F>],
+    branches_trace: &[ops::branches::columns::Branches<F>],
+    jalr_trace: &[ops::jalr::columns::Jalr<F>],
+    load_store_trace: &[ops::load_store::columns::LoadStore<F>],
     poseidon2_sponge: &[Poseidon2Sponge<F>],
     mem_private: &[StorageDevice<F>],
     mem_public: &[StorageDevice<F>],
@@ -110,6 +115,11 @@ pub fn generate_register_trace<F: RichField>(
             TableKind::Cpu => extract(cpu_trace, &looking_table),
             TableKind::Add => extract(add_trace, &looking_table),
             TableKind::BltTaken => extract(blt_trace, &looking_table),
+            TableKind::MulDiv => extract(mul_div_trace, &looking_table),
+            TableKind::Bitwise => extract(bitwise_trace, &looking_table),
+            TableKind::Branches => extract(branches_trace, &looking_table),
+            TableKind::Jalr => extract(jalr_trace, &looking_table),
+            TableKind::LoadStore => extract(load_store_trace, &looking_table),
             TableKind::StorageDevicePrivate => extract(mem_private, &looking_table),
             TableKind::StorageDevicePublic => extract(mem_public, &looking_table),
             TableKind::CallTape => extract(mem_call_tape, &looking_table),
@@ -224,6 +234,11 @@ mod tests {
         let cpu_rows = generate_cpu_trace::<F>(&record);
         let add_rows = ops::add::generate(&record);
         let blt_rows = ops::blt_taken::generate(&record);
+        let mul_div_rows = ops::mul_div::generate(&record);
+        let bitwise_rows = ops::bitwise::generate(&record);
+        let branches_rows = ops::branches::generate(&record);
+        let jalr_rows = ops::jalr::generate(&record);
+        let load_store_rows = ops::load_store::generate(&record);
         let private_tape = generate_private_tape_trace(&record.executed);
         let public_tape = generate_public_tape_trace(&record.executed);
         let call_tape = generate_call_tape_trace(&record.executed);
@@ -240,6 +255,11 @@ mod tests {
             &cpu_rows,
             &add_rows,
             &blt_rows,
+            &mul_div_rows,
+            &bitwise_rows,
+            &branches_rows,
+            &jalr_rows,
+            &load_store_rows,
             &poseidon2_sponge_trace,
             &private_tape,
             &public_tape,
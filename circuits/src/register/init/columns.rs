@@ -1,3 +1,5 @@
+use mozak_circuits_derive::ColumnNames;
+
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
 use crate::linear_combination_typed::ColumnWithTypedInput;
@@ -7,7 +9,7 @@ use crate::stark::mozak_stark::{RegisterInitTable, TableWithTypedOutput};
 columns_view_impl!(RegisterInit);
 make_col_map!(RegisterInit);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct RegisterInit<T> {
     /// The 'address' that indexes into 1 of our 32 registers. Should only
     /// take values 0-31, so this column should be a running sum
@@ -1,3 +1,4 @@
+use mozak_circuits_derive::ColumnNames;
 use plonky2::hash::hash_types::RichField;
 
 use crate::columns_view::{columns_view_impl, make_col_map};
@@ -10,7 +11,7 @@ use crate::stark::mozak_stark::{RegisterZeroWriteTable, TableWithTypedOutput};
 columns_view_impl!(RegisterZeroWrite);
 make_col_map!(RegisterZeroWrite);
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 /// The columns of the register 0 table.
 /// Register 0 is a special register that is always 0.
 /// Thus we don't need neither a value column nor a register address column.
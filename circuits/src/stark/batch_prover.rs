@@ -882,14 +882,27 @@ pub(crate) fn batch_reduction_arity_bits(
 
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
     use mozak_runner::code;
+    use mozak_runner::code::execute_code_with_ro_memory;
+    use mozak_runner::decode::ECALL;
     use mozak_runner::instruction::{Args, Instruction, Op};
+    use mozak_runner::state::RawTapes;
+    use mozak_sdk::core::ecall;
+    use mozak_sdk::core::reg_abi::{REG_A0, REG_A1, REG_A2};
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
     use plonky2::util::timing::TimingTree;
 
+    use crate::generation::generate_traces;
+    use crate::memoryinit::generation::generate_elf_memory_init_trace;
+    use crate::program::generation::generate_program_rom_trace;
     use crate::stark::batch_prover::{batch_prove, batch_reduction_arity_bits};
     use crate::stark::batch_verifier::batch_verify_proof;
-    use crate::stark::mozak_stark::{MozakStark, PublicInputs, PUBLIC_TABLE_KINDS};
+    use crate::stark::mozak_stark::{
+        MozakStark, PublicElfMemoryInit, PublicInputs, PublicProgramRom, PublicTable, PublicTape,
+        TableKind, PUBLIC_TABLE_KINDS,
+    };
+    use crate::storage_device::columns::public_tape_bytes;
     use crate::test_utils::fast_test_config;
     use crate::utils::from_u32;
 
@@ -967,4 +980,56 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn batch_proof_decodes_into_typed_rows() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let public_tape = vec![0xDE, 0xAD, 0xBE];
+        let (program, record) = execute_code_with_ro_memory(
+            [ECALL],
+            &[],
+            &[(1024, 0), (1025, 0), (1026, 0)],
+            &[(REG_A0, ecall::PUBLIC_TAPE), (REG_A1, 1024), (REG_A2, 3)],
+            RawTapes {
+                public_tape: public_tape.clone(),
+                ..Default::default()
+            },
+        );
+        let config = fast_test_config();
+        let stark: MozakStark<F, D> = MozakStark::default();
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+
+        let (proof, degree_bits) = batch_prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &PUBLIC_TABLE_KINDS,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        let public_tape_rows =
+            stark.public_sub_table_rows::<PublicTape, _>(&proof.public_sub_table_values)?;
+        assert_eq!(public_tape_bytes(&public_tape_rows)?, public_tape);
+        batch_verify_proof(&stark, &PUBLIC_TABLE_KINDS, proof, &config, &degree_bits)?;
+
+        // The public tables are committed to as a whole, so their rows are
+        // those of the traces.
+        let traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+        assert_eq!(
+            PublicProgramRom::decode(&traces[PublicProgramRom::KIND])?,
+            generate_program_rom_trace(&program)
+        );
+        assert_eq!(
+            PublicElfMemoryInit::decode(&traces[PublicElfMemoryInit::KIND])?,
+            generate_elf_memory_init_trace(&program)
+        );
+        assert!(PublicProgramRom::decode(&traces[TableKind::Cpu]).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,373 @@
+//! On-disk cache of built plonky2 circuits.
+//!
+//! Building the recursive verifier circuits is a large fixed cost of every
+//! recursive proof.  A [`CircuitCache`] stores their [`CircuitData`],
+//! serialized with plonky2's default gate and generator serializers, so that
+//! later runs can skip [`CircuitBuilder::build`].
+//!
+//! Caching is opt-in: the circuit functions only go through a cache inside
+//! [`with_circuit_cache`].
+//!
+//! Targets are cheap to lay out, so the circuit functions still add all their
+//! targets to a fresh builder; only the build itself is replaced by a cache
+//! lookup.  Entries are keyed by the name of the circuit, its parameters, a
+//! fingerprint of the running executable and the number of gates and public
+//! inputs of the builder.  A circuit is a deterministic function of the code
+//! that lays it out and of its parameters, so an entry is only ever picked up
+//! by the binary that built it.
+//!
+//! Each entry records the exact number of gates of the builder it was built
+//! from and the digest of the built circuit.  Loaded circuits are checked
+//! against both, and against the config and public inputs of the builder,
+//! before they are used.  Still, the cache is only meant for proving: anything
+//! that verifies proofs should build its circuits itself.
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use log::{debug, warn};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitData;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut};
+use plonky2::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+/// A directory of serialized [`CircuitData`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitCache {
+    dir: PathBuf,
+}
+
+impl CircuitCache {
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self { Self { dir: dir.into() } }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    /// The file that holds the circuit `name` with `params`, as laid out in
+    /// `builder` by the executable with `fingerprint`.
+    fn path<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        fingerprint: u64,
+        name: &str,
+        params: &dyn Debug,
+        builder: &CircuitBuilder<F, D>,
+    ) -> PathBuf {
+        let key = format!(
+            "{name} {fingerprint:016x} {} {} {params:?}",
+            builder.num_gates(),
+            builder.num_public_inputs(),
+        );
+        self.dir
+            .join(format!("{name}-{:016x}.bin", fnv1a(key.as_bytes())))
+    }
+
+    /// Returns the circuit built from `builder`, loading it from the cache if
+    /// it is there, and storing it otherwise.
+    ///
+    /// `params` has to cover everything that the layout of `builder` depends
+    /// on besides the code.  Failures to read or write the cache are logged
+    /// and fall back to building the circuit, as does an executable that
+    /// cannot be read to fingerprint it.
+    #[must_use]
+    pub fn build<F, C, const D: usize>(
+        &self,
+        name: &str,
+        params: &dyn Debug,
+        builder: CircuitBuilder<F, D>,
+    ) -> CircuitData<F, C, D>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        C::Hasher: AlgebraicHasher<F>, {
+        let Some(fingerprint) = executable_fingerprint() else {
+            warn!("not caching circuit {name}: cannot fingerprint the executable");
+            return builder.build::<C>();
+        };
+        let path = self.path(fingerprint, name, params, &builder);
+        let gate_serializer = DefaultGateSerializer;
+        let generator_serializer = DefaultGeneratorSerializer::<C, D> {
+            _phantom: PhantomData,
+        };
+
+        if let Ok(bytes) = fs::read(&path) {
+            let entry = Entry::decode(&bytes).and_then(|(entry, circuit)| {
+                Some((
+                    entry,
+                    CircuitData::from_bytes(circuit, &gate_serializer, &generator_serializer)
+                        .ok()?,
+                ))
+            });
+            match entry {
+                Some((entry, circuit)) if entry.matches(&circuit, &builder) => {
+                    debug!("loaded circuit {name} from {}", path.display());
+                    return circuit;
+                }
+                Some(_) => warn!("ignoring mismatched circuit cache entry {}", path.display()),
+                None => warn!("ignoring corrupt circuit cache entry {}", path.display()),
+            }
+        }
+
+        let num_gates = builder.num_gates();
+        let circuit = builder.build::<C>();
+        let entry = Entry {
+            num_gates,
+            circuit_digest: circuit.verifier_only.circuit_digest.to_bytes(),
+        };
+        let stored = circuit
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(|_| anyhow!("circuit serialization failed"))
+            .and_then(|bytes| {
+                fs::create_dir_all(&self.dir)?;
+                // Write to a temporary file first, so that concurrent runs
+                // never see a partially written entry.
+                let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+                fs::write(&tmp, entry.encode(&bytes))?;
+                fs::rename(&tmp, &path)?;
+                Ok(())
+            });
+        match stored {
+            Ok(()) => debug!("stored circuit {name} in {}", path.display()),
+            Err(e) => warn!("could not cache circuit {name} in {}: {e}", path.display()),
+        }
+        circuit
+    }
+}
+
+thread_local! {
+    static CURRENT_CACHE: RefCell<Option<CircuitCache>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, with the circuits that it builds on this thread going through
+/// `cache` if there is one.
+#[must_use]
+pub fn with_circuit_cache<R>(cache: Option<&CircuitCache>, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous cache, even if `f` panics.
+    struct Restore(Option<CircuitCache>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) { CURRENT_CACHE.with(|current| current.replace(self.0.take())); }
+    }
+
+    let _restore = Restore(CURRENT_CACHE.with(|current| current.replace(cache.cloned())));
+    f()
+}
+
+/// Builds `builder`, going through the cache of the enclosing
+/// [`with_circuit_cache`] if there is one.
+#[must_use]
+pub fn build_circuit<F, C, const D: usize>(
+    name: &str,
+    params: &dyn Debug,
+    builder: CircuitBuilder<F, D>,
+) -> CircuitData<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>, {
+    match CURRENT_CACHE.with(|current| current.borrow().clone()) {
+        Some(cache) => cache.build(name, params, builder),
+        None => builder.build::<C>(),
+    }
+}
+
+/// The header of a cache entry, which is followed by the serialized circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    /// The number of gates of the builder, before padding.
+    num_gates: usize,
+    /// The digest of the built circuit.
+    circuit_digest: Vec<u8>,
+}
+
+impl Entry {
+    /// Prepends the entry to the serialized circuit `bytes`.
+    fn encode(&self, bytes: &[u8]) -> Vec<u8> {
+        [
+            &(self.num_gates as u64).to_le_bytes()[..],
+            &(self.circuit_digest.len() as u64).to_le_bytes(),
+            &self.circuit_digest,
+            bytes,
+        ]
+        .concat()
+    }
+
+    /// Splits `bytes` into the entry and the serialized circuit.
+    fn decode(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let read_u64 = |bytes: &[u8]| -> Option<usize> {
+            usize::try_from(u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?)).ok()
+        };
+        let num_gates = read_u64(bytes)?;
+        let digest_len = read_u64(bytes.get(8..)?)?;
+        let digest_end = 16_usize.checked_add(digest_len)?;
+        Some((
+            Self {
+                num_gates,
+                circuit_digest: bytes.get(16..digest_end)?.to_vec(),
+            },
+            bytes.get(digest_end..)?,
+        ))
+    }
+
+    /// Whether `circuit` is the one this entry was stored for, and was built
+    /// from a builder with the gates, config and public inputs of `builder`.
+    fn matches<F, C, const D: usize>(
+        &self,
+        circuit: &CircuitData<F, C, D>,
+        builder: &CircuitBuilder<F, D>,
+    ) -> bool
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>, {
+        self.num_gates == builder.num_gates()
+            && self.circuit_digest == circuit.verifier_only.circuit_digest.to_bytes()
+            && circuit.common.config == builder.config
+            && circuit.common.num_public_inputs == builder.num_public_inputs()
+            && circuit.common.degree() >= builder.num_gates()
+    }
+}
+
+/// A fingerprint of the running executable, or `None` if it cannot be read.
+fn executable_fingerprint() -> Option<u64> {
+    static FINGERPRINT: OnceLock<Option<u64>> = OnceLock::new();
+    *FINGERPRINT.get_or_init(|| {
+        let bytes = fs::read(std::env::current_exe().ok()?).ok()?;
+        Some(fnv1a(&bytes))
+    })
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Field;
+    use plonky2::gates::noop::NoopGate;
+    use plonky2::iop::target::Target;
+    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+    use plonky2::plonk::circuit_data::CircuitConfig;
+
+    use super::*;
+    use crate::test_utils::{C, D, F};
+
+    fn builder(num_gates: usize) -> (CircuitBuilder<F, D>, Target) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        while builder.num_gates() < num_gates {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        (builder, x)
+    }
+
+    #[test]
+    fn cached_circuit_proves_like_a_fresh_one() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("mozak-circuit-cache-{}", std::process::id()));
+        let cache = CircuitCache::new(&dir);
+
+        let (fresh_builder, _) = builder(8);
+        let fresh: CircuitData<F, C, D> = cache.build("square", &(), fresh_builder);
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+
+        let (cached_builder, x) = builder(8);
+        let cached: CircuitData<F, C, D> = cache.build("square", &(), cached_builder);
+        assert_eq!(fresh.verifier_only, cached.verifier_only);
+        assert_eq!(fresh.common, cached.common);
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, F::from_canonical_u64(3));
+        let proof = cached.prove(inputs)?;
+        assert_eq!(proof.public_inputs[1], F::from_canonical_u64(9));
+        fresh.verify(proof)?;
+
+        // A circuit of a different shape gets its own entry.
+        let (other_builder, _) = builder(100);
+        let _: CircuitData<F, C, D> = cache.build("square", &(), other_builder);
+        assert_eq!(fs::read_dir(&dir)?.count(), 2);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn mismatched_entry_is_rebuilt() -> anyhow::Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("mozak-circuit-mismatch-{}", std::process::id()));
+        let cache = CircuitCache::new(&dir);
+        let fingerprint = executable_fingerprint().expect("test executable is readable");
+
+        // Plant the entry of a small circuit where a bigger one is expected.
+        let (small_builder, _) = builder(8);
+        let small: CircuitData<F, C, D> = cache.build("square", &(), small_builder);
+        let (big_builder, _) = builder(100);
+        let big_path = cache.path(fingerprint, "square", &(), &big_builder);
+        fs::write(
+            &big_path,
+            fs::read(cache.path(fingerprint, "square", &(), &builder(8).0))?,
+        )?;
+
+        let big: CircuitData<F, C, D> = cache.build("square", &(), big_builder);
+        assert_ne!(big.verifier_only, small.verifier_only);
+        assert!(big.common.degree() >= 100);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn entry_with_wrong_digest_is_rebuilt() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("mozak-circuit-digest-{}", std::process::id()));
+        let cache = CircuitCache::new(&dir);
+        let fingerprint = executable_fingerprint().expect("test executable is readable");
+
+        let fresh: CircuitData<F, C, D> = cache.build("square", &(), builder(8).0);
+        let path = cache.path(fingerprint, "square", &(), &builder(8).0);
+        let stored = fs::read(&path)?;
+        let (entry, circuit) = Entry::decode(&stored).expect("entry is well-formed");
+        assert_eq!(entry.num_gates, builder(8).0.num_gates());
+        let tampered = Entry {
+            circuit_digest: vec![0; entry.circuit_digest.len()],
+            ..entry
+        };
+        fs::write(&path, tampered.encode(circuit))?;
+
+        let rebuilt: CircuitData<F, C, D> = cache.build("square", &(), builder(8).0);
+        assert_eq!(rebuilt.verifier_only, fresh.verifier_only);
+        assert_eq!(fs::read(&path)?, stored);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn circuits_are_only_cached_inside_with_circuit_cache() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("mozak-circuit-scope-{}", std::process::id()));
+        let cache = CircuitCache::new(&dir);
+
+        let _: CircuitData<F, C, D> = build_circuit("square", &(), builder(8).0);
+        assert!(!dir.exists());
+
+        let _: CircuitData<F, C, D> =
+            with_circuit_cache(Some(&cache), || build_circuit("square", &(), builder(8).0));
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+
+        // The cache does not outlive the closure.
+        let _: CircuitData<F, C, D> = build_circuit("square", &(), builder(100).0);
+        assert_eq!(fs::read_dir(&dir)?.count(), 1);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Degree analysis of the constraints of our Starks.
+//!
+//! Constraints whose degree exceeds [`Stark::constraint_degree`] are otherwise
+//! only discovered when proving, and then without pointing at the culprit.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use starky::constraint_consumer::ConstraintConsumer;
+use starky::evaluation_frame::StarkEvaluationFrame;
+use starky::stark::Stark;
+
+use super::mozak_stark::{all_starks, MozakStark, TableKindArray};
+use crate::expr::{constraint_degrees, ConstraintDegree, ConstraintType};
+
+/// The degrees of the constraints of a single Stark.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeReport {
+    pub table: String,
+    /// The degree the Stark declares with [`Stark::constraint_degree`].
+    pub constraint_degree: usize,
+    /// Every constraint, with its degree.  Empty if the Stark does not build
+    /// its constraints with a `ConstraintBuilder`.
+    pub constraints: Vec<ConstraintDegree>,
+}
+
+impl DegreeReport {
+    /// The highest degree of any constraint.
+    #[must_use]
+    pub fn max_degree(&self) -> usize {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.degree)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The number of constraints of every type.
+    #[must_use]
+    pub fn constraints_by_type(&self) -> BTreeMap<ConstraintType, usize> {
+        let mut counts = BTreeMap::new();
+        for constraint in &self.constraints {
+            *counts.entry(constraint.constraint_type).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The constraints whose degree exceeds the declared constraint degree.
+    #[must_use]
+    pub fn offending(&self) -> Vec<ConstraintDegree> {
+        self.constraints
+            .iter()
+            .filter(|constraint| constraint.degree > self.constraint_degree)
+            .copied()
+            .collect()
+    }
+}
+
+impl Display for DegreeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: max degree {} of declared {}",
+            self.table,
+            self.max_degree(),
+            self.constraint_degree
+        )?;
+        if self.constraints.is_empty() {
+            writeln!(f, "  (constraints not built with ConstraintBuilder)")?;
+        }
+        for (constraint_type, count) in self.constraints_by_type() {
+            writeln!(f, "  {count} {constraint_type:?} constraint(s)")?;
+        }
+        for ConstraintDegree {
+            constraint_type,
+            location,
+            degree,
+        } in self.offending()
+        {
+            writeln!(
+                f,
+                "  {constraint_type:?} constraint at {location} has degree {degree}"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports the degrees of the constraints of `stark`.
+#[must_use]
+pub fn degree_report<F, const D: usize, S>(stark: &S) -> DegreeReport
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D> + Display, {
+    // The degrees only depend on the shape of the constraints, so any row
+    // will do.
+    let row = vec![F::ZERO; S::COLUMNS];
+    let public_inputs = vec![F::ZERO; S::PUBLIC_INPUTS];
+    let vars = StarkEvaluationFrame::from_values(&row, &row, &public_inputs);
+    let mut consumer = ConstraintConsumer::new_debug_api(true, false);
+    let constraints = constraint_degrees(|| stark.eval_packed_generic(&vars, &mut consumer));
+    DegreeReport {
+        table: stark.to_string(),
+        constraint_degree: stark.constraint_degree(),
+        constraints,
+    }
+}
+
+/// Reports the degrees of the constraints of every table of `mozak_stark`.
+#[must_use]
+pub fn degree_reports<F: RichField + Extendable<D>, const D: usize>(
+    mozak_stark: &MozakStark<F, D>,
+) -> TableKindArray<DegreeReport> {
+    all_starks!(mozak_stark, |stark, _kind| degree_report::<F, D, _>(stark))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::add::stark::AddStark;
+    use crate::test_utils::{D, F};
+
+    #[test]
+    fn reports_degrees_and_counts() {
+        let report = degree_report::<F, D, _>(&AddStark::<F, D>::default());
+        assert_eq!(report.max_degree(), 2);
+        assert_eq!(
+            report.constraints_by_type(),
+            BTreeMap::from([(ConstraintType::Always, 1)])
+        );
+        assert!(report.offending().is_empty());
+
+        let strict = DegreeReport {
+            constraint_degree: 1,
+            ..report
+        };
+        assert_eq!(strict.offending().len(), 1);
+        assert!(strict.to_string().contains("ops/add/stark.rs"));
+    }
+
+    #[test]
+    fn all_tables_stay_within_constraint_degree() {
+        let reports = degree_reports(&MozakStark::<F, D>::default());
+        for report in &reports.0 {
+            assert!(report.offending().is_empty(), "{report}");
+        }
+    }
+}
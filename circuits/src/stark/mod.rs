@@ -4,6 +4,9 @@
 
 pub mod batch_prover;
 pub mod batch_verifier;
+pub mod circuit_cache;
+#[cfg(any(feature = "debug-constraints", test))]
+pub mod degrees;
 #[allow(clippy::module_name_repetitions)]
 pub mod mozak_stark;
 pub mod permutation;
@@ -11,5 +14,8 @@ pub mod poly;
 pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
+pub mod security;
+pub mod serialization;
 pub mod utils;
 pub mod verifier;
+pub mod wiring;
@@ -1,11 +1,15 @@
 use std::array::from_fn;
+use std::fmt::Debug;
 use std::ops::{Index, IndexMut, Neg};
 extern crate serde;
 extern crate serde_json;
+use anyhow::{anyhow, ensure, Result};
 use cpu::columns::CpuState;
 use itertools::{chain, izip};
 use mozak_circuits_derive::StarkSet;
+use mozak_runner::state::AlignmentPolicy;
 use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 #[allow(clippy::wildcard_imports)]
@@ -16,7 +20,7 @@ use serde_big_array::BigArray;
 
 use crate::bitshift::columns::{Bitshift, BitshiftView};
 use crate::bitshift::stark::BitshiftStark;
-use crate::columns_view::columns_view_impl;
+use crate::columns_view::{columns_view_impl, NumberOfColumns};
 use crate::cpu::stark::CpuStark;
 use crate::cpu_skeleton::columns::{CpuSkeleton, CpuSkeletonCtl};
 use crate::cpu_skeleton::stark::CpuSkeletonStark;
@@ -35,9 +39,19 @@ use crate::memoryinit::columns::{MemoryInit, MemoryInitCtl};
 use crate::memoryinit::stark::MemoryInitStark;
 use crate::ops::add::columns::Add;
 use crate::ops::add::stark::AddStark;
+use crate::ops::bitwise::columns::Bitwise;
+use crate::ops::bitwise::stark::BitwiseStark;
 use crate::ops::blt_taken::columns::BltTaken;
 use crate::ops::blt_taken::stark::BltTakenStark;
-use crate::ops::{add, blt_taken};
+use crate::ops::branches::columns::Branches;
+use crate::ops::branches::stark::BranchesStark;
+use crate::ops::jalr::columns::Jalr;
+use crate::ops::jalr::stark::JalrStark;
+use crate::ops::load_store::columns::LoadStore;
+use crate::ops::load_store::stark::LoadStoreStark;
+use crate::ops::mul_div::columns::MulDiv;
+use crate::ops::mul_div::stark::MulDivStark;
+use crate::ops::{add, bitwise, blt_taken, branches, jalr, load_store, mul_div};
 use crate::poseidon2::columns::{Poseidon2State, Poseidon2StateCtl};
 use crate::poseidon2::stark::Poseidon2_12Stark;
 use crate::poseidon2_output_bytes::columns::{Poseidon2OutputBytes, Poseidon2OutputBytesCtl};
@@ -48,7 +62,7 @@ use crate::program::columns::ProgramRom;
 use crate::program::stark::ProgramStark;
 use crate::program_multiplicities::columns::ProgramMult;
 use crate::program_multiplicities::stark::ProgramMultStark;
-use crate::public_sub_table::PublicSubTable;
+use crate::public_sub_table::{MakePublic, PublicSubTable, PublicSubTableWithTypedOutput};
 use crate::rangecheck::columns::{rangecheck_looking, RangeCheckColumnsView, RangeCheckCtl};
 use crate::rangecheck::stark::RangeCheckStark;
 use crate::rangecheck_u8::columns::RangeCheckU8;
@@ -62,9 +76,10 @@ use crate::register::zero_read::stark::RegisterZeroReadStark;
 use crate::register::zero_write::columns::RegisterZeroWrite;
 use crate::register::zero_write::stark::RegisterZeroWriteStark;
 use crate::register::RegisterCtl;
-use crate::storage_device::columns::{StorageDevice, StorageDeviceCtl};
+use crate::stark::wiring::Wiring;
+use crate::storage_device::columns::{PublicTapeRow, StorageDevice, StorageDeviceCtl};
 use crate::storage_device::stark::StorageDeviceStark;
-use crate::tape_commitments::columns::{TapeCommitmentCTL, TapeCommitments};
+use crate::tape_commitments::columns::{CommitmentByte, TapeCommitmentCTL, TapeCommitments};
 use crate::tape_commitments::stark::TapeCommitmentsStark;
 use crate::xor::columns::{XorColumnsView, XorView};
 use crate::xor::stark::XorStark;
@@ -74,11 +89,82 @@ use crate::{
     rangecheck, register, storage_device, xor,
 };
 
-const NUM_CROSS_TABLE_LOOKUP: usize = 18;
-const NUM_PUBLIC_SUB_TABLES: usize = 2;
-const NUM_PUBLIC_TABLES: usize = 2;
-pub const PUBLIC_TABLE_KINDS: [TableKind; NUM_PUBLIC_TABLES] =
-    [TableKind::Program, TableKind::ElfMemoryInit];
+/// Defines the cross table lookups of [`MozakStark`] from the [`Lookups`]
+/// that make them up, together with their number and names.
+macro_rules! cross_table_lookups {
+    ($($lookups:ident),* $(,)?) => {
+        const NUM_CROSS_TABLE_LOOKUP: usize = [$(stringify!($lookups)),*].len();
+
+        /// Names of the cross table lookups of [`MozakStark`], in order.
+        pub const CROSS_TABLE_LOOKUP_NAMES: [&str; NUM_CROSS_TABLE_LOOKUP] =
+            [$(stringify!($lookups)),*];
+
+        fn cross_table_lookups() -> [CrossTableLookup; NUM_CROSS_TABLE_LOOKUP] {
+            [$($lookups::lookups()),*]
+        }
+    };
+}
+
+// These tables contain only descriptions of the tables.
+// The values of the tables are generated as traces.
+cross_table_lookups!(
+    RangecheckTable,
+    XorOpsTable,
+    BitshiftMulDivTable,
+    InnerCpuTable,
+    ProgramCpuTable,
+    IntoMemoryTable,
+    MemoryInitMemoryTable,
+    RangeCheckU8LookupTable,
+    HalfWordMemoryLoadStoreTable,
+    FullWordMemoryLoadStoreTable,
+    RegisterLookups,
+    StorageDeviceToCpuTable,
+    Poseidon2SpongeCpuTable,
+    Poseidon2Poseidon2SpongeTable,
+    Poseidon2OutputBytesPoseidon2SpongeTable,
+    CpuToSkeletonTable,
+    EventCommitmentTapeIOLookupTable,
+    CastlistCommitmentTapeIOLookupTable,
+);
+
+/// Defines the public sub tables of [`MozakStark`] from the [`MakePublic`]
+/// types that declare them.
+macro_rules! public_sub_tables {
+    ($($public:ident),* $(,)?) => {
+        const NUM_PUBLIC_SUB_TABLES: usize = [$(stringify!($public)),*].len();
+
+        fn public_sub_tables() -> [PublicSubTable; NUM_PUBLIC_SUB_TABLES] {
+            [$($public::public_sub_table()),*]
+        }
+
+        public_sub_tables!(@position 0, $($public),*);
+    };
+    (@position $position:expr, $public:ident $(, $rest:ident)*) => {
+        impl MozakPublicSubTable for $public {
+            const POSITION: usize = $position;
+        }
+
+        public_sub_tables!(@position $position + 1, $($rest),*);
+    };
+    (@position $position:expr,) => {};
+}
+
+public_sub_tables!(PublicEventCommitment, PublicCastListCommitment, PublicTape);
+
+/// Defines [`PUBLIC_TABLE_KINDS`] from the [`PublicTable`] types that declare
+/// the public tables of [`MozakStark`].
+macro_rules! public_tables {
+    ($($public:ident),* $(,)?) => {
+        const NUM_PUBLIC_TABLES: usize = [$(stringify!($public)),*].len();
+
+        /// The tables whose whole traces the batch prover commits to
+        /// separately.
+        pub const PUBLIC_TABLE_KINDS: [TableKind; NUM_PUBLIC_TABLES] = [$($public::KIND),*];
+    };
+}
+
+public_tables!(PublicProgramRom, PublicElfMemoryInit);
 
 /// STARK Gadgets of Mozak-VM
 ///
@@ -153,11 +239,24 @@ pub struct MozakStark<F: RichField + Extendable<D>, const D: usize> {
     pub add_stark: AddStark<F, D>,
     #[StarkSet(stark_kind = "BltTaken")]
     pub blt_taken_stark: BltTakenStark<F, D>,
+    #[StarkSet(stark_kind = "MulDiv")]
+    pub mul_div_stark: MulDivStark<F, D>,
+    #[StarkSet(stark_kind = "Bitwise")]
+    pub bitwise_stark: BitwiseStark<F, D>,
+    #[StarkSet(stark_kind = "Branches")]
+    pub branches_stark: BranchesStark<F, D>,
+    #[StarkSet(stark_kind = "Jalr")]
+    pub jalr_stark: JalrStark<F, D>,
+    #[StarkSet(stark_kind = "LoadStore")]
+    pub load_store_stark: LoadStoreStark<F, D>,
     #[StarkSet(stark_kind = "TapeCommitments")]
     pub tape_commitments_stark: TapeCommitmentsStark<F, D>,
     pub cross_table_lookups: [CrossTableLookup; NUM_CROSS_TABLE_LOOKUP],
     pub public_sub_tables: [PublicSubTable; NUM_PUBLIC_SUB_TABLES],
     pub debug: bool,
+    /// Prove one table at a time and recompute the low-degree extensions of
+    /// the traces on demand, trading proving time for a lower peak memory.
+    pub low_memory: bool,
 }
 
 // A macro which takes metadata about `MozakStark`
@@ -171,13 +270,20 @@ macro_rules! mozak_stark_helpers {
         fields = [{ $($fields:ident)* }]
     } => {
         // Generate all the `TableKind`s and their associated values
-        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
         pub enum TableKind {
             $($kind_names = $kind_vals,)*
         }
 
         impl TableKind {
             const COUNT: usize = $kind_count;
+
+            /// The name of the field of [`MozakStark`] that holds the Stark
+            /// of this table.
+            #[must_use]
+            pub fn field_name(self) -> &'static str {
+                [$(stringify!($fields),)*][self as usize]
+            }
         }
 
         // Generate the set builder
@@ -417,7 +523,7 @@ pub struct PublicInputs<F> {
 
 impl<F: RichField + Extendable<D>, const D: usize> Default for MozakStark<F, D> {
     fn default() -> Self {
-        Self {
+        let mozak_stark = Self {
             cpu_stark: CpuStark::default(),
             rangecheck_stark: RangeCheckStark::default(),
             xor_stark: XorStark::default(),
@@ -447,40 +553,49 @@ impl<F: RichField + Extendable<D>, const D: usize> Default for MozakStark<F, D>
             cpu_skeleton_stark: CpuSkeletonStark::default(),
             add_stark: AddStark::default(),
             blt_taken_stark: BltTakenStark::default(),
+            mul_div_stark: MulDivStark::default(),
+            bitwise_stark: BitwiseStark::default(),
+            branches_stark: BranchesStark::default(),
+            jalr_stark: JalrStark::default(),
+            load_store_stark: LoadStoreStark::default(),
             tape_commitments_stark: TapeCommitmentsStark::default(),
-
-            // These tables contain only descriptions of the tables.
-            // The values of the tables are generated as traces.
-            cross_table_lookups: [
-                RangecheckTable::lookups(),
-                XorCpuTable::lookups(),
-                BitshiftCpuTable::lookups(),
-                InnerCpuTable::lookups(),
-                ProgramCpuTable::lookups(),
-                IntoMemoryTable::lookups(),
-                MemoryInitMemoryTable::lookups(),
-                RangeCheckU8LookupTable::lookups(),
-                HalfWordMemoryCpuTable::lookups(),
-                FullWordMemoryCpuTable::lookups(),
-                RegisterLookups::lookups(),
-                StorageDeviceToCpuTable::lookups(),
-                Poseidon2SpongeCpuTable::lookups(),
-                Poseidon2Poseidon2SpongeTable::lookups(),
-                Poseidon2OutputBytesPoseidon2SpongeTable::lookups(),
-                CpuToSkeletonTable::lookups(),
-                EventCommitmentTapeIOLookupTable::lookups(),
-                CastlistCommitmentTapeIOLookupTable::lookups(),
-            ],
-            public_sub_tables: [
-                crate::tape_commitments::columns::make_event_commitment_tape_public(),
-                crate::tape_commitments::columns::make_castlist_commitment_tape_public(),
-            ],
+            cross_table_lookups: cross_table_lookups(),
+            public_sub_tables: public_sub_tables(),
             debug: false,
+            low_memory: false,
+        };
+        if let Err(error) = Wiring::of(&mozak_stark).validate() {
+            panic!("Invalid wiring of MozakStark: {error}");
         }
+        mozak_stark
     }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> MozakStark<F, D> {
+    /// Decodes the rows of the public sub table `P` from `values`, the values
+    /// of all public sub tables per table.  These are either the
+    /// `public_sub_table_values` of a proof, or their targets in the
+    /// recursive circuits.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `values` has no values for `P`, or if they are not rows of
+    /// `P`.
+    pub fn public_sub_table_rows<P: MozakPublicSubTable, T: Copy + Debug>(
+        &self,
+        values: &TableKindArray<Vec<Vec<Vec<T>>>>,
+    ) -> Result<Vec<P::Row<T>>> {
+        let kind = self.public_sub_tables[P::POSITION].table.kind;
+        let index = self.public_sub_tables[..P::POSITION]
+            .iter()
+            .filter(|public_sub_table| public_sub_table.table.kind == kind)
+            .count();
+        let values = values[kind]
+            .get(index)
+            .ok_or_else(|| anyhow!("no values for public sub table {index} of {kind:?}"))?;
+        P::decode(values)
+    }
+
     #[must_use]
     pub fn default_debug() -> Self {
         Self {
@@ -488,6 +603,31 @@ impl<F: RichField + Extendable<D>, const D: usize> MozakStark<F, D> {
             ..Self::default()
         }
     }
+
+    #[must_use]
+    pub fn default_low_memory() -> Self {
+        Self {
+            low_memory: true,
+            ..Self::default()
+        }
+    }
+
+    /// Constrain halfword and word accesses according to `alignment_policy`,
+    /// which has to match the policy the runner executed with.
+    #[must_use]
+    pub fn with_alignment_policy(self, alignment_policy: AlignmentPolicy) -> Self {
+        Self {
+            halfword_memory_stark: HalfWordMemoryStark {
+                alignment_policy,
+                ..self.halfword_memory_stark
+            },
+            fullword_memory_stark: FullWordMemoryStark {
+                alignment_policy,
+                ..self.fullword_memory_stark
+            },
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -671,6 +811,11 @@ table_impl!(
 table_impl!(SkeletonTable, TableKind::CpuSkeleton, CpuSkeleton);
 table_impl!(AddTable, TableKind::Add, Add);
 table_impl!(BltTakenTable, TableKind::BltTaken, BltTaken);
+table_impl!(MulDivTable, TableKind::MulDiv, MulDiv);
+table_impl!(BitwiseTable, TableKind::Bitwise, Bitwise);
+table_impl!(BranchesTable, TableKind::Branches, Branches);
+table_impl!(JalrTable, TableKind::Jalr, Jalr);
+table_impl!(LoadStoreTable, TableKind::LoadStore, LoadStore);
 
 pub trait Lookups {
     type Row: IntoIterator<Item = Column>;
@@ -679,6 +824,85 @@ pub trait Lookups {
     fn lookups() -> CrossTableLookup { Self::lookups_with_typed_output().to_untyped_output() }
 }
 
+/// A public sub table of [`MozakStark`], declared in `public_sub_tables!`.
+pub trait MozakPublicSubTable: MakePublic {
+    /// Position of the subtable in [`MozakStark::public_sub_tables`].
+    const POSITION: usize;
+}
+
+/// A table whose whole trace the batch prover commits to separately, so that
+/// verifiers can compare its Merkle cap with the trace that they expect, like
+/// the program ROM that a program identifier commits to.
+pub trait PublicTable {
+    const KIND: TableKind;
+    type Row<T: Debug>: FromIterator<T> + IntoIterator<Item = T> + NumberOfColumns;
+
+    /// Decodes the `trace` of the table into typed rows.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `trace` does not have the columns of `Row`.
+    fn decode<F: Field>(trace: &[PolynomialValues<F>]) -> Result<Vec<Self::Row<F>>> {
+        ensure!(
+            trace.len() == <Self::Row<F> as NumberOfColumns>::NUMBER_OF_COLUMNS,
+            "{:?} has {} columns, but the trace has {}",
+            Self::KIND,
+            <Self::Row<F> as NumberOfColumns>::NUMBER_OF_COLUMNS,
+            trace.len()
+        );
+        let num_rows = trace.first().map_or(0, PolynomialValues::len);
+        Ok((0..num_rows)
+            .map(|row| trace.iter().map(|column| column.values[row]).collect())
+            .collect())
+    }
+}
+
+pub struct PublicProgramRom;
+
+impl PublicTable for PublicProgramRom {
+    type Row<T: Debug> = ProgramRom<T>;
+
+    const KIND: TableKind = TableKind::Program;
+}
+
+pub struct PublicElfMemoryInit;
+
+impl PublicTable for PublicElfMemoryInit {
+    type Row<T: Debug> = MemoryInit<T>;
+
+    const KIND: TableKind = TableKind::ElfMemoryInit;
+}
+
+pub struct PublicEventCommitment;
+
+impl MakePublic for PublicEventCommitment {
+    type Row<T: Debug> = CommitmentByte<T>;
+
+    fn public_sub_table_with_typed_output() -> PublicSubTableWithTypedOutput<Self::Row<Column>> {
+        crate::tape_commitments::columns::make_event_commitment_tape_public()
+    }
+}
+
+pub struct PublicCastListCommitment;
+
+impl MakePublic for PublicCastListCommitment {
+    type Row<T: Debug> = CommitmentByte<T>;
+
+    fn public_sub_table_with_typed_output() -> PublicSubTableWithTypedOutput<Self::Row<Column>> {
+        crate::tape_commitments::columns::make_castlist_commitment_tape_public()
+    }
+}
+
+pub struct PublicTape;
+
+impl MakePublic for PublicTape {
+    type Row<T: Debug> = PublicTapeRow<T>;
+
+    fn public_sub_table_with_typed_output() -> PublicSubTableWithTypedOutput<Self::Row<Column>> {
+        crate::storage_device::columns::make_public_tape_public()
+    }
+}
+
 pub struct CpuToSkeletonTable;
 
 impl Lookups for CpuToSkeletonTable {
@@ -690,6 +914,11 @@ impl Lookups for CpuToSkeletonTable {
                 cpu::columns::lookup_for_skeleton(),
                 ops::add::columns::lookup_for_skeleton(),
                 ops::blt_taken::columns::lookup_for_skeleton(),
+                ops::mul_div::columns::lookup_for_skeleton(),
+                ops::bitwise::columns::lookup_for_skeleton(),
+                ops::branches::columns::lookup_for_skeleton(),
+                ops::jalr::columns::lookup_for_skeleton(),
+                ops::load_store::columns::lookup_for_skeleton(),
             ],
             vec![cpu_skeleton::columns::lookup_for_cpu()],
         )
@@ -706,8 +935,14 @@ impl Lookups for RangecheckTable {
 
         let looking: Vec<TableWithTypedOutput<_>> = chain![
             memory::columns::rangecheck_looking(),
+            memory_halfword::columns::rangecheck_looking(),
+            memory_fullword::columns::rangecheck_looking(),
             cpu::columns::rangecheck_looking(),
             ops::add::columns::rangecheck_looking(),
+            ops::mul_div::columns::rangecheck_looking(),
+            ops::branches::columns::rangecheck_looking(),
+            ops::jalr::columns::rangecheck_looking(),
+            ops::load_store::columns::rangecheck_looking(),
             register,
         ]
         .collect();
@@ -715,15 +950,19 @@ impl Lookups for RangecheckTable {
     }
 }
 
-pub struct XorCpuTable;
+pub struct XorOpsTable;
 
-impl Lookups for XorCpuTable {
+impl Lookups for XorOpsTable {
     type Row = XorView<Column>;
 
     fn lookups_with_typed_output() -> CrossTableLookupWithTypedOutput<Self::Row> {
-        CrossTableLookupWithTypedOutput::new(vec![cpu::columns::lookup_for_xor()], vec![
-            xor::columns::lookup_for_cpu(),
-        ])
+        CrossTableLookupWithTypedOutput::new(
+            vec![
+                bitwise::columns::lookup_for_xor(),
+                load_store::columns::lookup_for_xor(),
+            ],
+            vec![xor::columns::lookup_for_ops()],
+        )
     }
 }
 
@@ -735,7 +974,7 @@ impl Lookups for IntoMemoryTable {
     #[allow(clippy::too_many_lines)]
     fn lookups_with_typed_output() -> CrossTableLookupWithTypedOutput<Self::Row> {
         let tables = chain![
-            [cpu::columns::lookup_for_memory()],
+            [load_store::columns::lookup_for_memory()],
             [
                 TableKind::StorageDevicePrivate,
                 TableKind::StorageDevicePublic,
@@ -772,15 +1011,16 @@ impl Lookups for MemoryInitMemoryTable {
     }
 }
 
-pub struct BitshiftCpuTable;
+pub struct BitshiftMulDivTable;
 
-impl Lookups for BitshiftCpuTable {
+impl Lookups for BitshiftMulDivTable {
     type Row = Bitshift<Column>;
 
     fn lookups_with_typed_output() -> CrossTableLookupWithTypedOutput<Bitshift<Column>> {
-        CrossTableLookupWithTypedOutput::new(vec![cpu::columns::lookup_for_shift_amount()], vec![
-            bitshift::columns::lookup_for_cpu(),
-        ])
+        CrossTableLookupWithTypedOutput::new(
+            vec![mul_div::columns::lookup_for_shift_amount()],
+            vec![bitshift::columns::lookup_for_mul_div()],
+        )
     }
 }
 
@@ -794,6 +1034,11 @@ impl Lookups for InnerCpuTable {
             vec![
                 add::columns::lookup_for_program_rom(),
                 blt_taken::columns::lookup_for_program_rom(),
+                mul_div::columns::lookup_for_program_rom(),
+                bitwise::columns::lookup_for_program_rom(),
+                branches::columns::lookup_for_program_rom(),
+                jalr::columns::lookup_for_program_rom(),
+                load_store::columns::lookup_for_program_rom(),
                 cpu::columns::lookup_for_program_rom(),
             ],
             vec![program_multiplicities::columns::lookup_for_cpu()],
@@ -828,28 +1073,28 @@ impl Lookups for RangeCheckU8LookupTable {
     }
 }
 
-pub struct HalfWordMemoryCpuTable;
+pub struct HalfWordMemoryLoadStoreTable;
 
-impl Lookups for HalfWordMemoryCpuTable {
+impl Lookups for HalfWordMemoryLoadStoreTable {
     type Row = MemoryCtl<Column>;
 
     fn lookups_with_typed_output() -> CrossTableLookupWithTypedOutput<MemoryCtl<Column>> {
         CrossTableLookupWithTypedOutput::new(
-            vec![cpu::columns::lookup_for_halfword_memory()],
-            vec![memory_halfword::columns::lookup_for_cpu()],
+            vec![load_store::columns::lookup_for_halfword_memory()],
+            vec![memory_halfword::columns::lookup_for_load_store()],
         )
     }
 }
 
-pub struct FullWordMemoryCpuTable;
+pub struct FullWordMemoryLoadStoreTable;
 
-impl Lookups for FullWordMemoryCpuTable {
+impl Lookups for FullWordMemoryLoadStoreTable {
     type Row = MemoryCtl<Column>;
 
     fn lookups_with_typed_output() -> CrossTableLookupWithTypedOutput<Self::Row> {
         CrossTableLookupWithTypedOutput::new(
-            vec![cpu::columns::lookup_for_fullword_memory()],
-            vec![memory_fullword::columns::lookup_for_cpu()],
+            vec![load_store::columns::lookup_for_fullword_memory()],
+            vec![memory_fullword::columns::lookup_for_load_store()],
         )
     }
 }
@@ -865,6 +1110,11 @@ impl Lookups for RegisterLookups {
                 crate::cpu::columns::register_looking(),
                 ops::add::columns::register_looking(),
                 ops::blt_taken::columns::register_looking(),
+                ops::mul_div::columns::register_looking(),
+                ops::bitwise::columns::register_looking(),
+                ops::branches::columns::register_looking(),
+                ops::jalr::columns::register_looking(),
+                ops::load_store::columns::register_looking(),
                 crate::storage_device::columns::register_looking(),
                 crate::poseidon2_sponge::columns::register_looking(),
                 vec![crate::register::init::columns::lookup_for_register()],
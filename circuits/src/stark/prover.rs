@@ -27,7 +27,7 @@ use starky::config::StarkConfig;
 use starky::stark::{LookupConfig, Stark};
 
 use super::mozak_stark::{
-    all_starks_par, MozakStark, TableKind, TableKindArray, TableKindSetBuilder,
+    all_starks, all_starks_par, MozakStark, TableKind, TableKindArray, TableKindSetBuilder,
 };
 use super::proof::{AllProof, StarkOpeningSet, StarkProof};
 use crate::cross_table_lookup::ctl_utils::debug_ctl;
@@ -37,6 +37,8 @@ use crate::public_sub_table::public_sub_table_data_and_values;
 use crate::stark::mozak_stark::PublicInputs;
 use crate::stark::permutation::challenge::GrandProductChallengeTrait;
 use crate::stark::poly::compute_quotient_polys;
+#[cfg(feature = "timing")]
+use crate::stark::utils::log_peak_memory_usage;
 
 /// Prove the execution of a given [Program]
 ///
@@ -85,7 +87,7 @@ where
             mozak_stark,
             config,
             public_inputs,
-            &traces_poly_values,
+            traces_poly_values,
             timing,
         )
     )
@@ -93,44 +95,31 @@ where
 
 /// Given the traces generated from [`generate_traces`], prove a [`MozakStark`].
 ///
+/// The traces are dropped as soon as the cross-table lookups are computed.
+/// With [`MozakStark::low_memory`], the traces are committed to one table at a
+/// time and the low-degree extension of each is released right after its
+/// commitment, to be recomputed table by table while proving.
+///
 /// # Errors
 /// Errors if proving fails.
 pub fn prove_with_traces<F, C, const D: usize>(
     mozak_stark: &MozakStark<F, D>,
     config: &StarkConfig,
     public_inputs: PublicInputs<F>,
-    traces_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
+    traces_poly_values: TableKindArray<Vec<PolynomialValues<F>>>,
     timing: &mut TimingTree,
 ) -> Result<AllProof<F, C, D>>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>, {
-    let rate_bits = config.fri_config.rate_bits;
-    let cap_height = config.fri_config.cap_height;
-
-    let trace_commitments = timed!(
+    let mut trace_commitments = timed!(
         timing,
         "Compute trace commitments for each table",
-        traces_poly_values
-            .each_ref()
-            .with_kind()
-            .par_map(|(trace, table)| {
-                let mut timing = TimingTree::default();
-                timed!(
-                    timing,
-                    &format!("compute trace commitment for {table:?}"),
-                    PolynomialBatch::<F, C, D>::from_values(
-                        trace.clone(),
-                        rate_bits,
-                        false,
-                        cap_height,
-                        &mut timing,
-                        None,
-                    )
-                )
-            })
+        commit_traces(mozak_stark, config, &traces_poly_values)
     );
+    #[cfg(feature = "timing")]
+    log_peak_memory_usage("trace commitments");
 
     let trace_caps = trace_commitments
         .each_ref()
@@ -146,7 +135,7 @@ where
         timing,
         "Compute CTL data for each table",
         cross_table_lookup_data::<F, D>(
-            traces_poly_values,
+            &traces_poly_values,
             &mozak_stark.cross_table_lookups,
             &ctl_challenges
         )
@@ -154,10 +143,12 @@ where
 
     let (public_sub_table_data_per_table, public_sub_table_values) =
         public_sub_table_data_and_values::<F, D>(
-            traces_poly_values,
+            &traces_poly_values,
             &mozak_stark.public_sub_tables,
             &ctl_challenges,
         );
+    // Everything from here on only needs the commitments.
+    drop(traces_poly_values);
 
     let proofs = timed!(
         timing,
@@ -166,7 +157,7 @@ where
             mozak_stark,
             config,
             &public_inputs,
-            &trace_commitments,
+            &mut trace_commitments,
             &ctl_data_per_table,
             &public_sub_table_data_per_table,
             &mut challenger,
@@ -180,6 +171,8 @@ where
         &trace_caps[TableKind::ElfMemoryInit],
     );
 
+    #[cfg(feature = "timing")]
+    log_peak_memory_usage("proving");
     if log_enabled!(Debug) {
         timing.print();
     }
@@ -192,6 +185,82 @@ where
     })
 }
 
+/// Commits to the trace of every table.
+///
+/// With [`MozakStark::low_memory`], the tables are committed to one at a time,
+/// and the low-degree extension of each is released right away.
+pub(crate) fn commit_traces<F, C, const D: usize>(
+    mozak_stark: &MozakStark<F, D>,
+    config: &StarkConfig,
+    traces_poly_values: &TableKindArray<Vec<PolynomialValues<F>>>,
+) -> TableKindArray<PolynomialBatch<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+
+    let commit = |(trace, table): (&Vec<PolynomialValues<F>>, TableKind)| {
+        let mut timing = TimingTree::default();
+        timed!(
+            timing,
+            &format!("compute trace commitment for {table:?}"),
+            PolynomialBatch::<F, C, D>::from_values(
+                trace.clone(),
+                rate_bits,
+                false,
+                cap_height,
+                &mut timing,
+                None,
+            )
+        )
+    };
+    if mozak_stark.low_memory {
+        // Commit to one table at a time, so that we never hold more than
+        // one low-degree extension.
+        traces_poly_values.each_ref().with_kind().map(|trace| {
+            let mut commitment = commit(trace);
+            release_lde(&mut commitment);
+            commitment
+        })
+    } else {
+        traces_poly_values.each_ref().with_kind().par_map(commit)
+    }
+}
+
+/// Frees the low-degree extension of a trace commitment, keeping only its
+/// coefficients and Merkle cap.  Use [`restore_lde`] to get it back.
+pub(crate) fn release_lde<F, C, const D: usize>(commitment: &mut PolynomialBatch<F, C, D>)
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    commitment.merkle_tree.leaves = vec![];
+    commitment.merkle_tree.digests = vec![];
+}
+
+/// Recomputes the low-degree extension that [`release_lde`] freed, if any.
+pub(crate) fn restore_lde<F, C, const D: usize>(
+    commitment: &mut PolynomialBatch<F, C, D>,
+    cap_height: usize,
+    timing: &mut TimingTree,
+) where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    if !commitment.merkle_tree.leaves.is_empty() {
+        return;
+    }
+    let restored = PolynomialBatch::from_coeffs(
+        commitment.polynomials.clone(),
+        commitment.rate_bits,
+        false,
+        cap_height,
+        timing,
+        None,
+    );
+    debug_assert_eq!(restored.merkle_tree.cap, commitment.merkle_tree.cap);
+    *commitment = restored;
+}
+
 pub fn get_program_id<F, C, const D: usize>(
     entry_point: F,
     program_trace_cap: &MerkleCap<F, C::Hasher>,
@@ -389,6 +458,10 @@ where
 /// Given the traces generated from [`generate_traces`] along with their
 /// commitments, prove a [`MozakStark`].
 ///
+/// With [`MozakStark::low_memory`], the tables are proven one at a time, and
+/// each trace commitment gets its low-degree extension back only while its
+/// table is proven.
+///
 /// # Errors
 /// Errors if proving fails.
 #[allow(clippy::too_many_arguments)]
@@ -396,7 +469,7 @@ pub fn prove_with_commitments<F, C, const D: usize>(
     mozak_stark: &MozakStark<F, D>,
     config: &StarkConfig,
     public_inputs: &PublicInputs<F>,
-    trace_commitments: &TableKindArray<PolynomialBatch<F, C, D>>,
+    trace_commitments: &mut TableKindArray<PolynomialBatch<F, C, D>>,
     ctl_data_per_table: &TableKindArray<CtlData<F>>,
     public_sub_data_per_table: &TableKindArray<CtlData<F>>,
     challenger: &mut Challenger<F, C::Hasher>,
@@ -414,6 +487,29 @@ where
     challenger.compact();
     let challenger: &Challenger<F, C::Hasher> = &challenger.clone();
 
+    if mozak_stark.low_memory {
+        let cap_height = config.fri_config.cap_height;
+        return Ok(all_starks!(mozak_stark, |stark, kind| {
+            let mut timing = TimingTree::default();
+            let trace_commitment = &mut trace_commitments[kind];
+            restore_lde(trace_commitment, cap_height, &mut timing);
+            let proof = prove_single_table(
+                stark,
+                config,
+                trace_commitment,
+                public_inputs[kind],
+                &ctl_data_per_table[kind],
+                &public_sub_data_per_table[kind],
+                &mut challenger.clone(),
+                &mut timing,
+            )
+            .unwrap();
+            release_lde(trace_commitment);
+            proof
+        }));
+    }
+
+    let trace_commitments = &*trace_commitments;
     Ok(all_starks_par!(mozak_stark, |stark, kind| {
         let mut timing = TimingTree::default();
         prove_single_table(
@@ -432,16 +528,31 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::iter;
 
+    use itertools::izip;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::polynomial::PolynomialValues;
     use plonky2::field::types::Field;
+    use plonky2::fri::oracle::PolynomialBatch;
     use plonky2::hash::poseidon2::Poseidon2Hash;
     use plonky2::plonk::config::{GenericHashOut, Hasher};
-
-    use crate::stark::mozak_stark::MozakStark;
-    use crate::test_utils::{create_poseidon2_test, Poseidon2Test, ProveAndVerify};
+    use plonky2::util::timing::TimingTree;
+
+    use super::{commit_traces, get_program_id, prove, restore_lde};
+    use crate::generation::generate_traces;
+    use crate::memoryinit::generation::generate_elf_memory_init_trace;
+    use crate::program::generation::generate_program_rom_trace;
+    use crate::stark::mozak_stark::{MozakStark, PublicInputs};
+    use crate::stark::security::program_id_config;
+    use crate::stark::utils::trace_rows_to_poly_values;
+    use crate::stark::verifier::verify_proof;
+    use crate::test_utils::{
+        create_poseidon2_test, fast_test_config, Poseidon2Test, ProveAndVerify, C, D, F,
+    };
+    use crate::utils::from_u32;
 
     #[test]
     fn prove_halt() {
@@ -449,6 +560,218 @@ mod tests {
         MozakStark::prove_and_verify(&program, &record).unwrap();
     }
 
+    #[test]
+    fn program_id_does_not_depend_on_profile() {
+        let (program, record) = code::execute([], &[], &[]);
+        let proof = prove::<F, C, D>(
+            &program,
+            &record,
+            &MozakStark::default(),
+            &fast_test_config(),
+            PublicInputs {
+                entry_point: from_u32(program.entry_point),
+            },
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+
+        let config = program_id_config();
+        let cap = |trace: Vec<PolynomialValues<F>>| {
+            PolynomialBatch::<F, C, D>::from_values(
+                trace,
+                config.fri_config.rate_bits,
+                false,
+                config.fri_config.cap_height,
+                &mut TimingTree::default(),
+                None,
+            )
+            .merkle_tree
+            .cap
+        };
+        let program_id = get_program_id::<F, C, D>(
+            from_u32(program.entry_point),
+            &cap(trace_rows_to_poly_values(generate_program_rom_trace(
+                &program,
+            ))),
+            &cap(trace_rows_to_poly_values(generate_elf_memory_init_trace(
+                &program,
+            ))),
+        );
+        assert_eq!(proof.program_id, program_id);
+    }
+
+    #[test]
+    fn prove_low_memory() {
+        let (program, record) = code::execute(
+            [Instruction {
+                op: Op::ADD,
+                args: Args {
+                    rd: 1,
+                    imm: 0xDEAD_BEEF,
+                    ..Args::default()
+                },
+            }],
+            &[],
+            &[],
+        );
+        let config = fast_test_config();
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+        let prove_with = |stark: &MozakStark<F, D>| {
+            prove::<F, C, D>(
+                &program,
+                &record,
+                stark,
+                &config,
+                public_inputs,
+                &mut TimingTree::default(),
+            )
+            .unwrap()
+        };
+        let proof = prove_with(&MozakStark::default());
+        let low_memory_proof = prove_with(&MozakStark::default_low_memory());
+
+        // Recomputing the extensions must not change what we commit to.
+        for (table_proof, low_memory_table_proof) in izip!(&proof.proofs, &low_memory_proof.proofs)
+        {
+            assert_eq!(table_proof.trace_cap, low_memory_table_proof.trace_cap);
+        }
+        verify_proof(&MozakStark::default(), low_memory_proof, &config).unwrap();
+    }
+
+    /// With [`MozakStark::low_memory`], no trace commitment holds on to its
+    /// low-degree extension, and it can be recomputed exactly.
+    #[test]
+    fn low_memory_releases_ldes() {
+        let add = Instruction {
+            op: Op::ADD,
+            args: Args {
+                rd: 1,
+                rs1: 1,
+                imm: 1,
+                ..Args::default()
+            },
+        };
+        let (program, record) = code::execute(iter::repeat(add).take(1 << 6), &[], &[]);
+        let traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+        let config = fast_test_config();
+        let commitments = commit_traces::<F, C, D>(&MozakStark::default(), &config, &traces);
+        let mut low_memory_commitments =
+            commit_traces::<F, C, D>(&MozakStark::default_low_memory(), &config, &traces);
+
+        for (commitment, low_memory_commitment) in
+            izip!(commitments.0, low_memory_commitments.0.iter_mut())
+        {
+            assert!(!commitment.merkle_tree.leaves.is_empty());
+            assert!(low_memory_commitment.merkle_tree.leaves.is_empty());
+            assert!(low_memory_commitment.merkle_tree.digests.is_empty());
+            assert_eq!(
+                commitment.merkle_tree.cap,
+                low_memory_commitment.merkle_tree.cap
+            );
+
+            restore_lde(
+                low_memory_commitment,
+                config.fri_config.cap_height,
+                &mut TimingTree::default(),
+            );
+            assert_eq!(
+                commitment.merkle_tree.leaves,
+                low_memory_commitment.merkle_tree.leaves
+            );
+        }
+    }
+
+    /// Peak memory measurements, which need the `timing` feature.
+    #[cfg(feature = "timing")]
+    mod peak_memory {
+        use std::process::Command;
+        use std::{env, iter};
+
+        use mozak_runner::code;
+        use mozak_runner::instruction::{Args, Instruction, Op};
+        use plonky2::util::timing::TimingTree;
+
+        use crate::stark::mozak_stark::{MozakStark, PublicInputs};
+        use crate::stark::prover::prove;
+        use crate::stark::utils::peak_memory_usage;
+        use crate::test_utils::{fast_test_config, C, D, F};
+        use crate::utils::from_u32;
+
+        /// Proves a long program, with [`MozakStark::low_memory`] if
+        /// `MOZAK_LOW_MEMORY` is set, and prints the peak memory usage.
+        #[test]
+        #[ignore = "run in a fresh process by `low_memory_lowers_peak_memory`"]
+        fn prove_long_program_for_peak_memory() {
+            let add = Instruction {
+                op: Op::ADD,
+                args: Args {
+                    rd: 1,
+                    rs1: 1,
+                    imm: 1,
+                    ..Args::default()
+                },
+            };
+            let (program, record) = code::execute(iter::repeat(add).take(1 << 12), &[], &[]);
+            let stark = if env::var_os("MOZAK_LOW_MEMORY").is_some() {
+                MozakStark::default_low_memory()
+            } else {
+                MozakStark::default()
+            };
+            prove::<F, C, D>(
+                &program,
+                &record,
+                &stark,
+                &fast_test_config(),
+                PublicInputs {
+                    entry_point: from_u32(program.entry_point),
+                },
+                &mut TimingTree::default(),
+            )
+            .unwrap();
+            println!("peak memory: {}", peak_memory_usage().unwrap());
+        }
+
+        #[test]
+        #[ignore = "peak RSS depends on the allocator and the machine, so this is flaky"]
+        fn low_memory_lowers_peak_memory() {
+            if peak_memory_usage().is_none() {
+                // The peak memory usage is only known on Linux.
+                return;
+            }
+            // The peak memory usage never goes down, so measure each mode in a
+            // fresh process.
+            let peak_memory = |low_memory: bool| -> u64 {
+                let mut command = Command::new(env::current_exe().unwrap());
+                command.args([
+                    "stark::prover::tests::peak_memory::prove_long_program_for_peak_memory",
+                    "--exact",
+                    "--ignored",
+                    "--nocapture",
+                    "--test-threads=1",
+                ]);
+                if low_memory {
+                    command.env("MOZAK_LOW_MEMORY", "1");
+                }
+                let output = command.output().unwrap();
+                assert!(output.status.success(), "{output:?}");
+                String::from_utf8(output.stdout)
+                    .unwrap()
+                    .lines()
+                    .find_map(|line| line.strip_prefix("peak memory: "))
+                    .unwrap()
+                    .parse()
+                    .unwrap()
+            };
+            let (peak, low_memory_peak) = (peak_memory(false), peak_memory(true));
+            assert!(
+                low_memory_peak < peak,
+                "low memory peak of {low_memory_peak} bytes is not below {peak} bytes"
+            );
+        }
+    }
+
     #[test]
     fn prove_lui() {
         let lui = Instruction {
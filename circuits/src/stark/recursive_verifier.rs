@@ -1,9 +1,12 @@
 #![allow(clippy::iter_without_into_iter)]
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::iter::repeat;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use itertools::{chain, zip_eq, Itertools};
 use log::info;
 use mozak_sdk::core::constants::DIGEST_BYTES;
@@ -13,16 +16,19 @@ use plonky2::fri::proof::FriProofTarget;
 use plonky2::fri::structure::{FriOpeningBatchTarget, FriOpeningsTarget};
 use plonky2::fri::witness_util::set_fri_proof_target;
 use plonky2::gates::noop::NoopGate;
-use plonky2::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
+use plonky2::hash::hash_types::{
+    HashOut, HashOutTarget, MerkleCapTarget, RichField, NUM_HASH_OUT_ELTS,
+};
+use plonky2::hash::hashing::PlonkyPermutation;
 use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
-use plonky2::iop::target::Target;
+use plonky2::iop::target::{BoolTarget, Target};
 use plonky2::iop::witness::{PartialWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
 };
-use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
 use plonky2::util::reducing::ReducingFactorTarget;
 use plonky2::with_context;
@@ -42,13 +48,15 @@ use crate::public_sub_table::{
 use crate::stark::batch_prover::{
     batch_fri_instances_target, batch_reduction_arity_bits, sort_degree_bits,
 };
-use crate::stark::mozak_stark::{MozakStark, TableKind};
+use crate::stark::circuit_cache::build_circuit;
+use crate::stark::mozak_stark::{MozakStark, PublicTape, TableKind};
 use crate::stark::permutation::challenge::get_grand_product_challenge_set_target;
 use crate::stark::poly::eval_vanishing_poly_circuit;
 use crate::stark::proof::{
     AllProof, BatchProof, StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget,
     StarkProofTarget, StarkProofWithPublicInputsTarget,
 };
+use crate::storage_device::columns::PublicTapeRow;
 
 /// Plonky2's recursion threshold is 2^12 gates, but we need some extra gates
 /// for public inputs.
@@ -61,6 +69,7 @@ pub const VM_RECURSION_THRESHOLD_DEGREE_BITS: usize = 13;
 ///   `ElfMemoryInit trace cap`: 64
 ///   `event commitment_tape`: 32
 ///   `castlist_commitment_tape`: 32
+///   `public_tape_commitment`: 4
 pub const VM_PUBLIC_INPUT_SIZE: usize = VMRecursiveProofPublicInputs::<()>::NUMBER_OF_COLUMNS;
 pub const VM_RECURSION_CONFIG: CircuitConfig = CircuitConfig::standard_recursion_config();
 
@@ -71,10 +80,34 @@ pub struct VMRecursiveProofPublicInputs<T> {
     pub program_hash_as_bytes: [T; DIGEST_BYTES],
     pub event_commitment_tape: [T; DIGEST_BYTES],
     pub castlist_commitment_tape: [T; DIGEST_BYTES],
+    pub public_tape_commitment: [T; NUM_HASH_OUT_ELTS],
 }
 
 columns_view_impl!(VMRecursiveProofPublicInputs);
 
+impl<F: RichField> VMRecursiveProofPublicInputs<F> {
+    /// Checks that the proof commits to the public `tape`, see
+    /// [`public_tape_commitment`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the proof commits to another public tape.
+    pub fn check_public_tape<H: Hasher<F, Hash = HashOut<F>>>(&self, tape: &[u8]) -> Result<()> {
+        check_public_tape_commitment::<F, H>(self.public_tape_commitment, tape)
+    }
+}
+
+fn check_public_tape_commitment<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    commitment: [F; NUM_HASH_OUT_ELTS],
+    tape: &[u8],
+) -> Result<()> {
+    ensure!(
+        commitment == public_tape_commitment::<F, H>(tape).elements,
+        "The proof commits to another public tape"
+    );
+    Ok(())
+}
+
 #[derive(Eq, PartialEq, Debug)]
 pub struct MozakProofTarget<F, C, const D: usize>
 where
@@ -175,13 +208,11 @@ where
                 &self.proof.public_sub_table_values_targets[kind],
                 &all_proof.public_sub_table_values[kind],
             ) {
-                for (row_target, row) in
-                    zip_eq(public_sub_table_values_target, public_sub_table_values)
-                {
-                    for (&values_target, &values) in zip_eq(row_target, row) {
-                        inputs.set_target(values_target, values);
-                    }
-                }
+                PublicSubTable::set_targets(
+                    &mut inputs,
+                    public_sub_table_values_target,
+                    public_sub_table_values,
+                );
             }
         });
 
@@ -221,13 +252,11 @@ where
                 &self.proof.public_sub_table_values_targets[kind],
                 &all_proof.public_sub_table_values[kind],
             ) {
-                for (row_target, row) in
-                    zip_eq(public_sub_table_values_target, public_sub_table_values)
-                {
-                    for (&values_target, &values) in zip_eq(row_target, row) {
-                        inputs.set_target(values_target, values);
-                    }
-                }
+                PublicSubTable::set_targets(
+                    &mut inputs,
+                    public_sub_table_values_target,
+                    public_sub_table_values,
+                );
             }
         });
 
@@ -264,7 +293,7 @@ fn get_num_columns<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>
 #[allow(clippy::too_many_lines)]
 pub fn recursive_batch_stark_circuit<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     const D: usize,
 >(
     mozak_stark: &MozakStark<F, D>,
@@ -348,6 +377,7 @@ where
         public_sub_table_values_and_reduced_targets(
             &mut builder,
             &mozak_stark.public_sub_tables,
+            degree_bits,
             &ctl_challenges,
         );
 
@@ -471,16 +501,11 @@ where
     }
 
     builder.register_public_inputs(&program_hash);
-    all_kind!(|kind| {
-        builder.register_public_inputs(
-            &public_sub_table_values_targets[kind]
-                .clone()
-                .into_iter()
-                .flatten()
-                .flatten()
-                .collect_vec(),
-        );
-    });
+    register_public_sub_table_values::<F, C, D>(
+        &mut builder,
+        mozak_stark,
+        &public_sub_table_values_targets,
+    );
 
     let num_ctl_zs_per_table = all_kind!(|kind| stark_proof_with_pis_target[kind]
         .proof
@@ -542,7 +567,16 @@ where
         &fri_params,
     );
 
-    let circuit = builder.build();
+    let circuit = build_circuit(
+        "recursive_batch_stark",
+        &(
+            degree_bits,
+            public_table_kinds,
+            circuit_config,
+            inner_config,
+        ),
+        builder,
+    );
     MozakBatchStarkVerifierCircuit {
         circuit,
         proof: MozakBatchProofTarget {
@@ -559,7 +593,7 @@ where
 #[allow(clippy::too_many_lines)]
 pub fn recursive_mozak_stark_circuit<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     const D: usize,
 >(
     mozak_stark: &MozakStark<F, D>,
@@ -609,6 +643,7 @@ where
         public_sub_table_values_and_reduced_targets(
             &mut builder,
             &mozak_stark.public_sub_tables,
+            degree_bits,
             &ctl_challenges,
         );
 
@@ -664,18 +699,17 @@ where
     }
 
     builder.register_public_inputs(&program_hash);
-    all_kind!(|kind| {
-        builder.register_public_inputs(
-            &public_sub_table_values_targets[kind]
-                .clone()
-                .into_iter()
-                .flatten()
-                .flatten()
-                .collect_vec(),
-        );
-    });
+    register_public_sub_table_values::<F, C, D>(
+        &mut builder,
+        mozak_stark,
+        &public_sub_table_values_targets,
+    );
 
-    let circuit = builder.build();
+    let circuit = build_circuit(
+        "recursive_mozak_stark",
+        &(degree_bits, circuit_config, inner_config),
+        builder,
+    );
     MozakStarkVerifierCircuit {
         circuit,
         proof: MozakProofTarget {
@@ -949,7 +983,7 @@ pub fn set_stark_proof_with_pis_target<F, C: GenericConfig<D, F = F>, W, const D
 #[must_use]
 pub fn circuit_data_for_recursion<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     const D: usize,
 >(
     config: &CircuitConfig,
@@ -983,7 +1017,11 @@ where
     while builder.num_gates() < min_gates {
         builder.add_gate(NoopGate, vec![]);
     }
-    builder.build::<C>()
+    build_circuit(
+        "recursion_common",
+        &(config, target_degree_bits, public_input_size),
+        builder,
+    )
 }
 
 /// Represents a circuit which recursively verifies a PLONK proof.
@@ -999,7 +1037,7 @@ where
 impl<F, C, const D: usize> PlonkWrapperCircuit<F, C, D>
 where
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     C::Hasher: AlgebraicHasher<F>,
 {
     pub fn new(
@@ -1007,12 +1045,15 @@ where
         common: &CommonCircuitData<F, D>,
         config: CircuitConfig,
     ) -> PlonkWrapperCircuit<F, C, D> {
+        // The wrapped circuit is baked in as constants, so its digest is part
+        // of the cache key.
+        let params = (verifier_only.circuit_digest, config.clone());
         let mut builder = CircuitBuilder::new(config);
         let proof_with_pis_target = builder.add_virtual_proof_with_pis(common);
         let last_vk = builder.constant_verifier_data(verifier_only);
         builder.verify_proof::<C>(&proof_with_pis_target, &last_vk, common);
         builder.register_public_inputs(&proof_with_pis_target.public_inputs); // carry PIs forward
-        let circuit = builder.build::<C>();
+        let circuit = build_circuit("plonk_wrapper", &params, builder);
         PlonkWrapperCircuit {
             circuit,
             proof_with_pis_target,
@@ -1032,7 +1073,7 @@ where
 /// Shrinks a PLONK circuit to the target degree bits.
 pub fn shrink_to_target_degree_bits_circuit<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     const D: usize,
 >(
     verifier_only: &VerifierOnlyCircuitData<C, D>,
@@ -1078,7 +1119,7 @@ pub struct VMVerificationTargets<const D: usize> {
 /// and vk to ensure that the proof is from the correct program.
 pub fn verify_recursive_vm_proof<
     F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
+    C: GenericConfig<D, F = F> + 'static,
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
@@ -1105,6 +1146,291 @@ where
     }
 }
 
+/// One program proof aggregated by a [`VMAggregationCircuit`], as exposed in
+/// the public inputs of the aggregated proof.
+///
+/// `verifier_key_hash` is the [`verifier_key_hash`] of the key the program
+/// proof was verified against.  Verifiers of the aggregated proof have to
+/// compare it with the key they expect for the program.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct VMAggregatedEntry<T> {
+    pub program_hash_as_bytes: [T; DIGEST_BYTES],
+    pub event_commitment_tape: [T; DIGEST_BYTES],
+    pub castlist_commitment_tape: [T; DIGEST_BYTES],
+    pub public_tape_commitment: [T; NUM_HASH_OUT_ELTS],
+    pub verifier_key_hash: [T; NUM_HASH_OUT_ELTS],
+}
+
+columns_view_impl!(VMAggregatedEntry);
+
+impl<T: Copy> VMAggregatedEntry<T> {
+    /// Splits the public inputs of an aggregated proof into one entry per
+    /// program proof, in the order the proofs were aggregated in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the public inputs are not a whole number of entries.
+    #[must_use]
+    pub fn from_public_inputs(public_inputs: &[T]) -> Vec<Self> {
+        assert_eq!(
+            public_inputs.len() % Self::NUMBER_OF_COLUMNS,
+            0,
+            "public inputs are not a whole number of aggregated entries"
+        );
+        public_inputs
+            .chunks_exact(Self::NUMBER_OF_COLUMNS)
+            .map(|entry| *<&Self>::from(entry))
+            .collect()
+    }
+}
+
+impl<F: RichField> VMAggregatedEntry<F> {
+    /// Checks that the program proof commits to the public `tape`, see
+    /// [`public_tape_commitment`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if the program proof commits to another public tape.
+    pub fn check_public_tape<H: Hasher<F, Hash = HashOut<F>>>(&self, tape: &[u8]) -> Result<()> {
+        check_public_tape_commitment::<F, H>(self.public_tape_commitment, tape)
+    }
+}
+
+/// Hash of a verifier key, as exposed for every program proof aggregated by a
+/// [`VMAggregationCircuit`].
+#[must_use]
+pub fn verifier_key_hash<F, C, const D: usize>(
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> HashOut<F>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    let elements = chain!(
+        verifier_only
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .flat_map(GenericHashOut::to_vec),
+        verifier_only.circuit_digest.to_vec()
+    )
+    .collect_vec();
+    C::InnerHasher::hash_no_pad(&elements)
+}
+
+fn verifier_key_hash_circuit<F, C, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    verifier_key: &VerifierCircuitTarget,
+) -> HashOutTarget
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    let elements = chain!(
+        verifier_key
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .flat_map(|hash| hash.elements),
+        verifier_key.circuit_digest.elements
+    )
+    .collect_vec();
+    builder.hash_n_to_hash_no_pad::<C::InnerHasher>(elements)
+}
+
+/// A sub-tree of a [`VMAggregationCircuit`].
+#[derive(Eq, PartialEq, Debug)]
+enum AggregationChild<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    /// A single program proof, with the verifier key it is checked against.
+    Program {
+        proof: ProofWithPublicInputsTarget<D>,
+        verifier_key: VerifierCircuitTarget,
+    },
+    /// The aggregated proof of a sub-tree of program proofs.
+    Aggregate {
+        proof: ProofWithPublicInputsTarget<D>,
+        circuit: Rc<VMAggregationCircuit<F, C, D>>,
+    },
+}
+
+/// Represents a circuit which aggregates the recursive proofs of
+/// `num_proofs` programs, as shrunk by
+/// [`shrink_to_target_degree_bits_circuit`], into a single proof.
+///
+/// The proofs are verified in a binary tree, where every node verifies the
+/// proofs of its two sub-trees: a program proof for a single leaf, and an
+/// aggregated proof otherwise.  The circuits only depend on `num_proofs` and
+/// the config, as the verifier keys of the program proofs are part of the
+/// witness.  The public inputs of the aggregated proof are one
+/// [`VMAggregatedEntry`] per program proof.
+#[derive(Eq, PartialEq, Debug)]
+pub struct VMAggregationCircuit<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    pub circuit: CircuitData<F, C, D>,
+    pub num_proofs: usize,
+    children: Vec<AggregationChild<F, C, D>>,
+}
+
+impl<F, C, const D: usize> VMAggregationCircuit<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds the circuits to aggregate `num_proofs` program proofs, made
+    /// with the recursion `config`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_proofs` is zero.
+    #[must_use]
+    pub fn new(num_proofs: usize, config: &CircuitConfig) -> Self {
+        assert!(num_proofs > 0, "Cannot aggregate zero proofs");
+        Self::node(num_proofs, config, &mut HashMap::new())
+    }
+
+    /// The circuit of the sub-tree over `num_proofs` proofs, shared between
+    /// all sub-trees of that size.
+    fn sub_tree(
+        num_proofs: usize,
+        config: &CircuitConfig,
+        sub_trees: &mut HashMap<usize, Rc<Self>>,
+    ) -> Rc<Self> {
+        if let Some(circuit) = sub_trees.get(&num_proofs) {
+            return Rc::clone(circuit);
+        }
+        let circuit = Rc::new(Self::node(num_proofs, config, sub_trees));
+        sub_trees.insert(num_proofs, Rc::clone(&circuit));
+        circuit
+    }
+
+    fn node(
+        num_proofs: usize,
+        config: &CircuitConfig,
+        sub_trees: &mut HashMap<usize, Rc<Self>>,
+    ) -> Self {
+        let sizes = if num_proofs == 1 {
+            vec![1]
+        } else {
+            vec![num_proofs.div_ceil(2), num_proofs / 2]
+        };
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let children = sizes
+            .into_iter()
+            .map(|size| {
+                if size == 1 {
+                    let VMVerificationTargets {
+                        proof_with_pis_target: proof,
+                        vk_target: verifier_key,
+                    } = verify_recursive_vm_proof::<F, C, D>(
+                        &mut builder,
+                        VM_PUBLIC_INPUT_SIZE,
+                        config,
+                        VM_RECURSION_THRESHOLD_DEGREE_BITS,
+                    );
+                    let public_inputs: &VMRecursiveProofPublicInputs<Target> =
+                        proof.public_inputs.as_slice().into();
+                    let entry = VMAggregatedEntry {
+                        program_hash_as_bytes: public_inputs.program_hash_as_bytes,
+                        event_commitment_tape: public_inputs.event_commitment_tape,
+                        castlist_commitment_tape: public_inputs.castlist_commitment_tape,
+                        public_tape_commitment: public_inputs.public_tape_commitment,
+                        verifier_key_hash: verifier_key_hash_circuit::<F, C, D>(
+                            &mut builder,
+                            &verifier_key,
+                        )
+                        .elements,
+                    };
+                    builder.register_public_inputs(entry.array_ref());
+                    AggregationChild::Program {
+                        proof,
+                        verifier_key,
+                    }
+                } else {
+                    let circuit = Self::sub_tree(size, config, sub_trees);
+                    let proof = builder.add_virtual_proof_with_pis(&circuit.circuit.common);
+                    let verifier_key =
+                        builder.constant_verifier_data(&circuit.circuit.verifier_only);
+                    builder.verify_proof::<C>(&proof, &verifier_key, &circuit.circuit.common);
+                    // The entries of the sub-tree are carried forward as they are.
+                    builder.register_public_inputs(&proof.public_inputs);
+                    AggregationChild::Aggregate { proof, circuit }
+                }
+            })
+            .collect_vec();
+
+        // The circuits of the sub-trees are baked in as constants, so their
+        // digests are part of the cache key.
+        let sub_tree_digests = children
+            .iter()
+            .filter_map(|child| match child {
+                AggregationChild::Program { .. } => None,
+                AggregationChild::Aggregate { circuit, .. } =>
+                    Some(circuit.circuit.verifier_only.circuit_digest),
+            })
+            .collect_vec();
+        let circuit = build_circuit(
+            "vm_aggregation",
+            &(num_proofs, config, sub_tree_digests),
+            builder,
+        );
+        VMAggregationCircuit {
+            circuit,
+            num_proofs,
+            children,
+        }
+    }
+
+    /// Aggregates `proofs`, each a program's recursive proof together with
+    /// the verifier key it verifies against.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the number of proofs does not match the circuit, or if
+    /// proving fails, e.g. because a proof does not verify.
+    pub fn prove(
+        &self,
+        proofs: &[(
+            ProofWithPublicInputs<F, C, D>,
+            VerifierOnlyCircuitData<C, D>,
+        )],
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            proofs.len() == self.num_proofs,
+            "Expected {} proofs to aggregate, got {}",
+            self.num_proofs,
+            proofs.len()
+        );
+        let mut inputs = PartialWitness::new();
+        let mut rest = proofs;
+        for child in &self.children {
+            match child {
+                AggregationChild::Program {
+                    proof,
+                    verifier_key,
+                } => {
+                    let ((program_proof, program_verifier_key), remaining) =
+                        rest.split_first().expect("sizes of the children add up");
+                    inputs.set_proof_with_pis_target(proof, program_proof);
+                    inputs.set_verifier_data_target(verifier_key, program_verifier_key);
+                    rest = remaining;
+                }
+                AggregationChild::Aggregate { proof, circuit } => {
+                    let (sub_tree_proofs, remaining) = rest.split_at(circuit.num_proofs);
+                    inputs.set_proof_with_pis_target(proof, &circuit.prove(sub_tree_proofs)?);
+                    rest = remaining;
+                }
+            }
+        }
+        self.circuit.prove(inputs)
+    }
+}
+
 /// Flat hash of trace cap.
 pub fn hash_trace_cap_circuit<F, C, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
@@ -1170,6 +1496,95 @@ where
         .unwrap()
 }
 
+/// Commitment to the consumed public tape, as found in the public inputs of
+/// the recursive proofs.
+///
+/// Each byte of the tape is absorbed by its own permutation of `H`, so that the
+/// recursive circuits can skip the rows that pad the public bytes of the tape.
+#[must_use]
+pub fn public_tape_commitment<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    tape: &[u8],
+) -> HashOut<F> {
+    let mut state = H::Permutation::new(repeat(F::ZERO));
+    for &byte in tape {
+        state.set_elt(F::from_canonical_u8(byte), 0);
+        state.permute();
+    }
+    HashOut::from_partial(&state.squeeze()[..NUM_HASH_OUT_ELTS])
+}
+
+/// Compute [`public_tape_commitment`] in circuit, from the rows of the
+/// `StorageDevicePublic` table made public by
+/// [`make_public_tape_public`](crate::storage_device::columns::make_public_tape_public).
+///
+/// The rows are only public as a multiset, so we check that they come in
+/// the order of the tape: the `i`th row, if present, holds the `i`th byte.
+/// The present rows come first, and the rest pad the subtable.
+pub fn public_tape_commitment_circuit<F, C, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    public_tape_rows: &[PublicTapeRow<Target>],
+) -> HashOutTarget
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    let zero = builder.zero();
+    let mut state: <C::InnerHasher as AlgebraicHasher<F>>::AlgebraicPermutation =
+        PlonkyPermutation::new(repeat(zero));
+    for (
+        index,
+        &PublicTapeRow {
+            is_memory_store,
+            tape_index,
+            value,
+        },
+    ) in public_tape_rows.iter().enumerate()
+    {
+        let index = builder.constant(F::from_canonical_usize(index));
+        let misplaced = builder.sub(tape_index, index);
+        let misplaced = builder.mul(is_memory_store, misplaced);
+        builder.connect(misplaced, zero);
+        let mut absorbed = state.clone();
+        absorbed.set_elt(value, 0);
+        let permuted = builder.permute::<C::InnerHasher>(absorbed);
+        // `is_memory_store` tells present rows from padding, and is binary by
+        // the checks of the public subtable.
+        let is_memory_store = BoolTarget::new_unsafe(is_memory_store);
+        state = PlonkyPermutation::new(
+            zip_eq(permuted.as_ref(), state.as_ref())
+                .map(|(&new, &old)| builder.select(is_memory_store, new, old))
+                .collect_vec(),
+        );
+    }
+    HashOutTarget::from_partial(&state.squeeze()[..NUM_HASH_OUT_ELTS], zero)
+}
+
+/// Register the values of the public sub tables as public inputs, except for
+/// the public tape, which is replaced by its [`public_tape_commitment`].
+fn register_public_sub_table_values<F, C, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    mozak_stark: &MozakStark<F, D>,
+    public_sub_table_values_targets: &TableKindArray<Vec<PublicSubTableValuesTarget>>,
+) where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>, {
+    all_kind!(|kind| if kind != TableKind::StorageDevicePublic {
+        builder.register_public_inputs(
+            &public_sub_table_values_targets[kind]
+                .clone()
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect_vec(),
+        );
+    });
+    let public_tape_rows = mozak_stark
+        .public_sub_table_rows::<PublicTape, _>(public_sub_table_values_targets)
+        .expect("targets are laid out like the public sub tables");
+    let public_tape_commitment =
+        public_tape_commitment_circuit::<F, C, D>(builder, &public_tape_rows);
+    builder.register_public_inputs(&public_tape_commitment.elements);
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1177,29 +1592,42 @@ mod tests {
     use std::panic::AssertUnwindSafe;
 
     use anyhow::Result;
+    use itertools::{chain, zip_eq};
     use log::info;
     use mozak_runner::code;
+    use mozak_runner::decode::ECALL;
     use mozak_runner::instruction::{Args, Instruction, Op};
+    use mozak_runner::state::RawTapes;
     use mozak_sdk::core::constants::DIGEST_BYTES;
+    use mozak_sdk::core::ecall;
+    use mozak_sdk::core::reg_abi::{REG_A0, REG_A1, REG_A2};
     use plonky2::field::goldilocks_field::GoldilocksField;
     use plonky2::field::types::Field;
     use plonky2::iop::witness::{PartialWitness, WitnessWrite};
     use plonky2::plonk::circuit_builder::CircuitBuilder;
-    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
+    use plonky2::plonk::config::GenericConfig;
+    use plonky2::plonk::proof::ProofWithPublicInputs;
     use plonky2::util::timing::TimingTree;
     use starky::config::StarkConfig;
+    use starky::constraint_consumer::RecursiveConstraintConsumer;
+    use starky::evaluation_frame::StarkEvaluationFrame;
+    use starky::stark::Stark;
 
     use crate::stark::batch_prover::batch_prove;
     use crate::stark::batch_verifier::batch_verify_proof;
-    use crate::stark::mozak_stark::{MozakStark, PublicInputs, PUBLIC_TABLE_KINDS};
+    use crate::stark::mozak_stark::{
+        all_starks, MozakStark, PublicInputs, PublicTape, PUBLIC_TABLE_KINDS,
+    };
     use crate::stark::prover::prove;
     use crate::stark::recursive_verifier::{
         recursive_batch_stark_circuit, recursive_mozak_stark_circuit,
-        shrink_to_target_degree_bits_circuit, verify_recursive_vm_proof,
-        VMRecursiveProofPublicInputs, VM_PUBLIC_INPUT_SIZE, VM_RECURSION_CONFIG,
-        VM_RECURSION_THRESHOLD_DEGREE_BITS,
+        shrink_to_target_degree_bits_circuit, verifier_key_hash, verify_recursive_vm_proof,
+        VMAggregatedEntry, VMAggregationCircuit, VMRecursiveProofPublicInputs,
+        VM_PUBLIC_INPUT_SIZE, VM_RECURSION_CONFIG, VM_RECURSION_THRESHOLD_DEGREE_BITS,
     };
     use crate::stark::verifier::verify_proof;
+    use crate::storage_device::columns::public_tape_bytes;
     use crate::test_utils::{C, D, F};
     use crate::utils::from_u32;
 
@@ -1264,6 +1692,124 @@ mod tests {
         mozak_stark_circuit.circuit.verify(recursive_proof)
     }
 
+    /// The number of gates `stark` adds to a recursive verifier to evaluate
+    /// its constraints.
+    fn constraint_gates<T: Stark<F, D>>(stark: &T) -> usize {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let local_values = builder.add_virtual_extension_targets(T::COLUMNS);
+        let next_values = builder.add_virtual_extension_targets(T::COLUMNS);
+        let public_inputs = builder.add_virtual_extension_targets(T::PUBLIC_INPUTS);
+        let vars =
+            T::EvaluationFrameTarget::from_values(&local_values, &next_values, &public_inputs);
+        let zero = builder.zero_extension();
+        let alphas = builder.add_virtual_targets(2);
+        let mut consumer = RecursiveConstraintConsumer::new(zero, alphas, zero, zero, zero);
+        let before = builder.num_gates();
+        stark.eval_ext_circuit(&mut builder, &vars, &mut consumer);
+        builder.num_gates() - before
+    }
+
+    // TODO: pin the gate counts of every table with `assert_eq!`, so that a
+    // change in either direction shows up in review.
+    #[test]
+    fn simplification_never_costs_recursive_gates() {
+        let stark = S::default();
+        let gates = all_starks!(&stark, |stark, kind| {
+            let simplified = constraint_gates(stark);
+            let unsimplified = expr::without_simplification(|| constraint_gates(stark));
+            assert!(
+                simplified <= unsimplified,
+                "{kind:?}: {simplified} gates, {unsimplified} without simplification"
+            );
+            (simplified, unsimplified)
+        });
+        let simplified: usize = gates.0.iter().map(|&(simplified, _)| simplified).sum();
+        let unsimplified: usize = gates.0.iter().map(|&(_, unsimplified)| unsimplified).sum();
+        assert!(
+            simplified < unsimplified,
+            "simplification saves no gates: {simplified} gates, {unsimplified} without"
+        );
+    }
+
+    #[test]
+    fn recursive_proof_commits_to_public_tape() -> Result<()> {
+        let stark = S::default();
+        let config = StarkConfig::standard_fast_config();
+        let public_tape = vec![0xDE, 0xAD, 0xBE, 0xEF];
+        let read_public_tape = |size: u32| {
+            [
+                Instruction {
+                    op: Op::ADD,
+                    args: Args {
+                        rd: REG_A0,
+                        imm: ecall::PUBLIC_TAPE,
+                        ..Args::default()
+                    },
+                },
+                Instruction {
+                    op: Op::ADD,
+                    args: Args {
+                        rd: REG_A2,
+                        imm: size,
+                        ..Args::default()
+                    },
+                },
+                ECALL,
+            ]
+        };
+        // Read the tape in two chunks, to check that we commit across ecalls.
+        let (program, record) = code::execute_code_with_ro_memory(
+            chain!(read_public_tape(3), read_public_tape(1)),
+            &[],
+            &[(1024, 0), (1025, 0), (1026, 0)],
+            &[(REG_A1, 1024)],
+            RawTapes {
+                public_tape: public_tape.clone(),
+                ..Default::default()
+            },
+        );
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+
+        let mozak_proof = prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        // Only the bytes of the tape are public, not the ecalls or the padding.
+        let public_tape_rows =
+            stark.public_sub_table_rows::<PublicTape, _>(&mozak_proof.public_sub_table_values)?;
+        assert_eq!(public_tape_rows.len(), public_tape.len());
+        assert_eq!(public_tape_bytes(&public_tape_rows)?, public_tape);
+
+        let mozak_stark_circuit = recursive_mozak_stark_circuit::<F, C, D>(
+            &stark,
+            &mozak_proof.degree_bits(&config),
+            &CircuitConfig::standard_recursion_config(),
+            &config,
+        );
+
+        let recursive_proof = mozak_stark_circuit.prove(&mozak_proof)?;
+        let public_input_slice: [F; VM_PUBLIC_INPUT_SIZE] =
+            recursive_proof.public_inputs.as_slice().try_into().unwrap();
+        let recursive_proof_public_inputs: &VMRecursiveProofPublicInputs<F> =
+            &public_input_slice.into();
+        type H = <C as GenericConfig<D>>::InnerHasher;
+        recursive_proof_public_inputs.check_public_tape::<H>(&public_tape)?;
+        assert!(recursive_proof_public_inputs
+            .check_public_tape::<H>(&public_tape[..3])
+            .is_err());
+        assert!(recursive_proof_public_inputs
+            .check_public_tape::<H>(&[0xDE, 0xAD, 0xBE, 0xEE])
+            .is_err());
+
+        mozak_stark_circuit.circuit.verify(recursive_proof)
+    }
+
     #[test]
     fn recursive_verify_batch_starks() -> Result<()> {
         let stark = S::default();
@@ -1491,4 +2037,116 @@ mod tests {
 
         Ok(())
     }
+
+    fn shrunk_vm_proof(
+        n: usize,
+    ) -> Result<(
+        ProofWithPublicInputs<F, C, D>,
+        VerifierOnlyCircuitData<C, D>,
+    )> {
+        let stark = S::default();
+        let config = StarkConfig::standard_fast_config();
+        let inst = Instruction {
+            op: Op::ADD,
+            args: Args {
+                rd: 5,
+                rs1: 6,
+                rs2: 7,
+                ..Args::default()
+            },
+        };
+        let (program, record) = code::execute(vec![inst; n], &[], &[(6, 100), (7, 200)]);
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+        let mozak_proof = prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        let recursion_circuit = recursive_mozak_stark_circuit::<F, C, D>(
+            &stark,
+            &mozak_proof.degree_bits(&config),
+            &VM_RECURSION_CONFIG,
+            &config,
+        );
+        let recursion_proof = recursion_circuit.prove(&mozak_proof)?;
+        let (final_circuit, final_proof) = shrink_to_target_degree_bits_circuit(
+            &recursion_circuit.circuit.verifier_only,
+            &recursion_circuit.circuit.common,
+            &VM_RECURSION_CONFIG,
+            VM_RECURSION_THRESHOLD_DEGREE_BITS,
+            &recursion_proof,
+        )?;
+        Ok((final_proof, final_circuit.circuit.verifier_only))
+    }
+
+    #[test]
+    #[ignore]
+    fn aggregate_vm_proofs() -> Result<()> {
+        let proofs = vec![
+            shrunk_vm_proof(1)?,
+            shrunk_vm_proof(128)?,
+            shrunk_vm_proof(1)?,
+        ];
+
+        let aggregation_circuit =
+            VMAggregationCircuit::<F, C, D>::new(proofs.len(), &VM_RECURSION_CONFIG);
+        let aggregated_proof = aggregation_circuit.prove(&proofs)?;
+
+        let entries = VMAggregatedEntry::from_public_inputs(&aggregated_proof.public_inputs);
+        assert_eq!(entries.len(), proofs.len());
+        for (entry, (proof, verifier_only)) in zip_eq(&entries, &proofs) {
+            let public_inputs: &VMRecursiveProofPublicInputs<F> =
+                proof.public_inputs.as_slice().into();
+            assert_eq!(
+                entry.program_hash_as_bytes,
+                public_inputs.program_hash_as_bytes
+            );
+            assert_eq!(
+                entry.event_commitment_tape,
+                public_inputs.event_commitment_tape
+            );
+            assert_eq!(
+                entry.castlist_commitment_tape,
+                public_inputs.castlist_commitment_tape
+            );
+            assert_eq!(
+                entry.public_tape_commitment,
+                public_inputs.public_tape_commitment
+            );
+            // The programs do not read their public tape.
+            entry.check_public_tape::<<C as GenericConfig<D>>::InnerHasher>(&[])?;
+            assert_eq!(
+                entry.verifier_key_hash,
+                verifier_key_hash::<F, C, D>(verifier_only).elements
+            );
+        }
+
+        // The circuits only depend on the number of proofs, so verifiers can
+        // rebuild them.
+        let rebuilt = VMAggregationCircuit::<F, C, D>::new(proofs.len(), &VM_RECURSION_CONFIG);
+        assert_eq!(
+            rebuilt.circuit.verifier_only,
+            aggregation_circuit.circuit.verifier_only
+        );
+        rebuilt.circuit.verify(aggregated_proof)?;
+
+        // Proofs do not aggregate against the wrong verifier key.
+        let mut wrong_keys = proofs.clone();
+        wrong_keys[0].1 = proofs[1].1.clone();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let proof = aggregation_circuit.prove(&wrong_keys)?;
+            aggregation_circuit.circuit.verify(proof)
+        }));
+        assert!(
+            !matches!(result, Ok(Ok(()))),
+            "Aggregation with a wrong verifier key did not fail as expected"
+        );
+
+        Ok(())
+    }
 }
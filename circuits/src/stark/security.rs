@@ -0,0 +1,208 @@
+//! Named security profiles, fixing the [`StarkConfig`] of the STARK proofs and
+//! the [`CircuitConfig`] of the recursion circuits that verify them.
+//!
+//! The security of FRI is estimated as `rate_bits` bits per query round plus
+//! the proof of work bits, under the usual conjecture that FRI is sound up to
+//! the list-decoding radius.  None of the profiles is provably secure: the
+//! Goldilocks extension field is too small for a provable 128 bits.
+//!
+//! All profiles commit to traces with the `rate_bits` and `cap_height` of
+//! [`program_id_config`], so the trace caps of the public tables, and with them
+//! the [`ProgramIdentifier`](mozak_sdk::common::types::ProgramIdentifier) of a
+//! program, are the same under every profile.  The profiles only differ in
+//! their number of query rounds and proof of work.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use plonky2::fri::FriConfig;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use starky::config::StarkConfig;
+
+use super::recursive_verifier::VM_RECURSION_CONFIG;
+
+/// The config that
+/// [`ProgramIdentifier`](mozak_sdk::common::types::ProgramIdentifier)s are
+/// computed with, from the trace caps of the public tables.
+///
+/// Only its `rate_bits` and `cap_height` matter, which every
+/// [`SecurityProfile`] shares.
+#[must_use]
+pub fn program_id_config() -> StarkConfig { StarkConfig::standard_fast_config() }
+
+/// A named pair of [`StarkConfig`] and recursion [`CircuitConfig`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SecurityProfile {
+    /// Barely any security, for tests only.
+    Test,
+    /// [`StarkConfig::standard_fast_config`]: 100 conjectured bits with the
+    /// fastest prover.
+    #[default]
+    Fast,
+    /// 100 conjectured bits with more proof of work, for fewer query rounds
+    /// and so smaller proofs.
+    Bits100,
+    /// 128 conjectured bits.
+    Bits128Conjectured,
+    /// 128 conjectured bits, even if a query round only gets half a bit per
+    /// rate bit, as it would at the Johnson bound.
+    Bits128Conservative,
+}
+
+impl SecurityProfile {
+    pub const ALL: [Self; 5] = [
+        Self::Test,
+        Self::Fast,
+        Self::Bits100,
+        Self::Bits128Conjectured,
+        Self::Bits128Conservative,
+    ];
+
+    /// The name of the profile, as accepted by [`FromStr`].
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Test => "test",
+            Self::Fast => "fast",
+            Self::Bits100 => "100-bit",
+            Self::Bits128Conjectured => "128-bit-conjectured",
+            Self::Bits128Conservative => "128-bit-conservative",
+        }
+    }
+
+    /// The config of the STARK proofs.
+    #[must_use]
+    pub fn stark_config(self) -> StarkConfig {
+        let fast = program_id_config();
+        let with_queries = |security_bits, proof_of_work_bits, num_query_rounds| StarkConfig {
+            security_bits,
+            fri_config: FriConfig {
+                proof_of_work_bits,
+                num_query_rounds,
+                ..fast.fri_config.clone()
+            },
+            ..fast.clone()
+        };
+        match self {
+            Self::Test => with_queries(1, 0, 5),
+            Self::Fast => fast.clone(),
+            Self::Bits100 => with_queries(100, 24, 76),
+            Self::Bits128Conjectured => with_queries(128, 16, 112),
+            Self::Bits128Conservative => with_queries(128, 16, 224),
+        }
+    }
+
+    /// The config of the circuits that recursively verify the STARK proofs.
+    #[must_use]
+    pub fn recursion_config(self) -> CircuitConfig {
+        let with_query_rounds = |num_query_rounds| CircuitConfig {
+            security_bits: 128,
+            fri_config: FriConfig {
+                num_query_rounds,
+                ..VM_RECURSION_CONFIG.fri_config
+            },
+            ..VM_RECURSION_CONFIG
+        };
+        match self {
+            Self::Test | Self::Fast | Self::Bits100 => VM_RECURSION_CONFIG,
+            Self::Bits128Conjectured => with_query_rounds(38),
+            Self::Bits128Conservative => with_query_rounds(75),
+        }
+    }
+
+    /// The profile whose STARK config is `config`, if any.
+    #[must_use]
+    pub fn of(config: &StarkConfig) -> Option<Self> {
+        Self::ALL.into_iter().find(|profile| {
+            let profile_config = profile.stark_config();
+            profile_config.security_bits == config.security_bits
+                && profile_config.num_challenges == config.num_challenges
+                && profile_config.fri_config == config.fri_config
+        })
+    }
+
+    pub(crate) fn tag(self) -> u8 { self as u8 }
+
+    pub(crate) fn from_tag(tag: u8) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.tag() == tag)
+            .ok_or_else(|| anyhow!("Unknown security profile {tag}"))
+    }
+}
+
+impl fmt::Display for SecurityProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+impl FromStr for SecurityProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        if name == "128-bit-proven" {
+            return Err(anyhow!(
+                "No profile is provably 128-bit secure over the Goldilocks quadratic extension; \
+                 128-bit-conservative comes closest"
+            ));
+        }
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name() == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown security profile {name:?}, expected one of {}",
+                    Self::ALL.map(Self::name).join(", ")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_identified_by_name_and_config() {
+        for profile in SecurityProfile::ALL {
+            assert_eq!(profile.name().parse::<SecurityProfile>().unwrap(), profile);
+            assert_eq!(SecurityProfile::of(&profile.stark_config()), Some(profile));
+            assert_eq!(SecurityProfile::from_tag(profile.tag()).unwrap(), profile);
+        }
+        assert!("64-bit".parse::<SecurityProfile>().is_err());
+        assert!("128-bit-proven".parse::<SecurityProfile>().is_err());
+    }
+
+    #[test]
+    fn profiles_share_program_ids() {
+        let program_id_config = program_id_config();
+        for profile in SecurityProfile::ALL {
+            let config = profile.stark_config();
+            assert_eq!(
+                config.fri_config.rate_bits,
+                program_id_config.fri_config.rate_bits
+            );
+            assert_eq!(
+                config.fri_config.cap_height,
+                program_id_config.fri_config.cap_height
+            );
+        }
+    }
+
+    #[test]
+    fn profiles_meet_their_security_bits() {
+        for profile in SecurityProfile::ALL {
+            let config = profile.stark_config();
+            let fri_config = &config.fri_config;
+            // In half bits, as the conservative estimate only gets half a
+            // bit per query round and rate bit.
+            let half_bits_per_query = match profile {
+                SecurityProfile::Bits128Conservative => fri_config.rate_bits,
+                _ => 2 * fri_config.rate_bits,
+            };
+            let half_bits = half_bits_per_query * fri_config.num_query_rounds
+                + 2 * usize::try_from(fri_config.proof_of_work_bits).unwrap();
+            assert!(half_bits >= 2 * config.security_bits, "{profile}");
+        }
+    }
+}
@@ -0,0 +1,727 @@
+//! Compact, versioned binary encoding of [`AllProof`]s and [`BatchProof`]s.
+//!
+//! A serialized proof starts with a [`ProofHeader`]: the magic bytes `MZKP`,
+//! the format version, the [`ProofKind`], the [`StarkConfig`] the proof was
+//! produced with, the [`SecurityProfile`] of that config, if any, and the
+//! [`TableKind`]s it covers.  The proof follows, with field elements written
+//! as canonical little-endian `u64`s, hashes in their canonical byte form and
+//! variable-length sequences prefixed by their `u32` length.
+
+use std::mem::size_of;
+
+use anyhow::{anyhow, ensure, Result};
+use itertools::Itertools;
+use mozak_sdk::common::types::{Poseidon2Hash, ProgramIdentifier};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::fri::proof::{FriInitialTreeProof, FriProof, FriQueryRound, FriQueryStep};
+use plonky2::fri::reduction_strategies::FriReductionStrategy;
+use plonky2::fri::FriConfig;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_proofs::MerkleProof;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::{GenericConfig, GenericHashOut, Hasher};
+use starky::config::StarkConfig;
+
+use super::mozak_stark::{all_kind, PublicInputs, TableKind, TableKindArray};
+use super::proof::{AllProof, BatchProof, StarkOpeningSet, StarkProof};
+use super::security::SecurityProfile;
+
+/// Magic bytes at the start of every serialized proof.
+pub const PROOF_MAGIC: [u8; 4] = *b"MZKP";
+
+/// Version of the binary proof format written by this crate.
+pub const PROOF_FORMAT_VERSION: u16 = 2;
+
+/// Tag of proofs made with a config that is not a [`SecurityProfile`].
+const CUSTOM_PROFILE_TAG: u8 = u8::MAX;
+
+/// The kind of proof that follows a [`ProofHeader`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofKind {
+    /// An [`AllProof`], with one FRI proof per table.
+    All,
+    /// A [`BatchProof`], which batches the FRI proofs of all private tables.
+    Batch,
+}
+
+impl ProofKind {
+    fn tag(self) -> u8 {
+        match self {
+            ProofKind::All => 0,
+            ProofKind::Batch => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(ProofKind::All),
+            1 => Ok(ProofKind::Batch),
+            _ => Err(anyhow!("Unknown proof kind {tag}")),
+        }
+    }
+}
+
+/// Metadata at the start of a serialized proof.
+#[derive(Clone, Debug)]
+pub struct ProofHeader {
+    pub version: u16,
+    pub kind: ProofKind,
+    pub config: StarkConfig,
+    pub profile: Option<SecurityProfile>,
+    pub table_kinds: Vec<TableKind>,
+}
+
+impl ProofHeader {
+    fn new(kind: ProofKind, config: &StarkConfig) -> Self {
+        Self {
+            version: PROOF_FORMAT_VERSION,
+            kind,
+            config: config.clone(),
+            profile: SecurityProfile::of(config),
+            table_kinds: all_kind!(|kind| kind).iter().copied().collect(),
+        }
+    }
+
+    /// Reads just the header of the serialized proof in `bytes`, eg to find
+    /// out which kind of proof it holds.
+    pub fn read(bytes: &[u8]) -> Result<Self> { Reader::new(bytes).header() }
+
+    /// Checks that the proof was made with the config of `profile`.
+    pub fn check_profile(&self, profile: SecurityProfile) -> Result<()> {
+        match self.profile {
+            Some(actual) if actual == profile => Ok(()),
+            Some(actual) => Err(anyhow!(
+                "Proof was made with the {actual} security profile, but expected {profile}"
+            )),
+            None => Err(anyhow!(
+                "Proof was made with a custom config, but expected the {profile} security profile"
+            )),
+        }
+    }
+
+    /// Checks that the proof that follows this header can be decoded as a
+    /// proof of `kind` by this build.
+    fn check(&self, kind: ProofKind) -> Result<()> {
+        ensure!(
+            self.kind == kind,
+            "Expected {kind:?} proof, but found {:?} proof",
+            self.kind
+        );
+        let table_kinds = all_kind!(|kind| kind).iter().copied().collect_vec();
+        ensure!(
+            self.table_kinds == table_kinds,
+            "Proof covers tables {:?}, but expected {table_kinds:?}",
+            self.table_kinds
+        );
+        Ok(())
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> AllProof<F, C, D> {
+    /// Serializes the proof, produced with `config`, in the binary proof
+    /// format.
+    #[must_use]
+    pub fn to_bytes(&self, config: &StarkConfig) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.header(&ProofHeader::new(ProofKind::All, config));
+        writer.tables(&self.proofs, Writer::stark_proof::<F, C, D>);
+        writer.public_values(&self.public_inputs, &self.public_sub_table_values);
+        writer.program_id(self.program_id);
+        writer.0
+    }
+
+    /// Deserializes a proof in the binary proof format, together with its
+    /// header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(ProofHeader, Self)> {
+        let mut reader = Reader::new(bytes);
+        let header = reader.header()?;
+        header.check(ProofKind::All)?;
+        let proof = AllProof {
+            proofs: reader.tables(Reader::stark_proof::<F, C, D>)?,
+            public_inputs: reader.public_inputs()?,
+            public_sub_table_values: reader.tables(Reader::public_sub_table_values)?,
+            program_id: reader.program_id()?,
+        };
+        reader.finish()?;
+        Ok((header, proof))
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> BatchProof<F, C, D> {
+    /// Serializes the proof, produced with `config` over traces of
+    /// `degree_bits`, in the binary proof format.
+    ///
+    /// The degree bits are part of the encoding, because, unlike for
+    /// [`AllProof`]s, they cannot be recovered from the proof itself.
+    #[must_use]
+    pub fn to_bytes(&self, degree_bits: &TableKindArray<usize>, config: &StarkConfig) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.header(&ProofHeader::new(ProofKind::Batch, config));
+        writer.tables(degree_bits, |writer, &bits| writer.usize(bits));
+        writer.tables(&self.proofs, Writer::stark_proof::<F, C, D>);
+        writer.stark_proof::<F, C, D>(&self.batch_stark_proof);
+        writer.public_values(&self.public_inputs, &self.public_sub_table_values);
+        writer.program_id(self.program_id);
+        writer.0
+    }
+
+    /// Deserializes a proof in the binary proof format, together with its
+    /// header and the degree bits of its traces.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(ProofHeader, TableKindArray<usize>, Self)> {
+        let mut reader = Reader::new(bytes);
+        let header = reader.header()?;
+        header.check(ProofKind::Batch)?;
+        let degree_bits = reader.tables(Reader::usize)?;
+        let proofs = reader.tables(Reader::stark_proof::<F, C, D>)?;
+        let batch_stark_proof = reader.stark_proof::<F, C, D>()?;
+        let proof = BatchProof {
+            proofs,
+            batch_stark_proof,
+            public_inputs: reader.public_inputs()?,
+            public_sub_table_values: reader.tables(Reader::public_sub_table_values)?,
+            program_id: reader.program_id()?,
+        };
+        reader.finish()?;
+        Ok((header, degree_bits, proof))
+    }
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) { self.0.push(value); }
+
+    fn u16(&mut self, value: u16) { self.0.extend(value.to_le_bytes()); }
+
+    fn u32(&mut self, value: u32) { self.0.extend(value.to_le_bytes()); }
+
+    fn u64(&mut self, value: u64) { self.0.extend(value.to_le_bytes()); }
+
+    fn usize(&mut self, value: usize) { self.u64(value as u64); }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("sequence too long for the proof format"));
+    }
+
+    fn seq<T>(&mut self, items: &[T], mut write: impl FnMut(&mut Self, &T)) {
+        self.len(items.len());
+        for item in items {
+            write(self, item);
+        }
+    }
+
+    fn tables<T>(&mut self, tables: &TableKindArray<T>, mut write: impl FnMut(&mut Self, &T)) {
+        for table in tables {
+            write(self, table);
+        }
+    }
+
+    fn field<F: RichField>(&mut self, value: &F) { self.u64(value.to_canonical_u64()); }
+
+    fn fields<F: RichField>(&mut self, values: &[F]) { self.seq(values, Self::field); }
+
+    fn extension<F: RichField + Extendable<D>, const D: usize>(&mut self, value: &F::Extension) {
+        for limb in value.to_basefield_array() {
+            self.field(&limb);
+        }
+    }
+
+    fn extensions<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        values: &[F::Extension],
+    ) {
+        self.seq(values, Self::extension::<F, D>);
+    }
+
+    fn hash<F: RichField, H: Hasher<F>>(&mut self, hash: &H::Hash) {
+        self.0.extend(hash.to_bytes());
+    }
+
+    fn merkle_cap<F: RichField, H: Hasher<F>>(&mut self, cap: &MerkleCap<F, H>) {
+        self.seq(&cap.0, Self::hash::<F, H>);
+    }
+
+    fn merkle_proof<F: RichField, H: Hasher<F>>(&mut self, proof: &MerkleProof<F, H>) {
+        self.seq(&proof.siblings, Self::hash::<F, H>);
+    }
+
+    fn fri_proof<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize>(
+        &mut self,
+        proof: &FriProof<F, H, D>,
+    ) {
+        self.seq(&proof.commit_phase_merkle_caps, Self::merkle_cap);
+        self.seq(&proof.query_round_proofs, |writer, round| {
+            writer.seq(
+                &round.initial_trees_proof.evals_proofs,
+                |writer, (evals, merkle_proof)| {
+                    writer.fields(evals);
+                    writer.merkle_proof(merkle_proof);
+                },
+            );
+            writer.seq(&round.steps, |writer, step| {
+                writer.extensions::<F, D>(&step.evals);
+                writer.merkle_proof(&step.merkle_proof);
+            });
+        });
+        self.extensions::<F, D>(&proof.final_poly.coeffs);
+        self.field(&proof.pow_witness);
+    }
+
+    fn stark_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        &mut self,
+        proof: &StarkProof<F, C, D>,
+    ) {
+        self.merkle_cap(&proof.trace_cap);
+        self.merkle_cap(&proof.ctl_zs_cap);
+        self.merkle_cap(&proof.quotient_polys_cap);
+        let openings = &proof.openings;
+        self.extensions::<F, D>(&openings.local_values);
+        self.extensions::<F, D>(&openings.next_values);
+        self.extensions::<F, D>(&openings.ctl_zs);
+        self.extensions::<F, D>(&openings.ctl_zs_next);
+        self.fields(&openings.ctl_zs_last);
+        self.extensions::<F, D>(&openings.quotient_polys);
+        self.fri_proof(&proof.opening_proof);
+    }
+
+    fn public_values<F: RichField>(
+        &mut self,
+        public_inputs: &PublicInputs<F>,
+        public_sub_table_values: &TableKindArray<Vec<Vec<Vec<F>>>>,
+    ) {
+        self.field(&public_inputs.entry_point);
+        self.tables(public_sub_table_values, |writer, sub_tables| {
+            writer.seq(sub_tables, |writer, rows| {
+                writer.seq(rows, |writer, row| writer.fields(row));
+            });
+        });
+    }
+
+    fn program_id(&mut self, program_id: ProgramIdentifier) { self.0.extend(program_id.0 .0); }
+
+    fn config(&mut self, config: &StarkConfig) {
+        self.usize(config.security_bits);
+        self.usize(config.num_challenges);
+        let fri_config = &config.fri_config;
+        self.usize(fri_config.rate_bits);
+        self.usize(fri_config.cap_height);
+        self.u32(fri_config.proof_of_work_bits);
+        self.usize(fri_config.num_query_rounds);
+        match &fri_config.reduction_strategy {
+            FriReductionStrategy::Fixed(arities) => {
+                self.u8(0);
+                self.seq(arities, |writer, &arity| writer.usize(arity));
+            }
+            FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits) => {
+                self.u8(1);
+                self.usize(*arity_bits);
+                self.usize(*final_poly_bits);
+            }
+            FriReductionStrategy::MinSize(opt_max_arity_bits) => {
+                self.u8(2);
+                self.u8(u8::from(opt_max_arity_bits.is_some()));
+                self.usize(opt_max_arity_bits.unwrap_or_default());
+            }
+        }
+    }
+
+    fn header(&mut self, header: &ProofHeader) {
+        self.0.extend(PROOF_MAGIC);
+        self.u16(header.version);
+        self.u8(header.kind.tag());
+        self.config(&header.config);
+        let profile = header
+            .profile
+            .map_or(CUSTOM_PROFILE_TAG, SecurityProfile::tag);
+        self.u8(profile);
+        self.seq(&header.table_kinds, |writer, &kind| writer.u8(kind as u8));
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self { Self(bytes) }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(len <= self.0.len(), "Serialized proof ends unexpectedly");
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> { Ok(self.take(N)?.try_into().unwrap()) }
+
+    fn finish(&self) -> Result<()> {
+        ensure!(
+            self.0.is_empty(),
+            "{} trailing bytes after serialized proof",
+            self.0.len()
+        );
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8> { Ok(self.take(1)?[0]) }
+
+    fn u16(&mut self) -> Result<u16> { Ok(u16::from_le_bytes(self.array()?)) }
+
+    fn u32(&mut self) -> Result<u32> { Ok(u32::from_le_bytes(self.array()?)) }
+
+    fn u64(&mut self) -> Result<u64> { Ok(u64::from_le_bytes(self.array()?)) }
+
+    fn usize(&mut self) -> Result<usize> { Ok(usize::try_from(self.u64()?)?) }
+
+    fn seq<T>(&mut self, mut read: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let len = self.u32()? as usize;
+        // Every item takes up at least one byte, so this guards against
+        // allocating huge vectors for corrupted lengths.
+        ensure!(len <= self.0.len(), "Serialized proof ends unexpectedly");
+        (0..len).map(|_| read(self)).collect()
+    }
+
+    fn tables<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<TableKindArray<T>> {
+        Ok(all_kind!(|_kind| read(self)?))
+    }
+
+    fn field<F: RichField>(&mut self) -> Result<F> {
+        let value = self.u64()?;
+        ensure!(value < F::ORDER, "Non-canonical field element {value:#x}");
+        Ok(F::from_canonical_u64(value))
+    }
+
+    fn fields<F: RichField>(&mut self) -> Result<Vec<F>> { self.seq(Self::field) }
+
+    fn extension<F: RichField + Extendable<D>, const D: usize>(&mut self) -> Result<F::Extension> {
+        let mut limbs = [F::ZERO; D];
+        for limb in &mut limbs {
+            *limb = self.field()?;
+        }
+        Ok(F::Extension::from_basefield_array(limbs))
+    }
+
+    fn extensions<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+    ) -> Result<Vec<F::Extension>> {
+        self.seq(Self::extension::<F, D>)
+    }
+
+    fn hash<F: RichField, H: Hasher<F>>(&mut self) -> Result<H::Hash> {
+        let bytes = self.take(H::HASH_SIZE)?;
+        // Hashes are made of field elements, which have to be canonical just
+        // like the ones read by `field`.
+        for limb in bytes.chunks_exact(size_of::<u64>()) {
+            let value = u64::from_le_bytes(limb.try_into()?);
+            ensure!(value < F::ORDER, "Non-canonical hash element {value:#x}");
+        }
+        Ok(H::Hash::from_bytes(bytes))
+    }
+
+    fn merkle_cap<F: RichField, H: Hasher<F>>(&mut self) -> Result<MerkleCap<F, H>> {
+        Ok(MerkleCap(self.seq(Self::hash::<F, H>)?))
+    }
+
+    fn merkle_proof<F: RichField, H: Hasher<F>>(&mut self) -> Result<MerkleProof<F, H>> {
+        Ok(MerkleProof {
+            siblings: self.seq(Self::hash::<F, H>)?,
+        })
+    }
+
+    fn fri_proof<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize>(
+        &mut self,
+    ) -> Result<FriProof<F, H, D>> {
+        Ok(FriProof {
+            commit_phase_merkle_caps: self.seq(Self::merkle_cap)?,
+            query_round_proofs: self.seq(|reader| {
+                Ok(FriQueryRound {
+                    initial_trees_proof: FriInitialTreeProof {
+                        evals_proofs: reader
+                            .seq(|reader| Ok((reader.fields()?, reader.merkle_proof()?)))?,
+                    },
+                    steps: reader.seq(|reader| {
+                        Ok(FriQueryStep {
+                            evals: reader.extensions::<F, D>()?,
+                            merkle_proof: reader.merkle_proof()?,
+                        })
+                    })?,
+                })
+            })?,
+            final_poly: PolynomialCoeffs::new(self.extensions::<F, D>()?),
+            pow_witness: self.field()?,
+        })
+    }
+
+    fn stark_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+        &mut self,
+    ) -> Result<StarkProof<F, C, D>> {
+        Ok(StarkProof {
+            trace_cap: self.merkle_cap()?,
+            ctl_zs_cap: self.merkle_cap()?,
+            quotient_polys_cap: self.merkle_cap()?,
+            openings: StarkOpeningSet {
+                local_values: self.extensions::<F, D>()?,
+                next_values: self.extensions::<F, D>()?,
+                ctl_zs: self.extensions::<F, D>()?,
+                ctl_zs_next: self.extensions::<F, D>()?,
+                ctl_zs_last: self.fields()?,
+                quotient_polys: self.extensions::<F, D>()?,
+            },
+            opening_proof: self.fri_proof()?,
+        })
+    }
+
+    fn public_inputs<F: RichField>(&mut self) -> Result<PublicInputs<F>> {
+        Ok(PublicInputs {
+            entry_point: self.field()?,
+        })
+    }
+
+    fn public_sub_table_values<F: RichField>(&mut self) -> Result<Vec<Vec<Vec<F>>>> {
+        self.seq(|reader| reader.seq(Self::fields))
+    }
+
+    fn program_id(&mut self) -> Result<ProgramIdentifier> {
+        Ok(ProgramIdentifier(Poseidon2Hash(self.array()?)))
+    }
+
+    fn config(&mut self) -> Result<StarkConfig> {
+        let security_bits = self.usize()?;
+        let num_challenges = self.usize()?;
+        let rate_bits = self.usize()?;
+        let cap_height = self.usize()?;
+        let proof_of_work_bits = self.u32()?;
+        let num_query_rounds = self.usize()?;
+        let reduction_strategy = match self.u8()? {
+            0 => FriReductionStrategy::Fixed(self.seq(Self::usize)?),
+            1 => FriReductionStrategy::ConstantArityBits(self.usize()?, self.usize()?),
+            2 => {
+                let has_max_arity_bits = self.u8()? != 0;
+                let max_arity_bits = self.usize()?;
+                FriReductionStrategy::MinSize(has_max_arity_bits.then_some(max_arity_bits))
+            }
+            tag => return Err(anyhow!("Unknown FRI reduction strategy {tag}")),
+        };
+        Ok(StarkConfig {
+            security_bits,
+            num_challenges,
+            fri_config: FriConfig {
+                rate_bits,
+                cap_height,
+                proof_of_work_bits,
+                reduction_strategy,
+                num_query_rounds,
+            },
+        })
+    }
+
+    fn header(&mut self) -> Result<ProofHeader> {
+        ensure!(
+            self.array::<4>()? == PROOF_MAGIC,
+            "Not a serialized proof: bad magic bytes"
+        );
+        let version = self.u16()?;
+        ensure!(
+            version == PROOF_FORMAT_VERSION,
+            "Unsupported proof format version {version}, expected {PROOF_FORMAT_VERSION}"
+        );
+        let kind = ProofKind::from_tag(self.u8()?)?;
+        let config = self.config()?;
+        let profile = match self.u8()? {
+            CUSTOM_PROFILE_TAG => None,
+            tag => Some(SecurityProfile::from_tag(tag)?),
+        };
+        ensure!(
+            profile == SecurityProfile::of(&config),
+            "Security profile {profile:?} does not match the config of the proof"
+        );
+        let all_kinds = all_kind!(|kind| kind);
+        let table_kinds = self.seq(|reader| {
+            let tag = reader.u8()?;
+            all_kinds
+                .iter()
+                .copied()
+                .find(|&kind| kind as u8 == tag)
+                .ok_or_else(|| anyhow!("Unknown table kind {tag}"))
+        })?;
+        Ok(ProofHeader {
+            version,
+            kind,
+            config,
+            profile,
+            table_kinds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mozak_runner::code;
+    use mozak_runner::instruction::{Args, Instruction, Op};
+    use plonky2::field::types::{Field, Field64};
+    use plonky2::util::timing::TimingTree;
+
+    use super::*;
+    use crate::stark::batch_prover::batch_prove;
+    use crate::stark::batch_verifier::batch_verify_proof;
+    use crate::stark::mozak_stark::{MozakStark, PUBLIC_TABLE_KINDS};
+    use crate::stark::prover::prove;
+    use crate::stark::verifier::verify_proof;
+    use crate::test_utils::{fast_test_config, C, D, F};
+    use crate::utils::from_u32;
+
+    fn add_program() -> (
+        mozak_runner::elf::Program,
+        mozak_runner::vm::ExecutionRecord<F>,
+    ) {
+        code::execute(
+            [Instruction {
+                op: Op::ADD,
+                args: Args {
+                    rd: 5,
+                    rs1: 6,
+                    rs2: 7,
+                    ..Args::default()
+                },
+            }],
+            &[],
+            &[(6, 3), (7, 4)],
+        )
+    }
+
+    #[test]
+    fn all_proof_round_trip() {
+        let (program, record) = add_program();
+        let config = fast_test_config();
+        let stark = MozakStark::default();
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+        let proof = prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+
+        let bytes = proof.to_bytes(&config);
+        let json = serde_json::to_string(&proof).unwrap();
+        assert!(bytes.len() * 2 < json.len());
+
+        let header = ProofHeader::read(&bytes).unwrap();
+        assert_eq!(header.kind, ProofKind::All);
+        assert_eq!(header.config.fri_config, config.fri_config);
+        header.check_profile(SecurityProfile::Test).unwrap();
+        assert!(header.check_profile(SecurityProfile::Fast).is_err());
+
+        let (_, decoded) = AllProof::<F, C, D>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(&config), bytes);
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        verify_proof(&stark, decoded, &config).unwrap();
+
+        assert!(BatchProof::<F, C, D>::from_bytes(&bytes).is_err());
+        assert!(AllProof::<F, C, D>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(AllProof::<F, C, D>::from_bytes(&[&bytes[..], &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn batch_proof_round_trip() {
+        let (program, record) = add_program();
+        let config = fast_test_config();
+        let stark = MozakStark::default();
+        let public_inputs = PublicInputs {
+            entry_point: from_u32(program.entry_point),
+        };
+        let (proof, degree_bits) = batch_prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &PUBLIC_TABLE_KINDS,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+
+        let bytes = proof.to_bytes(&degree_bits, &config);
+        let json = serde_json::to_string(&proof).unwrap();
+        assert!(bytes.len() * 2 < json.len());
+
+        // Batching the FRI proofs of the private tables has to pay off.
+        let all_proof_bytes = prove::<F, C, D>(
+            &program,
+            &record,
+            &stark,
+            &config,
+            public_inputs,
+            &mut TimingTree::default(),
+        )
+        .unwrap()
+        .to_bytes(&config);
+        assert!(bytes.len() < all_proof_bytes.len());
+        assert_eq!(ProofHeader::read(&bytes).unwrap().kind, ProofKind::Batch);
+
+        let (_, decoded_degree_bits, decoded) = BatchProof::<F, C, D>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded_degree_bits, degree_bits);
+        assert_eq!(decoded.to_bytes(&degree_bits, &config), bytes);
+        batch_verify_proof(
+            &stark,
+            &PUBLIC_TABLE_KINDS,
+            decoded,
+            &config,
+            &decoded_degree_bits,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_foreign_headers() {
+        let config = fast_test_config();
+        let mut writer = Writer::default();
+        writer.header(&ProofHeader::new(ProofKind::All, &config));
+        let header = writer.0;
+        assert!(ProofHeader::read(&header).is_ok());
+
+        let mut bad_magic = header.clone();
+        bad_magic[0] = b'X';
+        assert!(ProofHeader::read(&bad_magic).is_err());
+
+        let mut bad_version = header.clone();
+        bad_version[4..6].copy_from_slice(&(PROOF_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(ProofHeader::read(&bad_version).is_err());
+
+        let mut bad_profile = header.clone();
+        let profile_index = header.len() - 1 - 4 - all_kind!(|kind| kind).iter().count();
+        bad_profile[profile_index] = SecurityProfile::Fast.tag();
+        assert!(ProofHeader::read(&bad_profile).is_err());
+
+        let mut bad_table_kind = header;
+        *bad_table_kind.last_mut().unwrap() = u8::MAX;
+        assert!(ProofHeader::read(&bad_table_kind).is_err());
+    }
+
+    #[test]
+    fn rejects_non_canonical_field_elements() {
+        let mut writer = Writer::default();
+        writer.u64(F::ORDER);
+        assert!(Reader::new(&writer.0).field::<F>().is_err());
+    }
+
+    #[test]
+    fn rejects_non_canonical_hashes() {
+        type H = <C as GenericConfig<D>>::Hasher;
+        let hash = H::hash_no_pad(&[F::ONE]);
+        let mut writer = Writer::default();
+        writer.hash::<F, H>(&hash);
+        assert_eq!(Reader::new(&writer.0).hash::<F, H>().unwrap(), hash);
+
+        writer.0[..size_of::<u64>()].copy_from_slice(&F::ORDER.to_le_bytes());
+        assert!(Reader::new(&writer.0).hash::<F, H>().is_err());
+    }
+}
@@ -31,3 +31,29 @@ pub fn trace_rows_to_poly_values<F: Field, Row: IntoIterator<Item = F>>(
 ) -> Vec<PolynomialValues<F>> {
     trace_to_poly_values(transpose_trace(trace_rows))
 }
+
+/// Peak resident memory of this process in bytes, as reported by the kernel.
+///
+/// Only available on Linux, `None` elsewhere.
+#[cfg(feature = "timing")]
+#[must_use]
+pub fn peak_memory_usage() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib << 10)
+}
+
+/// Log the peak resident memory of this process so far, after `stage`.
+#[cfg(feature = "timing")]
+pub fn log_peak_memory_usage(stage: &str) {
+    if let Some(peak) = peak_memory_usage() {
+        log::info!("Peak memory after {stage}: {} MiB", peak >> 20);
+    }
+}
@@ -0,0 +1,206 @@
+//! Description of how the cross table lookups of a [`MozakStark`] wire its
+//! tables together.
+//!
+//! The description is derived from the `StarkSet` metadata of [`MozakStark`]
+//! and its cross table lookups, so it can't go stale.  It serializes to JSON
+//! for tooling, and renders as a Graphviz diagram for reviews.
+
+use std::fmt::Write;
+
+use itertools::{izip, Itertools};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use serde::Serialize;
+use starky::stark::Stark;
+use thiserror::Error;
+
+use super::mozak_stark::{all_starks, MozakStark, TableKind, CROSS_TABLE_LOOKUP_NAMES};
+
+/// A table of a [`MozakStark`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TableDescription {
+    pub kind: TableKind,
+    /// The field of [`MozakStark`] that holds the Stark of the table.
+    pub field: &'static str,
+    pub stark: String,
+    pub num_columns: usize,
+    /// Number of public sub tables that make rows of this table public.
+    pub num_public_sub_tables: usize,
+}
+
+/// A cross table lookup of a [`MozakStark`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LookupDescription {
+    pub name: &'static str,
+    pub looking: Vec<TableKind>,
+    pub looked: Vec<TableKind>,
+    /// Number of values in the looked up tuples.
+    pub num_columns: usize,
+}
+
+/// All tables of a [`MozakStark`], and the cross table lookups between them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Wiring {
+    pub tables: Vec<TableDescription>,
+    pub cross_table_lookups: Vec<LookupDescription>,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum WiringError {
+    #[error(
+        "cross table lookup {0} has no looking tables, so nothing references its looked tables"
+    )]
+    NoLookingTables(&'static str),
+    #[error("cross table lookup {0} has no looked tables")]
+    NoLookedTables(&'static str),
+    #[error("table {0:?} takes part in no cross table lookup")]
+    Unconnected(TableKind),
+}
+
+fn num_columns<F, const D: usize, S>(_stark: &S) -> usize
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>, {
+    S::COLUMNS
+}
+
+impl Wiring {
+    #[must_use]
+    pub fn of<F: RichField + Extendable<D>, const D: usize>(
+        mozak_stark: &MozakStark<F, D>,
+    ) -> Self {
+        let tables = all_starks!(mozak_stark, |stark, kind| TableDescription {
+            kind,
+            field: kind.field_name(),
+            stark: stark.to_string(),
+            num_columns: num_columns::<F, D, _>(stark),
+            num_public_sub_tables: mozak_stark
+                .public_sub_tables
+                .iter()
+                .filter(|public_sub_table| public_sub_table.table.kind == kind)
+                .count(),
+        });
+        let cross_table_lookups = izip!(CROSS_TABLE_LOOKUP_NAMES, &mozak_stark.cross_table_lookups)
+            .map(|(name, ctl)| LookupDescription {
+                name,
+                looking: ctl.looking().iter().map(|table| table.kind).collect(),
+                looked: ctl.looked().iter().map(|table| table.kind).collect(),
+                num_columns: ctl
+                    .looking_tables
+                    .first()
+                    .map_or(0, |table| table.columns.len()),
+            })
+            .collect();
+        Self {
+            tables: tables.0.into(),
+            cross_table_lookups,
+        }
+    }
+
+    /// Checks that every cross table lookup has both looking and looked
+    /// tables, and that every table takes part in some cross table lookup.
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first violation found.
+    pub fn validate(&self) -> Result<(), WiringError> {
+        for ctl in &self.cross_table_lookups {
+            if ctl.looking.is_empty() {
+                return Err(WiringError::NoLookingTables(ctl.name));
+            }
+            if ctl.looked.is_empty() {
+                return Err(WiringError::NoLookedTables(ctl.name));
+            }
+        }
+        for table in &self.tables {
+            let connected = self
+                .cross_table_lookups
+                .iter()
+                .any(|ctl| ctl.looking.contains(&table.kind) || ctl.looked.contains(&table.kind));
+            if !connected {
+                return Err(WiringError::Unconnected(table.kind));
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the wiring as a Graphviz diagram, with cross table lookups as
+    /// nodes between their looking and looked tables.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph mozak_stark {\n    rankdir=LR;\n");
+        for table in &self.tables {
+            let style = if table.num_public_sub_tables > 0 {
+                ", style=bold"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    \"{:?}\" [shape=box, label=\"{:?}\\n{} columns\"{style}];",
+                table.kind, table.kind, table.num_columns
+            )
+            .unwrap();
+        }
+        for ctl in &self.cross_table_lookups {
+            writeln!(
+                dot,
+                "    \"{}\" [shape=ellipse, label=\"{}\\n{} columns\"];",
+                ctl.name, ctl.name, ctl.num_columns
+            )
+            .unwrap();
+            for kind in ctl.looking.iter().unique() {
+                writeln!(dot, "    \"{kind:?}\" -> \"{}\";", ctl.name).unwrap();
+            }
+            for kind in ctl.looked.iter().unique() {
+                writeln!(dot, "    \"{}\" -> \"{kind:?}\";", ctl.name).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{D, F};
+
+    #[test]
+    fn describes_all_tables_and_lookups() {
+        let wiring = Wiring::of(&MozakStark::<F, D>::default());
+        assert_eq!(wiring.validate(), Ok(()));
+        assert_eq!(
+            wiring.cross_table_lookups.len(),
+            CROSS_TABLE_LOOKUP_NAMES.len()
+        );
+
+        let cpu = &wiring.tables[TableKind::Cpu as usize];
+        assert_eq!((cpu.kind, cpu.field), (TableKind::Cpu, "cpu_stark"));
+        let skeleton = wiring
+            .cross_table_lookups
+            .iter()
+            .find(|ctl| ctl.name == "CpuToSkeletonTable")
+            .unwrap();
+        assert!(skeleton.looking.contains(&TableKind::Add));
+        assert_eq!(skeleton.looked, [TableKind::CpuSkeleton]);
+
+        let dot = wiring.to_dot();
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("\"Add\" -> \"CpuToSkeletonTable\";"));
+        assert!(dot.contains("\"CpuToSkeletonTable\" -> \"CpuSkeleton\";"));
+        assert!(serde_json::to_string(&wiring)
+            .unwrap()
+            .contains("\"field\":\"cpu_stark\""));
+    }
+
+    #[test]
+    fn rejects_lookups_without_looking_tables() {
+        let mut wiring = Wiring::of(&MozakStark::<F, D>::default());
+        wiring.cross_table_lookups[0].looking.clear();
+        assert_eq!(
+            wiring.validate(),
+            Err(WiringError::NoLookingTables(CROSS_TABLE_LOOKUP_NAMES[0]))
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use core::ops::Add;
 
+use anyhow::{ensure, Result};
+use itertools::Itertools;
+use mozak_circuits_derive::ColumnNames;
 use mozak_sdk::core::constants::DIGEST_BYTES;
 use mozak_sdk::core::reg_abi::REG_A1;
+use plonky2::field::types::PrimeField64;
 
 use crate::columns_view::{columns_view_impl, make_col_map, NumberOfColumns};
 use crate::cross_table_lookup::{Column, ColumnWithTypedInput};
 use crate::memory::columns::MemoryCtl;
+use crate::public_sub_table::PublicSubTableWithTypedOutput;
 use crate::register::RegisterCtl;
 use crate::stark::mozak_stark::{
     CallTapeTable, CastListCommitmentTapeTable, EventsCommitmentTapeTable, SelfProgIdTapeTable,
@@ -15,7 +20,7 @@ use crate::tape_commitments::columns::TapeCommitmentCTL;
 
 /// Operations (one-hot encoded)
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, ColumnNames)]
 pub struct Ops<T> {
     /// Binary filter column to represent a RISC-V SB operation.
     pub is_memory_store: T,
@@ -24,7 +29,7 @@ pub struct Ops<T> {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct StorageDevice<T> {
     /// Clock at memory access.
     pub clk: T,
@@ -38,6 +43,8 @@ pub struct StorageDevice<T> {
     pub ops: Ops<T>,
     /// Helper to decrease poly degree
     pub is_lv_and_nv_are_memory_rows: T,
+    /// Number of bytes of the tape consumed before this row.
+    pub tape_index: T,
 }
 
 columns_view_impl!(StorageDevice);
@@ -134,3 +141,62 @@ pub fn castlist_commitment_lookup_in_tape_commitments(
     };
     CastListCommitmentTapeTable::new(data, COL_MAP.ops.is_memory_store)
 }
+
+columns_view_impl!(PublicTapeRow);
+/// A byte of the public tape, as made public.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct PublicTapeRow<T> {
+    /// Always 1 in a proof.  In the recursive circuits, 0 marks the rows that
+    /// pad the subtable.
+    pub is_memory_store: T,
+    pub tape_index: T,
+    pub value: T,
+}
+
+/// Make the bytes of the public tape public, so that the recursive circuits
+/// can commit to the consumed public tape.
+///
+/// Only the rows that store a byte of the tape are public, not the rows of
+/// the ecalls or the padding.
+#[must_use]
+pub fn make_public_tape_public() -> PublicSubTableWithTypedOutput<PublicTapeRow<Column>> {
+    PublicSubTableWithTypedOutput {
+        table: StorageDevicePublicTable::new(
+            PublicTapeRow {
+                is_memory_store: COL_MAP.ops.is_memory_store,
+                tape_index: COL_MAP.tape_index,
+                value: COL_MAP.value,
+            },
+            COL_MAP.ops.is_memory_store,
+        ),
+        num_rows: None,
+    }
+}
+
+/// The consumed public tape, from the public rows of its table.
+///
+/// The rows are only public as a multiset, so we order them by their tape
+/// index.
+///
+/// # Errors
+///
+/// Errors if the rows are not the bytes of a tape, each at its own index
+/// from 0 on.
+pub fn public_tape_bytes<F: PrimeField64>(rows: &[PublicTapeRow<F>]) -> Result<Vec<u8>> {
+    rows.iter()
+        .sorted_by_key(|row| row.tape_index.to_canonical_u64())
+        .enumerate()
+        .map(|(index, row)| {
+            ensure!(
+                row.is_memory_store.is_one(),
+                "Public tape row {row:?} is not a byte"
+            );
+            ensure!(
+                row.tape_index == F::from_canonical_usize(index),
+                "Public tape has no byte at index {index}"
+            );
+            Ok(u8::try_from(row.value.to_canonical_u64())?)
+        })
+        .collect()
+}
@@ -8,12 +8,20 @@ use crate::generation::MIN_TRACE_LENGTH;
 use crate::memory::trace::get_memory_inst_clk;
 use crate::storage_device::columns::{Ops, StorageDevice};
 
-/// Pad the memory trace to a power of 2.
+/// Number the bytes of the tape, and pad the memory trace to a power of 2.
 #[must_use]
 fn pad_mem_trace<F: RichField>(mut trace: Vec<StorageDevice<F>>) -> Vec<StorageDevice<F>> {
+    let mut tape_index = F::ZERO;
+    for row in &mut trace {
+        row.tape_index = tape_index;
+        tape_index += row.ops.is_memory_store;
+    }
     trace.resize(
         trace.len().max(MIN_TRACE_LENGTH).next_power_of_two(),
-        StorageDevice::default(),
+        StorageDevice {
+            tape_index,
+            ..Default::default()
+        },
     );
     trace
 }
@@ -79,6 +87,7 @@ pub fn generate_storage_trace<F: RichField>(
                                 is_memory_store: is_storage_device_opcode(op),
                             },
                             is_lv_and_nv_are_memory_rows: F::from_bool(i + 1 != len),
+                            ..Default::default()
                         }
                     })
                 )
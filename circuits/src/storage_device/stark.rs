@@ -50,9 +50,15 @@ fn generate_constraints<'a, T: Copy>(
     let added = lv.addr + 1;
     let wrapped = added - (1 << 32);
     // nv.address == lv.address + 1 (wrapped)
-    constraints.always(lv.is_lv_and_nv_are_memory_rows * (nv.addr - added) * (nv.addr - wrapped));
+    constraints.always(
+        !lv.is_lv_and_nv_are_memory_rows.is_one()
+            | (nv.addr - added).is_zero()
+            | (nv.addr - wrapped).is_zero(),
+    );
     // nv.size == lv.size - 1 (not-wrapped)
-    constraints.transition(nv.is_lv_and_nv_are_memory_rows * (nv.size - (lv.size - 1)));
+    constraints.transition(
+        !nv.is_lv_and_nv_are_memory_rows.is_one() | (nv.size - (lv.size - 1)).is_zero(),
+    );
     // Edge cases:
     //  a) - storage_device with size = 0: <-- this case is solved since CTL from
     // CPU        a.1) is_lv_and_nv_are_memory_rows = 0 (no memory rows
@@ -62,8 +68,11 @@ fn generate_constraints<'a, T: Copy>(
     // If lv.is_storage_device() == 1 && lv.size != 0:
     //      lv.addr == nv.addr       <-- next row address must be the same !!!
     //      lv.size === nv.size - 1  <-- next row size is decreased
-    constraints.transition(lv.ops.is_storage_device * lv.size * (nv.addr - lv.addr));
-    constraints.transition(lv.ops.is_storage_device * lv.size * (nv.size - (lv.size - 1)));
+    constraints.transition(
+        !lv.ops.is_storage_device.is_one()
+            | lv.size.is_zero()
+            | ((nv.addr - lv.addr).is_zero() & (nv.size - (lv.size - 1)).is_zero()),
+    );
     // If lv.is_storage_device() == 1 && lv.size == 0:
     //      nv.is_memory() == 0 <-- next op can be only io - since size == 0
     // This one is ensured by:
@@ -72,7 +81,15 @@ fn generate_constraints<'a, T: Copy>(
 
     // If lv.is_storage_device() == 1 && nv.size != 0:
     //      nv.is_lv_and_nv_are_memory_rows == 1
-    constraints.always(lv.ops.is_storage_device * nv.size * (nv.is_lv_and_nv_are_memory_rows - 1));
+    constraints.always(
+        !lv.ops.is_storage_device.is_one()
+            | nv.size.is_zero()
+            | nv.is_lv_and_nv_are_memory_rows.is_one(),
+    );
+
+    // Memory rows number the bytes of the tape in the order they are consumed.
+    constraints.first_row(lv.tape_index);
+    constraints.transition(nv.tape_index - lv.tape_index - lv.ops.is_memory_store);
 
     constraints
 }
@@ -123,12 +140,14 @@ mod tests {
     use mozak_sdk::core::constants::DIGEST_BYTES;
     use mozak_sdk::core::ecall::{self};
     use mozak_sdk::core::reg_abi::{REG_A0, REG_A1, REG_A2};
+    use plonky2::field::types::Field;
     use plonky2::plonk::config::Poseidon2GoldilocksConfig;
     use proptest::prelude::ProptestConfig;
     use proptest::proptest;
     use starky::stark_testing::test_stark_circuit_constraints;
 
     use crate::stark::mozak_stark::MozakStark;
+    use crate::storage_device::columns::{public_tape_bytes, PublicTapeRow};
     use crate::storage_device::stark::StorageDeviceStark;
     use crate::test_utils::{ProveAndVerify, D, F};
 
@@ -490,4 +509,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn public_tape_bytes_follow_tape_index() {
+        let row = |tape_index: u64, value: u8| PublicTapeRow {
+            is_memory_store: F::ONE,
+            tape_index: F::from_canonical_u64(tape_index),
+            value: F::from_canonical_u8(value),
+        };
+        let rows = [row(1, 0xAD), row(0, 0xDE), row(2, 0xBE)];
+        assert_eq!(public_tape_bytes(&rows).unwrap(), [0xDE, 0xAD, 0xBE]);
+
+        // Every index has to hold exactly one byte.
+        assert!(public_tape_bytes(&[row(0, 0xDE), row(2, 0xBE)]).is_err());
+        assert!(public_tape_bytes(&[row(0, 0xDE), row(0, 0xAD)]).is_err());
+        let mut not_a_byte = row(0, 0);
+        not_a_byte.value = F::from_canonical_u16(0x100);
+        assert!(public_tape_bytes(&[not_a_byte]).is_err());
+    }
 }
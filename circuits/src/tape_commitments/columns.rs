@@ -1,8 +1,9 @@
+use mozak_circuits_derive::ColumnNames;
 use mozak_sdk::core::constants::DIGEST_BYTES;
 
 use crate::columns_view::{columns_view_impl, make_col_map};
 use crate::linear_combination::Column;
-use crate::public_sub_table::PublicSubTable;
+use crate::public_sub_table::PublicSubTableWithTypedOutput;
 use crate::stark::mozak_stark::{TableWithTypedOutput, TapeCommitmentsTable};
 
 make_col_map!(TAPE_COMMITMENTS, TapeCommitments);
@@ -16,7 +17,7 @@ columns_view_impl!(TapeCommitments);
 /// There is no definite order imposed on the rows of this
 /// table,
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct TapeCommitments<T> {
     pub commitment_byte_row: CommitmentByteWithIndex<T>,
     pub castlist_commitment_tape_multiplicity: T,
@@ -32,7 +33,7 @@ columns_view_impl!(CommitmentByteWithIndex);
 /// stark, while enforcing the original order in which bytes
 /// are to be read.
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct CommitmentByteWithIndex<T> {
     pub byte: T,
     pub index: T,
@@ -62,24 +63,38 @@ pub fn lookup_for_event_tape_commitment() -> TableWithTypedOutput<TapeCommitment
     )
 }
 
+columns_view_impl!(CommitmentByte);
+/// A byte of a tape commitment, as made public.
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct CommitmentByte<T> {
+    pub byte: T,
+}
+
+type PublicCommitmentBytes = PublicSubTableWithTypedOutput<CommitmentByte<Column>>;
+
 #[must_use]
-pub fn make_event_commitment_tape_public() -> PublicSubTable {
-    PublicSubTable {
+pub fn make_event_commitment_tape_public() -> PublicCommitmentBytes {
+    PublicSubTableWithTypedOutput {
         table: TapeCommitmentsTable::new(
-            vec![TAPE_COMMITMENTS.commitment_byte_row.byte],
+            CommitmentByte {
+                byte: TAPE_COMMITMENTS.commitment_byte_row.byte,
+            },
             TAPE_COMMITMENTS.is_event_commitment_tape_row,
         ),
-        num_rows: DIGEST_BYTES,
+        num_rows: Some(DIGEST_BYTES),
     }
 }
 
 #[must_use]
-pub fn make_castlist_commitment_tape_public() -> PublicSubTable {
-    PublicSubTable {
+pub fn make_castlist_commitment_tape_public() -> PublicCommitmentBytes {
+    PublicSubTableWithTypedOutput {
         table: TapeCommitmentsTable::new(
-            vec![TAPE_COMMITMENTS.commitment_byte_row.byte],
+            CommitmentByte {
+                byte: TAPE_COMMITMENTS.commitment_byte_row.byte,
+            },
             TAPE_COMMITMENTS.is_castlist_commitment_tape_row,
         ),
-        num_rows: DIGEST_BYTES,
+        num_rows: Some(DIGEST_BYTES),
     }
 }
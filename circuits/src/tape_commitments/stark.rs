@@ -19,16 +19,16 @@ fn generate_constraints<'a, T: Copy>(
     vars: &StarkFrameTyped<TapeCommitments<Expr<'a, T>>, NoColumns<Expr<'a, T>>>,
 ) -> ConstraintBuilder<Expr<'a, T>> {
     let lv: &TapeCommitments<Expr<'a, T>> = &vars.local_values;
+    let is_event = lv.is_event_commitment_tape_row;
+    let is_castlist = lv.is_castlist_commitment_tape_row;
     let mut constraint = ConstraintBuilder::default();
-    constraint.always(lv.is_event_commitment_tape_row.is_binary());
-    constraint.always(lv.is_castlist_commitment_tape_row.is_binary());
-    constraint
-        .always((lv.is_castlist_commitment_tape_row + lv.is_event_commitment_tape_row).is_binary());
-    constraint
-        .always(lv.event_commitment_tape_multiplicity * (1 - lv.is_event_commitment_tape_row));
-    constraint.always(
-        lv.castlist_commitment_tape_multiplicity * (1 - lv.is_castlist_commitment_tape_row),
-    );
+    constraint.always(is_event.is_binary());
+    constraint.always(is_castlist.is_binary());
+    // A row holds at most one of the commitments.
+    constraint.always(!is_event.is_one() | !is_castlist.is_one());
+    // Only rows of a commitment take part in its lookup.
+    constraint.always(lv.event_commitment_tape_multiplicity.is_zero() | is_event.is_one());
+    constraint.always(lv.castlist_commitment_tape_multiplicity.is_zero() | is_castlist.is_one());
     constraint
 }
 
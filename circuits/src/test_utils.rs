@@ -1,5 +1,8 @@
+use std::borrow::Borrow;
+use std::fmt::{Debug, Display};
+
 use anyhow::Result;
-use itertools::izip;
+use itertools::{izip, Itertools};
 use mozak_runner::code;
 use mozak_runner::decode::ECALL;
 use mozak_runner::elf::Program;
@@ -8,14 +11,14 @@ use mozak_runner::vm::ExecutionRecord;
 use mozak_sdk::core::ecall;
 use mozak_sdk::core::reg_abi::{REG_A0, REG_A1, REG_A2, REG_A3};
 use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
-use plonky2::fri::FriConfig;
 use plonky2::hash::hash_types::{HashOut, RichField};
 use plonky2::hash::poseidon2::Poseidon2Hash;
 use plonky2::plonk::circuit_data::CircuitConfig;
 use plonky2::plonk::config::{GenericConfig, Hasher, Poseidon2GoldilocksConfig};
-use plonky2::util::log2_ceil;
 use plonky2::util::timing::TimingTree;
+use plonky2::util::transpose;
 use starky::config::StarkConfig;
 use starky::prover::prove as prove_table;
 use starky::stark::Stark;
@@ -23,8 +26,11 @@ use starky::verifier::verify_stark_proof;
 
 use crate::bitshift::generation::generate_shift_amount_trace;
 use crate::bitshift::stark::BitshiftStark;
+use crate::columns_view::{ColumnNames, HasNamedColumns};
 use crate::cpu::generation::generate_cpu_trace;
 use crate::cpu::stark::CpuStark;
+use crate::cross_table_lookup::ctl_utils::check_single_ctl;
+use crate::generation::{check_single_trace, generate_traces};
 use crate::memory::generation::generate_memory_trace;
 use crate::memory::stark::MemoryStark;
 use crate::memory_fullword::generation::generate_fullword_memory_trace;
@@ -34,6 +40,11 @@ use crate::memory_halfword::stark::HalfWordMemoryStark;
 use crate::memory_zeroinit::generation::generate_memory_zero_init_trace;
 use crate::memoryinit::generation::generate_memory_init_trace;
 use crate::ops;
+use crate::ops::bitwise::stark::BitwiseStark;
+use crate::ops::branches::stark::BranchesStark;
+use crate::ops::jalr::stark::JalrStark;
+use crate::ops::load_store::stark::LoadStoreStark;
+use crate::ops::mul_div::stark::MulDivStark;
 use crate::poseidon2_output_bytes::generation::generate_poseidon2_output_bytes_trace;
 use crate::poseidon2_sponge::generation::generate_poseidon2_sponge_trace;
 use crate::rangecheck::generation::generate_rangecheck_trace;
@@ -43,8 +54,12 @@ use crate::register::generation::{generate_register_init_trace, generate_registe
 use crate::register::init::stark::RegisterInitStark;
 use crate::stark::batch_prover::batch_prove;
 use crate::stark::batch_verifier::batch_verify_proof;
-use crate::stark::mozak_stark::{MozakStark, PublicInputs, PUBLIC_TABLE_KINDS};
+use crate::stark::mozak_stark::{
+    all_starks, MozakStark, PublicInputs, TableKind, TableKindArray, TableKindSetBuilder,
+    PUBLIC_TABLE_KINDS,
+};
 use crate::stark::prover::prove;
+use crate::stark::security::SecurityProfile;
 use crate::stark::utils::trace_rows_to_poly_values;
 use crate::stark::verifier::verify_proof;
 use crate::storage_device::generation::{
@@ -66,24 +81,7 @@ pub type F = <C as GenericConfig<D>>::F;
 
 /// Test Configuration with 1 bit of security
 #[must_use]
-pub fn fast_test_config() -> StarkConfig {
-    let config = StarkConfig::standard_fast_config();
-    StarkConfig {
-        security_bits: 1,
-        num_challenges: 2,
-        fri_config: FriConfig {
-            // Plonky2 says: "Having constraints of degree higher than the rate is not supported
-            // yet." So we automatically set the rate here as required by plonky2.
-            // TODO(Matthias): Change to maximum of constraint degrees of all starks, as we
-            // accumulate more types of starks.
-            rate_bits: log2_ceil(S::default().cpu_stark.constraint_degree()),
-            cap_height: 0,
-            proof_of_work_bits: 0,
-            num_query_rounds: 5,
-            ..config.fri_config
-        },
-    }
-}
+pub fn fast_test_config() -> StarkConfig { SecurityProfile::Test.stark_config() }
 
 #[must_use]
 pub const fn fast_test_circuit_config() -> CircuitConfig {
@@ -151,6 +149,11 @@ impl ProveAndVerify for RangeCheckStark<F, D> {
         let cpu_trace = generate_cpu_trace(record);
         let add_trace = ops::add::generate(record);
         let blt_trace = ops::blt_taken::generate(record);
+        let mul_div_trace = ops::mul_div::generate(record);
+        let bitwise_trace = ops::bitwise::generate(record);
+        let branches_trace = ops::branches::generate(record);
+        let jalr_trace = ops::jalr::generate(record);
+        let load_store_trace = ops::load_store::generate(record);
 
         let memory_init = generate_memory_init_trace(program);
         let memory_zeroinit_rows = generate_memory_zero_init_trace(&record.executed, program);
@@ -188,6 +191,11 @@ impl ProveAndVerify for RangeCheckStark<F, D> {
             &cpu_trace,
             &add_trace,
             &blt_trace,
+            &mul_div_trace,
+            &bitwise_trace,
+            &branches_trace,
+            &jalr_trace,
+            &load_store_trace,
             &poseidon2_sponge_trace,
             &private_tape,
             &public_tape,
@@ -202,7 +210,13 @@ impl ProveAndVerify for RangeCheckStark<F, D> {
             &cpu_trace,
             &add_trace,
             &blt_trace,
+            &mul_div_trace,
+            &branches_trace,
+            &jalr_trace,
+            &load_store_trace,
             &memory_trace,
+            &halfword_memory,
+            &fullword_memory,
             &register_trace,
         ));
         let proof = prove_table::<F, C, S, D>(
@@ -224,8 +238,10 @@ impl ProveAndVerify for XorStark<F, D> {
         let config = fast_test_config();
 
         let stark = S::default();
-        let cpu_trace = generate_cpu_trace(record);
-        let trace_poly_values = trace_rows_to_poly_values(generate_xor_trace(&cpu_trace));
+        let trace_poly_values = trace_rows_to_poly_values(generate_xor_trace(
+            &ops::bitwise::generate(record),
+            &ops::load_store::generate(record),
+        ));
         let proof = prove_table::<F, C, S, D>(
             stark,
             &config,
@@ -354,8 +370,8 @@ impl ProveAndVerify for BitshiftStark<F, D> {
         let config = fast_test_config();
 
         let stark = S::default();
-        let cpu_rows = generate_cpu_trace::<F>(record);
-        let trace = generate_shift_amount_trace(&cpu_rows);
+        let mul_div_rows = ops::mul_div::generate(record);
+        let trace = generate_shift_amount_trace(&mul_div_rows);
         let trace_poly_values = trace_rows_to_poly_values(trace);
         let proof = prove_table::<F, C, S, D>(
             stark,
@@ -369,6 +385,101 @@ impl ProveAndVerify for BitshiftStark<F, D> {
     }
 }
 
+impl ProveAndVerify for MulDivStark<F, D> {
+    fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
+        type S = MulDivStark<F, D>;
+        let config = fast_test_config();
+
+        let stark = S::default();
+        let trace_poly_values = trace_rows_to_poly_values(ops::mul_div::generate(record));
+        let proof = prove_table::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+}
+
+impl ProveAndVerify for BitwiseStark<F, D> {
+    fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
+        type S = BitwiseStark<F, D>;
+        let config = fast_test_config();
+
+        let stark = S::default();
+        let trace_poly_values = trace_rows_to_poly_values(ops::bitwise::generate(record));
+        let proof = prove_table::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+}
+
+impl ProveAndVerify for BranchesStark<F, D> {
+    fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
+        type S = BranchesStark<F, D>;
+        let config = fast_test_config();
+
+        let stark = S::default();
+        let trace_poly_values = trace_rows_to_poly_values(ops::branches::generate(record));
+        let proof = prove_table::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+}
+
+impl ProveAndVerify for JalrStark<F, D> {
+    fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
+        type S = JalrStark<F, D>;
+        let config = fast_test_config();
+
+        let stark = S::default();
+        let trace_poly_values = trace_rows_to_poly_values(ops::jalr::generate(record));
+        let proof = prove_table::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+}
+
+impl ProveAndVerify for LoadStoreStark<F, D> {
+    fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
+        type S = LoadStoreStark<F, D>;
+        let config = fast_test_config();
+
+        let stark = S::default();
+        let trace_poly_values = trace_rows_to_poly_values(ops::load_store::generate(record));
+        let proof = prove_table::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+}
+
 impl ProveAndVerify for RegisterInitStark<F, D> {
     fn prove_and_verify(_program: &Program, record: &ExecutionRecord<F>) -> Result<()> {
         type S = RegisterInitStark<F, D>;
@@ -398,6 +509,11 @@ impl ProveAndVerify for RegisterStark<F, D> {
         let cpu_trace = generate_cpu_trace(record);
         let add_trace = ops::add::generate(record);
         let blt_trace = ops::blt_taken::generate(record);
+        let mul_div_trace = ops::mul_div::generate(record);
+        let bitwise_trace = ops::bitwise::generate(record);
+        let branches_trace = ops::branches::generate(record);
+        let jalr_trace = ops::jalr::generate(record);
+        let load_store_trace = ops::load_store::generate(record);
         let private_tape = generate_private_tape_trace(&record.executed);
         let public_tape = generate_public_tape_trace(&record.executed);
         let call_tape = generate_call_tape_trace(&record.executed);
@@ -413,6 +529,11 @@ impl ProveAndVerify for RegisterStark<F, D> {
             &cpu_trace,
             &add_trace,
             &blt_trace,
+            &mul_div_trace,
+            &bitwise_trace,
+            &branches_trace,
+            &jalr_trace,
+            &load_store_trace,
             &poseidon2_sponge_rows,
             &private_tape,
             &public_tape,
@@ -476,7 +597,16 @@ pub fn prove_and_verify_mozak_stark(
     record: &ExecutionRecord<F>,
     config: &StarkConfig,
 ) -> Result<()> {
-    let stark = MozakStark::default();
+    prove_and_verify_with_mozak_stark(&MozakStark::default(), program, record, config)
+}
+
+/// Like [`prove_and_verify_mozak_stark`], but with a customised `stark`.
+pub fn prove_and_verify_with_mozak_stark(
+    stark: &MozakStark<F, D>,
+    program: &Program,
+    record: &ExecutionRecord<F>,
+    config: &StarkConfig,
+) -> Result<()> {
     let public_inputs = PublicInputs {
         entry_point: from_u32(program.entry_point),
     };
@@ -484,12 +614,12 @@ pub fn prove_and_verify_mozak_stark(
     let all_proof = prove::<F, C, D>(
         program,
         record,
-        &stark,
+        stark,
         config,
         public_inputs,
         &mut TimingTree::default(),
     )?;
-    verify_proof(&stark, all_proof, config)
+    verify_proof(stark, all_proof, config)
 }
 
 pub fn prove_and_verify_batch_mozak_stark(
@@ -598,3 +728,168 @@ pub fn hash_branch<F: RichField>(left: &HashOut<F>, right: &HashOut<F>) -> HashO
     let [r0, r1, r2, r3] = right.elements;
     Poseidon2Hash::hash_no_pad(&[l0, l1, l2, l3, r0, r1, r2, r3])
 }
+
+/// A change to one table of a valid trace, as made by [`undetected_mutations`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    /// Adds one to the cell of `column` in `row`.
+    Perturb { row: usize, column: usize },
+    /// Swaps `row` with the row after it.
+    SwapRows { row: usize },
+}
+
+/// A mutation of a table that neither the constraints of its Stark nor the
+/// cross table lookups reject.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndetectedMutation {
+    pub table: TableKind,
+    pub mutation: Mutation,
+    /// The name of the perturbed column.
+    pub column: Option<String>,
+}
+
+impl Display for UndetectedMutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.mutation, &self.column) {
+            (Mutation::Perturb { row, .. }, Some(column)) =>
+                write!(f, "{:?}[{row}].{column} can be perturbed", self.table),
+            (Mutation::SwapRows { row }, _) => write!(
+                f,
+                "{:?} rows {row} and {} can be swapped",
+                self.table,
+                row + 1
+            ),
+            (mutation, None) => write!(f, "{:?}: {mutation:?} is not detected", self.table),
+        }
+    }
+}
+
+/// Whether the constraints of `stark` or the cross table lookups of the table
+/// `kind` reject `traces`.
+fn rejects<S>(
+    stark: &S,
+    kind: TableKind,
+    traces: &TableKindArray<Vec<PolynomialValues<F>>>,
+    mozak_stark: &MozakStark<F, D>,
+    public_inputs: &[F],
+) -> bool
+where
+    S: Stark<F, D> + Display + HasNamedColumns,
+    S::Columns: ColumnNames, {
+    !check_single_trace::<F, D, S>(stark, &traces[kind], public_inputs).is_empty()
+        || mozak_stark
+            .cross_table_lookups
+            .iter()
+            .filter(|ctl| ctl.looking_tables.iter().any(|table| table.kind == kind))
+            .any(|ctl| check_single_ctl(traces, ctl).is_err())
+}
+
+fn swap_rows(trace: &mut [PolynomialValues<F>], row: usize) {
+    for column in trace {
+        column.values.swap(row, row + 1);
+    }
+}
+
+/// Applies every mutation to the table `kind` of `traces` in turn, and
+/// returns those that go undetected.
+fn table_mutations<S>(
+    stark: &S,
+    kind: TableKind,
+    traces: &mut TableKindArray<Vec<PolynomialValues<F>>>,
+    mozak_stark: &MozakStark<F, D>,
+    public_inputs: &[F],
+) -> Vec<UndetectedMutation>
+where
+    S: Stark<F, D> + Display + HasNamedColumns,
+    S::Columns: ColumnNames, {
+    let is_rejected =
+        |traces: &TableKindArray<_>| rejects(stark, kind, traces, mozak_stark, public_inputs);
+    assert!(!is_rejected(traces), "{kind:?} rejects the unmutated trace");
+
+    let names = stark.column_names();
+    let rows = transpose(
+        &traces[kind]
+            .iter()
+            .map(|column| column.values.clone())
+            .collect_vec(),
+    );
+    // Padding rows are those that none of the lookups of the table use, and
+    // typically leave most of their cells unconstrained on purpose.
+    let filters = mozak_stark
+        .cross_table_lookups
+        .iter()
+        .flat_map(|ctl| &ctl.looking_tables)
+        .filter(|table| table.kind == kind)
+        .map(|table| table.filter_column.to_field::<F>())
+        .collect_vec();
+    let real = (0..rows.len())
+        .map(|row| {
+            filters
+                .iter()
+                .any(|filter| filter.eval_table(&traces[kind], row).is_nonzero())
+        })
+        .collect_vec();
+    let is_real = |row: usize| real.get(row).copied().unwrap_or_default();
+
+    let mut undetected = vec![];
+    for row in (0..rows.len()).filter(|&row| is_real(row)) {
+        for (column, name) in names.iter().enumerate() {
+            traces[kind][column].values[row] += F::ONE;
+            if !is_rejected(traces) {
+                undetected.push(UndetectedMutation {
+                    table: kind,
+                    mutation: Mutation::Perturb { row, column },
+                    column: Some(name.clone()),
+                });
+            }
+            traces[kind][column].values[row] -= F::ONE;
+        }
+        if is_real(row + 1) && rows[row] != rows[row + 1] {
+            swap_rows(&mut traces[kind], row);
+            if !is_rejected(traces) {
+                undetected.push(UndetectedMutation {
+                    table: kind,
+                    mutation: Mutation::SwapRows { row },
+                    column: None,
+                });
+            }
+            swap_rows(&mut traces[kind], row);
+        }
+    }
+    undetected
+}
+
+/// Mutates the traces of `program`, in the tables selected by `tables`, and
+/// returns the mutations that neither the constraints nor the cross table
+/// lookups reject.
+///
+/// Every cell of every row is perturbed on its own, and every row is swapped
+/// with the next one.  Padding rows, taken to be those where the filters of
+/// all the lookups of their table are zero, are left alone.  Public sub tables
+/// are not checked.
+#[must_use]
+pub fn undetected_mutations(
+    program: &Program,
+    record: &ExecutionRecord<F>,
+    tables: impl Fn(TableKind) -> bool,
+) -> Vec<UndetectedMutation> {
+    let mozak_stark = MozakStark::<F, D>::default();
+    let mut traces = generate_traces::<F, D>(program, record, &mut TimingTree::default());
+    let public_inputs = PublicInputs {
+        entry_point: from_u32(program.entry_point),
+    };
+    let public_inputs = TableKindSetBuilder::<&[_]> {
+        cpu_skeleton_stark: public_inputs.borrow(),
+        ..Default::default()
+    }
+    .build();
+
+    let undetected = all_starks!(&mozak_stark, |stark, kind| {
+        if tables(kind) {
+            table_mutations(stark, kind, &mut traces, &mozak_stark, public_inputs[kind])
+        } else {
+            vec![]
+        }
+    });
+    undetected.0.into_iter().flatten().collect()
+}
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use mozak_circuits_derive::StarkNameDisplay;
+use mozak_circuits_derive::{ColumnNames, StarkNameDisplay};
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
 use plonky2::hash::hash_types::RichField;
@@ -66,7 +66,7 @@ impl<
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ColumnNames)]
 pub struct NoColumns<T> {
     _phantom: PhantomData<T>,
 }
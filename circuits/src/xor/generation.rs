@@ -1,18 +1,26 @@
 use bitfield::Bit;
-use itertools::Itertools;
+use itertools::{chain, Itertools};
 use plonky2::hash::hash_types::RichField;
 
-use crate::cpu::columns::CpuState;
+use crate::ops::bitwise::columns::Bitwise;
+use crate::ops::load_store::columns::LoadStore;
 use crate::utils::pad_trace_with_default;
 use crate::xor::columns::{XorColumnsView, XorView};
 
-fn filter_xor_trace<F: RichField>(
-    step_rows: &[CpuState<F>],
-) -> impl Iterator<Item = XorView<F>> + '_ {
-    step_rows
-        .iter()
-        .filter(|row| row.inst.ops.ops_that_use_xor().is_one())
-        .map(|row| row.xor)
+fn filter_xor_trace<'a, F: RichField>(
+    bitwise_rows: &'a [Bitwise<F>],
+    load_store_rows: &'a [LoadStore<F>],
+) -> impl Iterator<Item = XorView<F>> + 'a {
+    chain!(
+        bitwise_rows
+            .iter()
+            .filter(|row| row.is_running().is_one())
+            .map(|row| row.xor),
+        load_store_rows
+            .iter()
+            .filter(|row| (row.inst.ops.sb + row.inst.ops.sh).is_one())
+            .map(|row| row.xor),
+    )
 }
 
 fn to_bits<F: RichField>(val: F) -> [F; u32::BITS as usize] {
@@ -24,9 +32,12 @@ fn to_bits<F: RichField>(val: F) -> [F; u32::BITS as usize] {
 }
 
 #[must_use]
-pub fn generate_xor_trace<F: RichField>(cpu_trace: &[CpuState<F>]) -> Vec<XorColumnsView<F>> {
+pub fn generate_xor_trace<F: RichField>(
+    bitwise_trace: &[Bitwise<F>],
+    load_store_trace: &[LoadStore<F>],
+) -> Vec<XorColumnsView<F>> {
     pad_trace_with_default({
-        filter_xor_trace(cpu_trace)
+        filter_xor_trace(bitwise_trace, load_store_trace)
             .map(|execution| XorColumnsView {
                 is_execution_row: F::ONE,
                 execution,
@@ -96,15 +96,19 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for XorStark<F, D
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
+    use expr::ExprBuilder;
     use mozak_runner::code;
     use mozak_runner::instruction::{Args, Instruction, Op};
     use plonky2::timed;
     use plonky2::util::timing::TimingTree;
+    use starky::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
     use starky::prover::prove as prove_table;
     use starky::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
     use starky::verifier::verify_stark_proof;
 
-    use crate::cpu::generation::generate_cpu_trace;
+    use super::{generate_constraints, COLUMNS, PUBLIC_INPUTS};
+    use crate::expr::num_operations;
+    use crate::ops;
     use crate::stark::utils::trace_rows_to_poly_values;
     use crate::test_utils::{fast_test_config, C, D, F};
     use crate::xor::generation::generate_xor_trace;
@@ -155,8 +159,13 @@ mod tests {
         );
         // assert_eq!(record.last_state.get_register_value(7), a ^ (b + imm));
         let mut timing = TimingTree::new("xor", log::Level::Debug);
-        let cpu_trace = generate_cpu_trace(&record);
-        let trace = timed!(timing, "generate_xor_trace", generate_xor_trace(&cpu_trace));
+        let bitwise_trace = ops::bitwise::generate(&record);
+        let load_store_trace = ops::load_store::generate(&record);
+        let trace = timed!(
+            timing,
+            "generate_xor_trace",
+            generate_xor_trace(&bitwise_trace, &load_store_trace)
+        );
         let trace_poly_values = timed!(timing, "trace to poly", trace_rows_to_poly_values(trace));
         let stark = S::default();
 
@@ -188,6 +197,28 @@ mod tests {
             }
     }
 
+    fn constraint_operations() -> u64 {
+        let eb = ExprBuilder::default();
+        let values = [0; COLUMNS];
+        let vars =
+            StarkFrame::<i64, i64, COLUMNS, PUBLIC_INPUTS>::from_values(&values, &values, &[]);
+        num_operations(generate_constraints(&eb.to_typed_starkframe(&vars)))
+    }
+
+    #[test]
+    fn simplification_saves_constraint_operations() {
+        // Two operations per bit to check that it is binary, 3 * 63 to
+        // recompose the values from their limbs, five per xor constraint as
+        // `a + b` is shared, and the constants 1 and 2.
+        assert_eq!(constraint_operations(), 96 * 2 + 3 * 63 + 32 * 5 + 2);
+        // Without simplification, `a + b` is built twice, recomposition starts
+        // from `0 + limb`, and 0 is a constant.
+        assert_eq!(
+            expr::without_simplification(constraint_operations),
+            96 * 2 + 3 * 64 + 32 * 6 + 3
+        );
+    }
+
     #[test]
     fn test_circuit() -> anyhow::Result<()> {
         let stark = S::default();
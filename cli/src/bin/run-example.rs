@@ -4,7 +4,6 @@ use mozak_circuits::test_utils::{C, D, F};
 use mozak_cli::runner::{get_self_prog_id, load_program, raw_tapes_from_system_tape};
 use mozak_runner::state::State;
 use mozak_runner::vm::step;
-use starky::config::StarkConfig;
 
 #[derive(Parser, Debug, Clone)]
 struct Cli {
@@ -20,9 +19,8 @@ fn main() {
     env_logger::Builder::new()
         .filter_level(args.verbose.log_level_filter())
         .init();
-    let config = StarkConfig::standard_fast_config();
     let program = load_program(args.elf).unwrap();
-    let self_prog_id = get_self_prog_id::<F, C, D>(&program, &config);
+    let self_prog_id = get_self_prog_id::<F, C, D>(&program);
 
     let raw_tapes = raw_tapes_from_system_tape(args.system_tape, self_prog_id);
 
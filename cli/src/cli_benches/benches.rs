@@ -3,6 +3,7 @@ use std::time::Duration;
 use anyhow::Result;
 use clap::{Args as Args_, Subcommand};
 
+use super::mul_div::MulDivBench;
 use super::nop::NopBench;
 use super::omni::OmniBench;
 use super::poseidon2::Poseidon2Bench;
@@ -51,6 +52,10 @@ pub enum BenchFunction {
     Poseidon2Bench {
         input_len: u32,
     },
+    /// Benchmarks multiplication, division and shifts.
+    MulDivBench {
+        iterations: u32,
+    },
     /// Benchmarks (almost) every instruction.
     OmniBench {
         iterations: u32,
@@ -77,6 +82,7 @@ impl BenchArgs {
         match &self.function {
             BenchFunction::XorBench { iterations } => XorBench.bench(iterations),
             BenchFunction::NopBench { iterations } => NopBench.bench(iterations),
+            BenchFunction::MulDivBench { iterations } => MulDivBench.bench(iterations),
             BenchFunction::OmniBench { iterations } => OmniBench.bench(iterations),
             BenchFunction::Poseidon2Bench { input_len } => Poseidon2Bench.bench(input_len),
             BenchFunction::SortBench { n } => SortBench.bench(n),
@@ -1,4 +1,5 @@
 pub mod benches;
+pub mod mul_div;
 pub mod nop;
 pub mod omni;
 pub mod poseidon2;
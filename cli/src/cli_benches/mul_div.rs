@@ -0,0 +1,88 @@
+use mozak_circuits::test_utils::{prove_and_verify_mozak_stark, F};
+use mozak_runner::code;
+use mozak_runner::elf::Program;
+use mozak_runner::instruction::{Args, Instruction, Op};
+use mozak_runner::vm::ExecutionRecord;
+use starky::config::StarkConfig;
+
+use super::benches::Bench;
+
+#[allow(clippy::module_name_repetitions)]
+pub fn mul_div_execute(
+    (program, record): (Program, ExecutionRecord<F>),
+) -> Result<(), anyhow::Error> {
+    prove_and_verify_mozak_stark(&program, &record, &StarkConfig::standard_fast_config())
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub fn mul_div_prepare(iterations: u32) -> (Program, ExecutionRecord<F>) {
+    let instructions = [
+        Instruction {
+            op: Op::ADD,
+            args: Args {
+                rd: 1,
+                rs1: 1,
+                imm: 1_u32.wrapping_neg(),
+                ..Args::default()
+            },
+        },
+        Instruction {
+            op: Op::MULHSU,
+            args: Args {
+                rd: 2,
+                rs1: 1,
+                imm: 0xDEAD_BEEF,
+                ..Args::default()
+            },
+        },
+        Instruction {
+            op: Op::DIV,
+            args: Args {
+                rd: 3,
+                rs1: 2,
+                rs2: 1,
+                ..Args::default()
+            },
+        },
+        Instruction {
+            op: Op::SRA,
+            args: Args {
+                rd: 4,
+                rs1: 2,
+                rs2: 1,
+                ..Args::default()
+            },
+        },
+        Instruction {
+            op: Op::BLT,
+            args: Args {
+                rs1: 0,
+                rs2: 1,
+                imm: 0,
+                ..Args::default()
+            },
+        },
+    ];
+    code::execute(instructions, &[], &[(1, iterations)])
+}
+
+pub(crate) struct MulDivBench;
+
+impl Bench for MulDivBench {
+    type Args = u32;
+    type Prepared = (Program, ExecutionRecord<F>);
+
+    fn prepare(&self, args: &Self::Args) -> Self::Prepared { mul_div_prepare(*args) }
+
+    fn execute(&self, prepared: Self::Prepared) -> anyhow::Result<()> { mul_div_execute(prepared) }
+}
+#[cfg(test)]
+mod tests {
+    use super::{mul_div_execute, mul_div_prepare};
+
+    #[test]
+    fn test_mul_div_bench() -> anyhow::Result<()> {
+        let iterations = 10;
+        mul_div_execute(mul_div_prepare(iterations))
+    }
+}
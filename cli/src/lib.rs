@@ -3,4 +3,5 @@ pub mod cli_benches;
 pub mod runner;
 #[cfg(test)]
 mod tests;
-mod trace_utils;
+pub mod trace_utils;
+pub mod verifier;
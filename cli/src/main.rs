@@ -11,38 +11,42 @@ use clap_derive::Args;
 use clio::{Input, Output};
 use itertools::Itertools;
 use log::debug;
+use mozak_circuits::generation::{estimate_cost, export_traces_csv, generate_traces};
 use mozak_circuits::memoryinit::generation::generate_elf_memory_init_trace;
 use mozak_circuits::program::generation::generate_program_rom_trace;
 use mozak_circuits::stark::batch_prover::batch_prove;
+use mozak_circuits::stark::circuit_cache::{with_circuit_cache, CircuitCache};
 use mozak_circuits::stark::mozak_stark::{
     MozakStark, PublicInputs, TableKindArray, PUBLIC_TABLE_KINDS,
 };
-use mozak_circuits::stark::proof::{AllProof, BatchProof};
+use mozak_circuits::stark::proof::BatchProof;
 use mozak_circuits::stark::prover::prove;
 use mozak_circuits::stark::recursive_verifier::{
-    circuit_data_for_recursion, recursive_batch_stark_circuit, recursive_mozak_stark_circuit,
+    recursive_batch_stark_circuit, recursive_mozak_stark_circuit,
     shrink_to_target_degree_bits_circuit, VMRecursiveProofPublicInputs, VM_PUBLIC_INPUT_SIZE,
-    VM_RECURSION_CONFIG, VM_RECURSION_THRESHOLD_DEGREE_BITS,
+    VM_RECURSION_THRESHOLD_DEGREE_BITS,
 };
+use mozak_circuits::stark::security::SecurityProfile;
 use mozak_circuits::stark::utils::trace_rows_to_poly_values;
-use mozak_circuits::stark::verifier::verify_proof;
+use mozak_circuits::stark::wiring::Wiring;
 use mozak_circuits::storage_device::generation::generate_call_tape_trace;
 use mozak_circuits::test_utils::{prove_and_verify_mozak_stark, C, D, F, S};
 #[cfg(feature = "bench")]
 use mozak_cli::cli_benches::benches::BenchArgs;
 use mozak_cli::runner::{
-    deserialize_system_tape, get_self_prog_id, load_program, raw_tapes_from_system_tape,
+    deserialize_system_tape, get_self_prog_id, load_program, load_program_with_elf_info,
+    raw_tapes_from_system_tape,
 };
+use mozak_cli::trace_utils::get_trace_merkle_cap;
+use mozak_cli::verifier::{verify_any_proof, verify_recursive_proof};
 use mozak_node::types::{Attestation, Transaction};
 use mozak_runner::state::State;
+use mozak_runner::transaction::{execute_transaction, TransactionRecord};
 use mozak_runner::vm::step;
 use mozak_sdk::common::types::{CrossProgramCall, ProgramIdentifier, SystemTape};
 use plonky2::field::types::Field;
 use plonky2::fri::oracle::PolynomialBatch;
-use plonky2::plonk::circuit_data::VerifierOnlyCircuitData;
-use plonky2::plonk::proof::ProofWithPublicInputs;
 use plonky2::util::timing::TimingTree;
-use starky::config::StarkConfig;
 
 const PROGRAMS_MAP_JSON: &str = "examples/programs_map.json";
 
@@ -53,9 +57,15 @@ struct Cli {
     verbose: clap_verbosity_flag::Verbosity,
     #[command(subcommand)]
     command: Command,
-    /// Debug API, default is OFF, currently only `prove` command is supported
+    /// Debug API, default is OFF, currently only `prove` command is supported.
+    /// Failing constraints are only named if the CLI is built with the
+    /// `debug-constraints` feature.
     #[arg(short, long)]
     debug: bool,
+    /// Directory to cache built recursive circuits in.  Without it, recursive
+    /// circuits are built from scratch.  Verification never uses the cache.
+    #[arg(long, global = true, env = "MOZAK_CIRCUIT_CACHE")]
+    circuit_cache: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Args)]
@@ -74,6 +84,50 @@ pub struct ProveArgs {
     #[arg(long)]
     system_tape: Option<Input>,
     recursive_proof: Option<Output>,
+    /// Prove one table at a time and recompute trace extensions on demand,
+    /// to lower peak memory at the cost of proving time.
+    #[arg(long)]
+    low_memory: bool,
+    /// Security profile to prove with: test, fast, 100-bit,
+    /// 128-bit-conjectured or 128-bit-conservative.
+    #[arg(long, default_value_t = SecurityProfile::Fast)]
+    security: SecurityProfile,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct VerifyArgs {
+    proof: Input,
+    /// Verifier key, needed to verify a recursive proof.
+    #[arg(long)]
+    verifier_key: Option<Input>,
+    /// Check that the proof is for this ELF.
+    #[arg(long, conflicts_with = "program_id")]
+    elf: Option<Input>,
+    /// Check that the proof is for this program ID, like `MZK-...`.
+    #[arg(long)]
+    program_id: Option<String>,
+    /// Security profile that the proof has to be made with.
+    #[arg(long, default_value_t = SecurityProfile::Fast)]
+    security: SecurityProfile,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct TraceArgs {
+    elf: Input,
+    #[arg(long)]
+    system_tape: Option<Input>,
+    /// Directory to write one `<table>.csv` per table into.
+    #[arg(long)]
+    out: PathBuf,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct WiringArgs {
+    /// Render a Graphviz diagram instead of JSON.
+    #[arg(long)]
+    dot: bool,
+    #[arg(default_value = "-")]
+    out: Output,
 }
 
 #[derive(Clone, Debug, Subcommand)]
@@ -85,15 +139,24 @@ enum Command {
     Run(RunArgs),
     /// Prove and verify the execution of a given ELF
     ProveAndVerify(RunArgs),
+    /// Execute a given ELF and export the trace of every table as CSV.
+    Trace(TraceArgs),
+    /// Execute a given ELF and estimate the cost of proving it, without
+    /// proving.
+    Estimate(RunArgs),
     /// Prove the execution of given ELF and write proof to file.
     Prove(ProveArgs),
-    /// Verify the given proof from file.
-    Verify { proof: Input },
+    /// Verify the given proof from file. Detects whether it is a plain, batch
+    /// or recursive proof.
+    Verify(VerifyArgs),
     /// Verify the given recursive proof from file.
     VerifyRecursiveProof {
         proof: Input,
         verifier_key: Input,
         program_id: String,
+        /// Security profile that the proof has to be made with.
+        #[arg(long, default_value_t = SecurityProfile::Fast)]
+        security: SecurityProfile,
     },
     /// Builds a transaction bundle.
     BundleTransaction {
@@ -110,6 +173,9 @@ enum Command {
     MemoryInitHash { elf: Input },
     /// Compute the Self Program Id of the given ELF,
     SelfProgId { elf: Input },
+    /// Describe the tables and cross table lookups, as JSON or as a Graphviz
+    /// diagram.
+    Wiring(WiringArgs),
     #[cfg(feature = "bench")]
     /// Bench the function with given parameters
     Bench(BenchArgs),
@@ -117,28 +183,45 @@ enum Command {
 
 /// Run me eg like `cargo run -- -vvv run vm/tests/testdata/rv32ui-p-addi
 /// iotape.txt`
-#[allow(clippy::too_many_lines)]
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let config = StarkConfig::standard_fast_config();
     env_logger::Builder::new()
         .filter_level(cli.verbose.log_level_filter())
         .init();
+    // A verifier must not trust circuits that it did not build itself.
+    let circuit_cache = match cli.command {
+        Command::Verify(_) | Command::VerifyRecursiveProof { .. } => None,
+        _ => cli.circuit_cache.clone().map(CircuitCache::new),
+    };
+    with_circuit_cache(circuit_cache.as_ref(), || run(cli))
+}
+
+#[allow(clippy::too_many_lines)]
+fn run(cli: Cli) -> Result<()> {
+    let config = SecurityProfile::default().stark_config();
     match cli.command {
         Command::Decode { elf } => {
-            let program = load_program(elf)?;
+            let program = load_program_with_elf_info(elf)?;
             debug!("{program:?}");
+            if let Some(elf_info) = &program.elf_info {
+                if let Some(metadata) = &elf_info.metadata {
+                    println!("{metadata:#?}");
+                }
+                for symbol in elf_info.symbols.iter().filter(|symbol| symbol.is_function) {
+                    println!("{:#010x} {:>8} {}", symbol.addr, symbol.size, symbol.name);
+                }
+            }
         }
         Command::Run(RunArgs { elf, system_tape }) => {
             let program = load_program(elf).unwrap();
-            let self_prog_id = get_self_prog_id::<F, C, D>(&program, &config);
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
             let raw_tapes = raw_tapes_from_system_tape(system_tape, self_prog_id);
             let state: State<F> = State::new(program.clone(), raw_tapes);
             step(&program, state)?;
         }
         Command::ProveAndVerify(RunArgs { elf, system_tape }) => {
             let program = load_program(elf).unwrap();
-            let self_prog_id = get_self_prog_id::<F, C, D>(&program, &config);
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
 
             let raw_tapes = raw_tapes_from_system_tape(system_tape, self_prog_id);
 
@@ -146,22 +229,69 @@ fn main() -> Result<()> {
             let record = step(&program, state)?;
             prove_and_verify_mozak_stark(&program, &record, &config)?;
         }
+        Command::Trace(TraceArgs {
+            elf,
+            system_tape,
+            out,
+        }) => {
+            let program = load_program(elf)?;
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
+            let raw_tapes = raw_tapes_from_system_tape(system_tape, self_prog_id);
+            let state = State::new(program.clone(), raw_tapes);
+            let record = step(&program, state)?;
+            let traces = generate_traces::<F, D>(&program, &record, &mut TimingTree::default());
+            std::fs::create_dir_all(&out)?;
+            export_traces_csv(&traces, &MozakStark::default(), &out)?;
+            println!("Traces written to {}", out.display());
+        }
+        Command::Estimate(RunArgs { elf, system_tape }) => {
+            let program = load_program(elf)?;
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
+            let raw_tapes = raw_tapes_from_system_tape(system_tape, self_prog_id);
+            let state = State::new(program.clone(), raw_tapes);
+            let record = step(&program, state)?;
+            let estimate = estimate_cost(&program, &record, &MozakStark::default(), &config);
+            println!(
+                "{:<24} {:>10} {:>6} {:>8} {:>8} {:>10} {:>14}",
+                "table", "rows", "bits", "columns", "ctl zs", "quotients", "commitments"
+            );
+            for &(table, kind) in estimate.tables.with_kind().iter() {
+                println!(
+                    "{:<24} {:>10} {:>6} {:>8} {:>8} {:>10} {:>10} KiB",
+                    format!("{kind:?}"),
+                    table.num_rows,
+                    table.degree_bits,
+                    table.num_columns,
+                    table.num_ctl_zs,
+                    table.num_quotient_chunks,
+                    table.commitment_bytes >> 10
+                );
+            }
+            println!(
+                "Estimated peak memory: {} MiB",
+                estimate.peak_memory_bytes >> 20
+            );
+        }
         Command::Prove(ProveArgs {
             elf,
             system_tape,
             mut proof,
             recursive_proof,
             batch_proof,
+            low_memory,
+            security,
         }) => {
+            let config = security.stark_config();
+            let recursion_config = security.recursion_config();
             let program = load_program(elf).unwrap();
-            let self_prog_id = get_self_prog_id::<F, C, D>(&program, &config);
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
             let raw_tapes = raw_tapes_from_system_tape(system_tape, self_prog_id);
             let state = State::new(program.clone(), raw_tapes);
             let record = step(&program, state)?;
-            let stark = if cli.debug {
-                MozakStark::default_debug()
-            } else {
-                MozakStark::default()
+            let stark = MozakStark {
+                debug: cli.debug,
+                low_memory,
+                ..MozakStark::default()
             };
             let public_inputs = PublicInputs {
                 entry_point: F::from_canonical_u32(program.entry_point),
@@ -176,8 +306,7 @@ fn main() -> Result<()> {
                 &mut TimingTree::default(),
             )?;
 
-            let serialized = serde_json::to_string(&all_proof).unwrap();
-            proof.write_all(serialized.as_bytes())?;
+            proof.write_all(&all_proof.to_bytes(&config))?;
 
             let mut batch_all_proof: Option<BatchProof<F, C, D>> = None;
             let mut batch_degree_bits: Option<TableKindArray<usize>> = None;
@@ -191,10 +320,9 @@ fn main() -> Result<()> {
                     public_inputs,
                     &mut TimingTree::default(),
                 )?;
+                batch_proof_output.write_all(&proof.to_bytes(&degree_bits, &config))?;
                 batch_all_proof = Some(proof);
                 batch_degree_bits = Some(degree_bits);
-                let serialized = serde_json::to_string(&batch_all_proof).unwrap();
-                batch_proof_output.write_all(serialized.as_bytes())?;
             }
 
             // Generate recursive proof
@@ -206,7 +334,7 @@ fn main() -> Result<()> {
                             &stark,
                             &degree_bits,
                             &PUBLIC_TABLE_KINDS,
-                            &VM_RECURSION_CONFIG,
+                            &recursion_config,
                             &config,
                         );
                         let verifier_only = recursive_circuit.circuit.verifier_only.clone();
@@ -218,7 +346,7 @@ fn main() -> Result<()> {
                         let recursive_circuit = recursive_mozak_stark_circuit::<F, C, D>(
                             &stark,
                             &degree_bits,
-                            &VM_RECURSION_CONFIG,
+                            &recursion_config,
                             &config,
                         );
                         let verifier_only = recursive_circuit.circuit.verifier_only.clone();
@@ -250,7 +378,7 @@ fn main() -> Result<()> {
                 let (final_circuit, final_proof) = shrink_to_target_degree_bits_circuit(
                     &verifier_only,
                     &common,
-                    &VM_RECURSION_CONFIG,
+                    &recursion_config,
                     VM_RECURSION_THRESHOLD_DEGREE_BITS,
                     &recursive_all_proof,
                 )?;
@@ -315,7 +443,7 @@ fn main() -> Result<()> {
 
             println!("Bundling transaction...");
 
-            let system_tape: SystemTape = deserialize_system_tape(system_tape_path.clone())?;
+            let system_tape: SystemTape = deserialize_system_tape(system_tape_path)?;
 
             // Q: will first call always be null program calling the program's entrypoint?
             let entrypoint_program_id = system_tape.call_tape.writer[0].callee;
@@ -333,41 +461,41 @@ fn main() -> Result<()> {
 
             let ids_and_paths = ids_and_paths_from_cast_list(entrypoint_program_id, &cast_list);
 
-            let mut attestations: Vec<Attestation> = vec![];
-            let mut call_tape_hash = None;
-
-            for (i, (program_id, elf)) in ids_and_paths.iter().enumerate() {
-                let program = load_program(
-                    Input::try_from(elf)
-                        .unwrap_or_else(|_| panic!("Elf filepath {elf:?} not found")),
-                )?;
-
-                let raw_tapes =
-                    raw_tapes_from_system_tape(Some(system_tape_path.clone()), *program_id);
-                if i == 0 {
-                    let rate_bits = config.fri_config.rate_bits;
-                    let cap_height = config.fri_config.cap_height;
-
-                    let state: State<F> = State::new(program.clone(), raw_tapes.clone());
-                    let record =
-                        step(&program, state).expect("Could not step through the given program");
-
-                    let trace = generate_call_tape_trace(&record.executed);
-                    let poly_values = trace_rows_to_poly_values(trace);
-
-                    let trace_commitment = PolynomialBatch::<F, C, D>::from_values(
-                        poly_values,
-                        rate_bits,
-                        false, // blinding
-                        cap_height,
-                        &mut TimingTree::default(),
-                        None, // fft_root_table
-                    );
-
-                    call_tape_hash = Some(trace_commitment.merkle_tree.cap);
-                }
+            let programs = ids_and_paths
+                .iter()
+                .map(|(program_id, elf)| {
+                    let program = load_program(
+                        Input::try_from(elf)
+                            .unwrap_or_else(|_| panic!("Elf filepath {elf:?} not found")),
+                    )?;
+                    Ok((*program_id, program))
+                })
+                .collect::<Result<HashMap<_, _>>>()?;
+            let transaction_record: TransactionRecord<F> =
+                execute_transaction(&system_tape, &programs)?;
+
+            let call_tape_hash =
+                transaction_record
+                    .records
+                    .get(&entrypoint_program_id)
+                    .map(|record| {
+                        let trace = generate_call_tape_trace(&record.executed);
+                        let poly_values = trace_rows_to_poly_values(trace);
+
+                        let trace_commitment = PolynomialBatch::<F, C, D>::from_values(
+                            poly_values,
+                            config.fri_config.rate_bits,
+                            false, // blinding
+                            config.fri_config.cap_height,
+                            &mut TimingTree::default(),
+                            None, // fft_root_table
+                        );
+                        trace_commitment.merkle_tree.cap
+                    });
 
-                let attestation = Attestation {
+            let attestations: Vec<Attestation> = ids_and_paths
+                .iter()
+                .map(|(program_id, _)| Attestation {
                     id: *program_id,
                     public_tape: system_tape
                         .public_input_tape
@@ -382,10 +510,8 @@ fn main() -> Result<()> {
                         .get(program_id)
                         .cloned()
                         .unwrap_or_default(),
-                };
-
-                attestations.push(attestation);
-            }
+                })
+                .collect();
 
             let transaction: Transaction<F, C, D> = Transaction {
                 call_tape_hash: call_tape_hash.expect("system tape generated from entrypoint program's native execution should contain a call tape"),
@@ -397,93 +523,75 @@ fn main() -> Result<()> {
             println!("Transaction bundled: {transaction:?}");
         }
 
-        Command::Verify { mut proof } => {
-            let stark = S::default();
+        Command::Verify(VerifyArgs {
+            mut proof,
+            verifier_key,
+            elf,
+            program_id,
+            security,
+        }) => {
+            let expected_program_id = match (elf, program_id) {
+                (Some(elf), _) => Some(get_self_prog_id::<F, C, D>(&load_program(elf)?)),
+                (None, program_id) => program_id.map(ProgramIdentifier::from),
+            };
+            let verifier_key = verifier_key
+                .map(|mut verifier_key| -> Result<Vec<u8>> {
+                    let mut vk_buffer: Vec<u8> = vec![];
+                    verifier_key.read_to_end(&mut vk_buffer)?;
+                    Ok(vk_buffer)
+                })
+                .transpose()?;
             let mut buffer: Vec<u8> = vec![];
             proof.read_to_end(&mut buffer)?;
-            let all_proof: AllProof<F, C, D> = serde_json::from_slice(&buffer)?;
-            verify_proof(&stark, all_proof, &config)?;
-            println!("proof verified successfully!");
+            let kind = verify_any_proof(buffer, verifier_key, expected_program_id, security)?;
+            println!("{kind:?} proof verified successfully!");
         }
         Command::VerifyRecursiveProof {
             mut proof,
             mut verifier_key,
             program_id,
+            security,
         } => {
-            let mut circuit = circuit_data_for_recursion::<F, C, D>(
-                &VM_RECURSION_CONFIG,
-                VM_RECURSION_THRESHOLD_DEGREE_BITS,
-                VM_PUBLIC_INPUT_SIZE,
-            );
-
             let mut vk_buffer: Vec<u8> = vec![];
             verifier_key.read_to_end(&mut vk_buffer)?;
-            circuit.verifier_only = VerifierOnlyCircuitData::from_bytes(vk_buffer).unwrap();
-
             let mut proof_buffer: Vec<u8> = vec![];
             proof.read_to_end(&mut proof_buffer)?;
-            let proof: ProofWithPublicInputs<F, C, D> =
-                ProofWithPublicInputs::from_bytes(proof_buffer, &circuit.common).map_err(|_| {
-                    anyhow::Error::msg("ProofWithPublicInputs deserialization failed.")
-                })?;
-            let public_inputs_array: [F; VM_PUBLIC_INPUT_SIZE] =
-                proof.public_inputs.clone().try_into().unwrap();
-
-            let public_inputs: VMRecursiveProofPublicInputs<F> = public_inputs_array.into();
-            assert_eq!(
-                public_inputs.program_hash_as_bytes.to_vec(),
-                ProgramIdentifier::from(program_id)
-                    .inner()
-                    .into_iter()
-                    .map(F::from_canonical_u8)
-                    .collect_vec()
-            );
-            println!("Public Inputs: {:?}", proof.public_inputs);
-            println!("Verifier Key: {:?}", circuit.verifier_only);
-
-            circuit.verify(proof.clone())?;
+            let public_inputs = verify_recursive_proof(
+                proof_buffer,
+                vk_buffer,
+                Some(ProgramIdentifier::from(program_id)),
+                security,
+            )?;
+            println!("Public Inputs: {public_inputs:?}");
             println!("Recursive VM proof verified successfully!");
         }
         Command::ProgramRomHash { elf } => {
             let program = load_program(elf)?;
-            let trace = generate_program_rom_trace(&program);
-            let trace_poly_values = trace_rows_to_poly_values(trace);
-            let rate_bits = config.fri_config.rate_bits;
-            let cap_height = config.fri_config.cap_height;
-            let trace_commitment = PolynomialBatch::<F, C, D>::from_values(
-                trace_poly_values,
-                rate_bits,
-                false, // blinding
-                cap_height,
-                &mut TimingTree::default(),
-                None, // fft_root_table
-            );
-            let trace_cap = trace_commitment.merkle_tree.cap;
+            let trace_cap =
+                get_trace_merkle_cap::<F, C, D, _>(generate_program_rom_trace(&program));
             println!("{trace_cap:?}");
         }
         Command::MemoryInitHash { elf } => {
             let program = load_program(elf)?;
-            let trace = generate_elf_memory_init_trace(&program);
-            let trace_poly_values = trace_rows_to_poly_values(trace);
-            let rate_bits = config.fri_config.rate_bits;
-            let cap_height = config.fri_config.cap_height;
-            let trace_commitment = PolynomialBatch::<F, C, D>::from_values(
-                trace_poly_values,
-                rate_bits,
-                false, // blinding
-                cap_height,
-                &mut TimingTree::default(),
-                None, // fft_root_table
-            );
-            let trace_cap = trace_commitment.merkle_tree.cap;
+            let trace_cap =
+                get_trace_merkle_cap::<F, C, D, _>(generate_elf_memory_init_trace(&program));
             println!("{trace_cap:?}");
         }
 
         Command::SelfProgId { elf } => {
             let program = load_program(elf)?;
-            let self_prog_id = get_self_prog_id::<F, C, D>(&program, &config);
+            let self_prog_id = get_self_prog_id::<F, C, D>(&program);
             println!("{self_prog_id:?}");
         }
+        Command::Wiring(WiringArgs { dot, mut out }) => {
+            let wiring = Wiring::of(&S::default());
+            if dot {
+                out.write_all(wiring.to_dot().as_bytes())?;
+            } else {
+                serde_json::to_writer_pretty(&mut out, &wiring)?;
+                writeln!(out)?;
+            }
+        }
         #[cfg(feature = "bench")]
         Command::Bench(bench) => {
             let time_taken = bench.bench()?.as_secs_f64();
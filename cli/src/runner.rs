@@ -1,33 +1,53 @@
 //! Utility functions that helps the CLI to interact with the
 //! [Mozak runner crate](mozak_runner).
-use std::collections::BTreeSet;
-
 use anyhow::Result;
-use itertools::{izip, Itertools};
-use log::debug;
+use log::{debug, warn};
 use mozak_circuits::memoryinit::generation::generate_elf_memory_init_trace;
 use mozak_circuits::program::generation::generate_program_rom_trace;
 use mozak_circuits::stark::prover::get_program_id;
 use mozak_runner::elf::Program;
 use mozak_runner::state::RawTapes;
-use mozak_sdk::common::merkle::merkleize;
-use mozak_sdk::common::types::{
-    CanonicalOrderedTemporalHints, Poseidon2Hash, ProgramIdentifier, SystemTape,
-};
+use mozak_runner::transaction;
+use mozak_sdk::common::types::{ProgramIdentifier, SystemTape};
 use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
-use rkyv::rancor::{Panic, Strategy};
-use rkyv::ser::AllocSerializer;
-use starky::config::StarkConfig;
 
 use crate::trace_utils::get_trace_merkle_cap;
 
-pub fn load_program<F: std::io::Read>(mut elf: F) -> Result<Program> {
+fn read_elf<F: std::io::Read>(mut elf: F) -> Result<Vec<u8>> {
     let mut elf_bytes = Vec::new();
     let bytes_read = elf.read_to_end(&mut elf_bytes)?;
     debug!("Read {bytes_read} of ELF data.");
-    Program::mozak_load_program(&elf_bytes)
+    Ok(elf_bytes)
+}
+
+/// Loads a [`Program`] to run or prove.
+///
+/// Its symbols, sections and Mozak metadata are only kept if they parse; a
+/// malformed ELF info is logged and left out, since running the program does
+/// not need it.
+///
+/// # Errors
+///
+/// Errors if the ELF cannot be read or does not hold a valid program.
+pub fn load_program<F: std::io::Read>(elf: F) -> Result<Program> {
+    let elf_bytes = read_elf(elf)?;
+    Program::mozak_load_program_with_elf_info(&elf_bytes).or_else(|err| {
+        let program = Program::mozak_load_program(&elf_bytes)?;
+        warn!("Ignoring malformed ELF info: {err:#}");
+        Ok(program)
+    })
+}
+
+/// Loads a [`Program`] together with its symbols, sections and Mozak metadata.
+///
+/// # Errors
+///
+/// Errors if the ELF cannot be read, does not hold a valid program, or its ELF
+/// info is malformed.
+pub fn load_program_with_elf_info<F: std::io::Read>(elf: F) -> Result<Program> {
+    Program::mozak_load_program_with_elf_info(&read_elf(elf)?)
 }
 
 /// Deserializes a serde JSON serialized system tape binary file into a
@@ -44,115 +64,32 @@ pub fn deserialize_system_tape<F: std::io::Read>(mut bin: F) -> Result<SystemTap
     Ok(deserialized)
 }
 
-fn length_prefixed_bytes(data: Vec<u8>, dgb_string: &str) -> Vec<u8> {
-    let data_len = data.len();
-    let mut len_prefix_bytes = Vec::with_capacity(data_len + 4);
-    len_prefix_bytes.extend_from_slice(
-        &(u32::try_from(data.len()))
-            .expect("length of data's max size shouldn't be more than u32")
-            .to_le_bytes(),
-    );
-    len_prefix_bytes.extend(data);
-    debug!(
-        "Length-Prefixed {:<15} of byte len: {:>5}, on-mem bytes: {:>5}",
-        dgb_string,
-        data_len,
-        len_prefix_bytes.len()
-    );
-    len_prefix_bytes
-}
-
+/// Derives the [`RawTapes`] of `self_prog_id` from a serialized
+/// [`SystemTape`], if one is given.
+///
+/// # Panics
+///
+/// Panics if the system tape cannot be deserialized.
 pub fn raw_tapes_from_system_tape<F: std::io::Read>(
     sys: Option<F>,
     self_prog_id: ProgramIdentifier,
 ) -> RawTapes {
-    if sys.is_none() {
-        return RawTapes::default();
-    }
-
-    let sys = &deserialize_system_tape(sys.unwrap()).unwrap();
-
-    let cast_list = sys
-        .call_tape
-        .writer
-        .iter()
-        .map(|msg| msg.callee)
-        .collect::<BTreeSet<_>>()
-        .into_iter()
-        .collect_vec();
-
-    let canonical_order_temporal_hints: Vec<CanonicalOrderedTemporalHints> = sys
-        .event_tape
-        .writer
-        .get(&self_prog_id)
-        .cloned()
-        .unwrap_or_default()
-        .get_canonical_order_temporal_hints();
-
-    let events_commitment_tape = merkleize(
-        canonical_order_temporal_hints
-            .iter()
-            .map(|x| {
-                (
-                    // May not be the best idea if
-                    // `addr` > goldilock's prime, cc
-                    // @Kapil
-                    u64::from_le_bytes(x.0.address.inner()),
-                    x.0.canonical_hash(),
-                )
-            })
-            .collect::<Vec<(u64, Poseidon2Hash)>>(),
-    )
-    .0;
-
-    let cast_list_commitment_tape =
-        merkleize(izip!(0.., &cast_list).map(|(idx, x)| (idx, x.0)).collect()).0;
-
-    debug!("Self Prog ID: {self_prog_id:#?}");
-    debug!("Found events: {:#?}", canonical_order_temporal_hints.len());
-
-    {
-        fn serialise<T>(tape: &T, dgb_string: &str) -> Vec<u8>
-        where
-            T: rkyv::Archive + rkyv::Serialize<Strategy<AllocSerializer<256>, Panic>>, {
-            let tape_bytes = rkyv::to_bytes::<_, 256, _>(tape).unwrap().into();
-            length_prefixed_bytes(tape_bytes, dgb_string)
-        }
-
-        RawTapes {
-            private_tape: length_prefixed_bytes(
-                sys.private_input_tape
-                    .writer
-                    .get(&self_prog_id)
-                    .cloned()
-                    .unwrap_or_default()
-                    .0,
-                "PRIVATE_TAPE",
-            ),
-            public_tape: length_prefixed_bytes(
-                sys.public_input_tape
-                    .writer
-                    .get(&self_prog_id)
-                    .cloned()
-                    .unwrap_or_default()
-                    .0,
-                "PUBLIC_TAPE",
-            ),
-            call_tape: serialise(&sys.call_tape.writer, "CALL_TAPE"),
-            event_tape: serialise(&canonical_order_temporal_hints, "EVENT_TAPE"),
-            self_prog_id_tape: self_prog_id.0 .0,
-            events_commitment_tape,
-            cast_list_commitment_tape,
-        }
-    }
+    sys.map_or_else(RawTapes::default, |sys| {
+        transaction::raw_tapes_from_system_tape(
+            &deserialize_system_tape(sys).unwrap(),
+            self_prog_id,
+        )
+    })
 }
 
 /// Computes `[ProgramIdentifer]` from hash of entry point and merkle caps
 /// of `ElfMemoryInit` and `ProgramRom` tables.
-pub fn get_self_prog_id<F, C, const D: usize>(
-    program: &Program,
-    config: &StarkConfig,
-) -> ProgramIdentifier
+///
+/// The caps are computed with
+/// [`program_id_config`](mozak_circuits::stark::security::program_id_config),
+/// so the identifier is the same whichever security profile the program is
+/// proven with.
+pub fn get_self_prog_id<F, C, const D: usize>(program: &Program) -> ProgramIdentifier
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -162,7 +99,7 @@ where
     let elf_memory_init_trace = generate_elf_memory_init_trace::<F>(program);
     let program_rom_trace = generate_program_rom_trace::<F>(program);
 
-    let elf_memory_init_cap = get_trace_merkle_cap::<F, C, D, _>(elf_memory_init_trace, config);
-    let program_cap = get_trace_merkle_cap::<F, C, D, _>(program_rom_trace, config);
+    let elf_memory_init_cap = get_trace_merkle_cap::<F, C, D, _>(elf_memory_init_trace);
+    let program_cap = get_trace_merkle_cap::<F, C, D, _>(program_rom_trace);
     get_program_id::<F, C, D>(entry_point, &program_cap, &elf_memory_init_cap)
 }
@@ -14,6 +14,7 @@ fn test_prove_and_verify_recursive_proof_command() {
     let proof_file = temp_path.join("proof.bin");
     let recursive_proof_file = temp_path.join("recursive_proof.bin");
     let recursive_proof_vk = temp_path.join("recursive_proof.vk");
+    let circuit_cache = temp_path.join("circuits");
 
     let elf_file: &str =
         "../examples/fibonacci/mozakvm/target/riscv32im-mozak-mozakvm-elf/mozak-release/fibonacci-mozakvm";
@@ -38,6 +39,8 @@ fn test_prove_and_verify_recursive_proof_command() {
             elf_file,
             &proof_file.to_string_lossy(),
             &recursive_proof_file.to_string_lossy(),
+            "--circuit-cache",
+            &circuit_cache.to_string_lossy(),
         ])
         .output()
         .expect("Failed to execute prove command");
@@ -52,6 +55,11 @@ fn test_prove_and_verify_recursive_proof_command() {
         let file_exists = file.exists();
         assert!(file_exists, "Expected file {:?} not found", file);
     }
+    assert!(
+        fs::read_dir(&circuit_cache).unwrap().next().is_some(),
+        "Expected the recursive circuits to be cached in {:?}",
+        circuit_cache
+    );
 
     // Execute the `--verify_recursive_proof` command
     let output = Command::new("cargo")
@@ -62,6 +70,8 @@ fn test_prove_and_verify_recursive_proof_command() {
             &recursive_proof_file.to_string_lossy(),
             &recursive_proof_vk.to_string_lossy(),
             &self_prog_id,
+            "--circuit-cache",
+            &circuit_cache.to_string_lossy(),
         ])
         .output()
         .expect("Failed to execute verify-recursive-proof command");
@@ -69,4 +79,22 @@ fn test_prove_and_verify_recursive_proof_command() {
         output.status.success(),
         "Verify recursive proof command failed"
     );
+
+    // Execute the `verify` command on both the STARK and the recursive proof
+    for (proof, extra_args) in [
+        (&proof_file, vec!["--elf", elf_file]),
+        (&recursive_proof_file, vec![
+            "--verifier-key",
+            recursive_proof_vk.to_str().unwrap(),
+            "--program-id",
+            self_prog_id.as_str(),
+        ]),
+    ] {
+        let output = Command::new("cargo")
+            .args(["run", "--", "verify", &proof.to_string_lossy()])
+            .args(extra_args)
+            .output()
+            .expect("Failed to execute verify command");
+        assert!(output.status.success(), "Verify command failed: {output:?}");
+    }
 }
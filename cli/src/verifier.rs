@@ -0,0 +1,135 @@
+//! Verification of the proofs written by the CLI, whatever their kind.
+use anyhow::{anyhow, ensure, Result};
+use itertools::Itertools;
+use log::debug;
+use mozak_circuits::stark::batch_verifier::batch_verify_proof;
+use mozak_circuits::stark::mozak_stark::PUBLIC_TABLE_KINDS;
+use mozak_circuits::stark::proof::{AllProof, BatchProof};
+use mozak_circuits::stark::recursive_verifier::{
+    circuit_data_for_recursion, VMRecursiveProofPublicInputs, VM_PUBLIC_INPUT_SIZE,
+    VM_RECURSION_THRESHOLD_DEGREE_BITS,
+};
+use mozak_circuits::stark::security::SecurityProfile;
+use mozak_circuits::stark::serialization::{ProofHeader, ProofKind, PROOF_MAGIC};
+use mozak_circuits::stark::verifier::verify_proof;
+use mozak_circuits::test_utils::{C, D, F, S};
+use mozak_sdk::common::types::ProgramIdentifier;
+use plonky2::field::types::Field;
+use plonky2::plonk::circuit_data::VerifierOnlyCircuitData;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+/// The kinds of proof accepted by [`verify_any_proof`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifiedProofKind {
+    All,
+    Batch,
+    Recursive,
+}
+
+fn check_program_id(actual: ProgramIdentifier, expected: Option<ProgramIdentifier>) -> Result<()> {
+    if let Some(expected) = expected {
+        ensure!(
+            actual == expected,
+            "Proof is for program {actual:?}, but expected {expected:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Verifies `proof`, detecting its kind from its header.
+///
+/// Proofs without a header are taken to be recursive proofs, which can only
+/// be verified given their `verifier_key`.  If `expected_program_id` is given,
+/// the proof also has to be for that program.  Proofs have to be made with the
+/// security `profile`.
+///
+/// # Errors
+///
+/// Errors if the proof cannot be decoded, is for another program or security
+/// profile, or does not verify.
+pub fn verify_any_proof(
+    proof: Vec<u8>,
+    verifier_key: Option<Vec<u8>>,
+    expected_program_id: Option<ProgramIdentifier>,
+    profile: SecurityProfile,
+) -> Result<VerifiedProofKind> {
+    if !proof.starts_with(&PROOF_MAGIC) {
+        let verifier_key = verifier_key.ok_or_else(|| {
+            anyhow!("Not a STARK proof; a verifier key is needed to verify it as a recursive proof")
+        })?;
+        verify_recursive_proof(proof, verifier_key, expected_program_id, profile)?;
+        return Ok(VerifiedProofKind::Recursive);
+    }
+
+    let stark = S::default();
+    let header = ProofHeader::read(&proof)?;
+    header.check_profile(profile)?;
+    let config = &header.config;
+    match header.kind {
+        ProofKind::All => {
+            let (_header, all_proof) = AllProof::<F, C, D>::from_bytes(&proof)?;
+            check_program_id(all_proof.program_id, expected_program_id)?;
+            verify_proof(&stark, all_proof, config)?;
+            Ok(VerifiedProofKind::All)
+        }
+        ProofKind::Batch => {
+            let (_header, degree_bits, batch_proof) = BatchProof::<F, C, D>::from_bytes(&proof)?;
+            check_program_id(batch_proof.program_id, expected_program_id)?;
+            batch_verify_proof(
+                &stark,
+                &PUBLIC_TABLE_KINDS,
+                batch_proof,
+                config,
+                &degree_bits,
+            )?;
+            Ok(VerifiedProofKind::Batch)
+        }
+    }
+}
+
+/// Verifies a recursive VM proof, made with the security `profile`, against its
+/// `verifier_key`, and returns its public inputs.
+///
+/// # Errors
+///
+/// Errors if the proof or key cannot be decoded, the proof is not for
+/// `expected_program_id`, or it does not verify.
+pub fn verify_recursive_proof(
+    proof: Vec<u8>,
+    verifier_key: Vec<u8>,
+    expected_program_id: Option<ProgramIdentifier>,
+    profile: SecurityProfile,
+) -> Result<VMRecursiveProofPublicInputs<F>> {
+    let mut circuit = circuit_data_for_recursion::<F, C, D>(
+        &profile.recursion_config(),
+        VM_RECURSION_THRESHOLD_DEGREE_BITS,
+        VM_PUBLIC_INPUT_SIZE,
+    );
+    circuit.verifier_only = VerifierOnlyCircuitData::from_bytes(verifier_key)
+        .map_err(|_| anyhow!("VerifierOnlyCircuitData deserialization failed."))?;
+
+    let proof: ProofWithPublicInputs<F, C, D> =
+        ProofWithPublicInputs::from_bytes(proof, &circuit.common)
+            .map_err(|_| anyhow!("ProofWithPublicInputs deserialization failed."))?;
+    let public_inputs_array: [F; VM_PUBLIC_INPUT_SIZE] = proof
+        .public_inputs
+        .clone()
+        .try_into()
+        .map_err(|_| anyhow!("Unexpected number of public inputs"))?;
+    let public_inputs: VMRecursiveProofPublicInputs<F> = public_inputs_array.into();
+    if let Some(expected) = expected_program_id {
+        ensure!(
+            public_inputs.program_hash_as_bytes.to_vec()
+                == expected
+                    .inner()
+                    .into_iter()
+                    .map(F::from_canonical_u8)
+                    .collect_vec(),
+            "Recursive proof is not for program {expected:?}"
+        );
+    }
+    debug!("Verifier Key: {:?}", circuit.verifier_only);
+
+    circuit.verify(proof)?;
+    Ok(public_inputs)
+}
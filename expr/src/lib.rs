@@ -22,6 +22,16 @@
 //!   - [`Expr`] * [`i64`]
 //! - [`Neg`]
 //!   - (- [`Expr`])
+//! - [`BitAnd`](core::ops::BitAnd), [`BitOr`](core::ops::BitOr) and
+//!   [`Not`](core::ops::Not) on binary-valued [`Expr`]s, which give the binary
+//!   value of the conjunction, disjunction and negation.
+//!
+//! Conditions to enforce with constraints are [`Predicate`]s, built from
+//! [`Expr::is_zero`] and [`Expr::is_one`] with `&`, `|` and `!`.
+//! [`Predicate::into_constraints`] splits conjunctions into separate
+//! constraints, and turns disjunctions into products:
+//! `(a & b) | c == (a | c) & (b | c) == [(a | c), (b | c)]`, where `[..]`
+//! means split into multiple constraints.
 //!
 //! Then, the current API for Expr builder was pretty much the ability to inject
 //! `V` and i64 into Exprs
@@ -37,6 +47,12 @@
 //!   - mul
 //!   - neg
 //!
+//! The builder simplifies expressions as it builds them: it folds constants,
+//! drops `x + 0`, `x * 1`, `x * 0`, `x - x` and `--x`, and hash-conses
+//! compound nodes, so that identical sub-trees, also across constraints, are
+//! the same node and are evaluated only once by [`Cached`] evaluators.  Run
+//! code under [`without_simplification`] to build expressions as written.
+//!
 //! There is a private contract between ExprBuilder and Expr, as Expr is just a
 //! wrapper around ExprTree provided by ExprBuilder, as builder internally
 //! operates on ExprTree.
@@ -50,17 +66,11 @@
 //!
 //! Finally, there is the case of Evaluators, because they do form a contract
 //! with internal ExprTree, as they provide the semantics for the operations.
-//!
-//! # TODO
-//!
-//! - [ ] TODO: support `|` via multiplication.
-//! - [ ] TODO support `&` via distributive law, and integration with constraint
-//! builder. (a & b) | c == (a | c) & (b | c) == [(a | c), (b | c)] where [..]
-//! means split into multiple constraints.
 
 pub mod ops;
 
 use core::ops::{Add, Mul, Neg, Sub};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 
 use bumpalo::Bump;
@@ -94,18 +104,36 @@ impl<'a, V> Expr<'a, V> {
             (Expr::Basic { value: left }, Expr::Basic { value: right }) =>
                 Expr::from(PureEvaluator::default().bin_op(op, left, right)),
             (left @ Expr::Compound { builder, .. }, right)
-            | (left, right @ Expr::Compound { builder, .. }) => builder.wrap(builder.bin_op(
-                op,
-                builder.ensure_interned(left),
-                builder.ensure_interned(right),
-            )),
+            | (left, right @ Expr::Compound { builder, .. }) =>
+                builder.bin_op_expr(op, left, right),
         }
     }
 
     fn una_op(op: UnaOp, expr: Expr<'a, V>) -> Expr<'a, V> {
         match expr {
             Expr::Basic { value } => Expr::from(PureEvaluator::default().una_op(op, value)),
-            Expr::Compound { expr, builder } => builder.wrap(builder.una_op(op, expr)),
+            Expr::Compound { expr, builder } => builder.una_op_expr(op, expr),
+        }
+    }
+
+    /// The value of this expression, if it is a constant.
+    fn constant_value(&self) -> Option<i64> {
+        match self {
+            Expr::Basic { value }
+            | Expr::Compound {
+                expr: CompoundExpr(ExprTree::Constant { value }),
+                ..
+            } => Some(*value),
+            Expr::Compound { .. } => None,
+        }
+    }
+
+    /// Whether both expressions are the same node of the same builder.
+    fn same_node(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Expr::Compound { expr: left, .. }, Expr::Compound { expr: right, .. }) =>
+                left.address() == right.address(),
+            _ => false,
         }
     }
 }
@@ -117,6 +145,12 @@ impl<'a, V> Expr<'a, V> {
         self * (1 - self)
     }
 
+    /// The predicate that this expression is zero.
+    pub fn is_zero(self) -> Predicate<'a, V> { Predicate::Zero(self) }
+
+    /// The predicate that this expression is one.
+    pub fn is_one(self) -> Predicate<'a, V> { Predicate::Zero(1 - self) }
+
     /// Reduce a sequence of terms into a single term using powers of `base`.
     pub fn reduce_with_powers<I>(terms: I, base: i64) -> Self
     where
@@ -129,11 +163,153 @@ impl<'a, V> Expr<'a, V> {
     }
 }
 
+/// A condition on expressions that can be enforced with constraints, built
+/// with [`Expr::is_zero`], [`Expr::is_one`], `&`, `|` and `!`.
+///
+/// Negation is only sound for binary-valued expressions: `!x.is_zero()` is
+/// taken to mean `x.is_one()`.
+#[derive(Clone, Debug)]
+pub enum Predicate<'a, V> {
+    /// Holds iff the expression is zero.
+    Zero(Expr<'a, V>),
+    And(Box<Predicate<'a, V>>, Box<Predicate<'a, V>>),
+    Or(Box<Predicate<'a, V>>, Box<Predicate<'a, V>>),
+}
+
+impl<'a, V> From<Expr<'a, V>> for Predicate<'a, V> {
+    fn from(expr: Expr<'a, V>) -> Self { expr.is_zero() }
+}
+
+impl<'a, V> Predicate<'a, V>
+where
+    V: Copy,
+{
+    /// The constraints that enforce this predicate, ie terms that all have
+    /// to be zero.
+    ///
+    /// Conjunctions become separate constraints, and a disjunction becomes
+    /// the products of the constraints of its operands.  The degree of a
+    /// disjunction is thus the sum of the degrees of its operands.
+    pub fn into_constraints(self) -> Vec<Expr<'a, V>> {
+        match self {
+            Predicate::Zero(expr) => vec![expr],
+            Predicate::And(left, right) => {
+                let mut constraints = left.into_constraints();
+                constraints.extend(right.into_constraints());
+                constraints
+            }
+            Predicate::Or(left, right) => {
+                let right = right.into_constraints();
+                left.into_constraints()
+                    .into_iter()
+                    .flat_map(|l| right.iter().map(move |&r| l * r))
+                    .collect()
+            }
+        }
+    }
+
+    /// The value of this predicate, one if it holds and zero otherwise,
+    /// assuming all its expressions are binary-valued.
+    pub fn value(self) -> Expr<'a, V> {
+        match self {
+            Predicate::Zero(expr) => !expr,
+            Predicate::And(left, right) => left.value() & right.value(),
+            Predicate::Or(left, right) => left.value() | right.value(),
+        }
+    }
+}
+
+thread_local! {
+    static SIMPLIFY: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Runs `f` with [`ExprBuilder`]s created in it building expressions exactly
+/// as written, without simplification or sharing of identical sub-trees.
+///
+/// Mostly useful to measure what the simplification saves.
+pub fn without_simplification<R>(f: impl FnOnce() -> R) -> R {
+    /// Restores the previous setting, even if `f` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) { SIMPLIFY.with(|simplify| simplify.set(self.0)); }
+    }
+
+    let _restore = Restore(SIMPLIFY.with(|simplify| simplify.replace(false)));
+    f()
+}
+
+/// An operand of a hash-consed node.  Constants are keyed by value, and trees
+/// by their address in the arena.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+enum Operand {
+    Constant(i64),
+    Tree(usize),
+}
+
+impl Operand {
+    fn of<V>(expr: &Expr<'_, V>) -> Self {
+        match (expr.constant_value(), expr) {
+            (Some(value), _) => Operand::Constant(value),
+            (None, Expr::Compound { expr, .. }) => Operand::Tree(expr.address()),
+            (None, Expr::Basic { .. }) => unreachable!("basic expressions are constants"),
+        }
+    }
+}
+
+/// Key of a hash-consed node.
+///
+/// Every key holds at least one [`Operand::Tree`].  As [`ExprTree`]s are
+/// never zero-sized and the arena never frees, that address identifies a
+/// single live tree, and thus also the type `V` of every node built on it.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+enum NodeKey {
+    BinOp(BinOp, Operand, Operand),
+    UnaOp(UnaOp, usize),
+}
+
+impl NodeKey {
+    fn bin_op<V>(op: BinOp, left: &Expr<'_, V>, right: &Expr<'_, V>) -> Option<Self> {
+        let (mut left, mut right) = (Operand::of(left), Operand::of(right));
+        if matches!(op, BinOp::Add | BinOp::Mul) && right < left {
+            std::mem::swap(&mut left, &mut right);
+        }
+        // Two constants only get here if folding them overflowed, and would
+        // not pin down `V`.
+        [left, right]
+            .iter()
+            .any(|operand| matches!(operand, Operand::Tree(_)))
+            .then_some(NodeKey::BinOp(op, left, right))
+    }
+}
+
+/// Folds an operation on two constants, unless it overflows.
+fn fold_constants(op: BinOp, left: i64, right: i64) -> Option<i64> {
+    match op {
+        BinOp::Add => left.checked_add(right),
+        BinOp::Sub => left.checked_sub(right),
+        BinOp::Mul => left.checked_mul(right),
+    }
+}
+
 /// Expression Builder.  Contains a [`Bump`] memory arena that will allocate and
 /// store all the [`ExprTree`]s.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ExprBuilder {
     bump: Bump,
+    /// Addresses of the hash-consed compound nodes.
+    nodes: RefCell<HashMap<NodeKey, usize>>,
+    simplify: bool,
+}
+
+impl Default for ExprBuilder {
+    fn default() -> Self {
+        Self {
+            bump: Bump::default(),
+            nodes: RefCell::default(),
+            simplify: SIMPLIFY.with(Cell::get),
+        }
+    }
 }
 
 impl ExprBuilder {
@@ -158,6 +334,79 @@ impl ExprBuilder {
         }
     }
 
+    /// Builds `left op right`, simplified and hash-consed.
+    fn bin_op_expr<'a, V>(
+        &'a self,
+        op: BinOp,
+        left: Expr<'a, V>,
+        right: Expr<'a, V>,
+    ) -> Expr<'a, V> {
+        if !self.simplify {
+            return self.wrap(self.bin_op(
+                op,
+                self.ensure_interned(left),
+                self.ensure_interned(right),
+            ));
+        }
+        let (l, r) = (left.constant_value(), right.constant_value());
+        if let Some(value) = l.zip(r).and_then(|(l, r)| fold_constants(op, l, r)) {
+            return Expr::from(value);
+        }
+        match (op, l, r) {
+            (BinOp::Add, Some(0), _) | (BinOp::Mul, Some(1), _) => return right,
+            (BinOp::Add | BinOp::Sub, _, Some(0)) | (BinOp::Mul, _, Some(1)) => return left,
+            (BinOp::Mul, Some(0), _) | (BinOp::Mul, _, Some(0)) => return Expr::from(0),
+            (BinOp::Sub, Some(0), None) => return Expr::una_op(UnaOp::Neg, right),
+            (BinOp::Sub, _, _) if left.same_node(&right) => return Expr::from(0),
+            _ => {}
+        }
+        let key = NodeKey::bin_op(op, &left, &right);
+        let build = || self.bin_op(op, self.ensure_interned(left), self.ensure_interned(right));
+        match key {
+            Some(key) => self.wrap(self.hash_consed(key, build)),
+            None => self.wrap(build()),
+        }
+    }
+
+    /// Builds `op expr`, simplified and hash-consed.
+    fn una_op_expr<'a, V>(&'a self, op: UnaOp, expr: CompoundExpr<'a, V>) -> Expr<'a, V> {
+        if !self.simplify {
+            return self.wrap(self.una_op(op, expr));
+        }
+        match (op, expr.0) {
+            (UnaOp::Neg, ExprTree::Constant { value }) =>
+                if let Some(value) = value.checked_neg() {
+                    return Expr::from(value);
+                },
+            (
+                UnaOp::Neg,
+                ExprTree::UnaOp {
+                    op: UnaOp::Neg,
+                    expr,
+                },
+            ) => return self.wrap(CompoundExpr(expr.0)),
+            _ => {}
+        }
+        let key = NodeKey::UnaOp(op, expr.address());
+        self.wrap(self.hash_consed(key, || self.una_op(op, expr)))
+    }
+
+    /// Returns the node for `key`, building it if there is none yet.
+    fn hash_consed<'a, V>(
+        &'a self,
+        key: NodeKey,
+        build: impl FnOnce() -> CompoundExpr<'a, V>,
+    ) -> CompoundExpr<'a, V> {
+        if let Some(&address) = self.nodes.borrow().get(&key) {
+            // SAFETY: the node was allocated in our arena, which outlives
+            // `'a`, and has type `ExprTree<'_, V>` as explained on `NodeKey`.
+            return CompoundExpr(unsafe { &*(address as *const ExprTree<'a, V>) });
+        }
+        let node = build();
+        self.nodes.borrow_mut().insert(key, node.address());
+        node
+    }
+
     /// Convenience method for creating `BinOp` nodes
     fn bin_op<'a, V>(
         &'a self,
@@ -252,6 +501,10 @@ pub enum UnaOp {
 #[derive(Debug, Clone, Copy)]
 pub struct CompoundExpr<'a, V>(&'a ExprTree<'a, V>);
 
+impl<'a, V> CompoundExpr<'a, V> {
+    fn address(&self) -> usize { self.0 as *const ExprTree<'a, V> as usize }
+}
+
 impl<'a, V> From<&'a ExprTree<'a, V>> for CompoundExpr<'a, V> {
     fn from(value: &'a ExprTree<'a, V>) -> Self { CompoundExpr(value) }
 }
@@ -363,6 +616,10 @@ where
     }
 }
 
+impl<'a, V, E> Cached<'a, V, E> {
+    pub fn evaluator(&self) -> &E { &self.evaluator }
+}
+
 impl<'a, V, E> Evaluator<'a, V> for Cached<'a, V, E>
 where
     V: Copy,
@@ -435,6 +692,61 @@ where
     }
 }
 
+/// Computes the degree of expressions, taking every literal to be a variable
+/// of degree one and every constant to be of degree zero.
+///
+/// The result is an upper bound, as terms that cancel out are not noticed.
+/// Like [`Cached`], shared sub-trees are only visited once.
+pub struct DegreeEvaluator<'a, V> {
+    cache: HashMap<*const ExprTree<'a, V>, usize>,
+}
+
+impl<'a, V> Default for DegreeEvaluator<'a, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, V> DegreeEvaluator<'a, V> {
+    pub fn eval(&mut self, expr: Expr<'a, V>) -> usize {
+        match expr {
+            Expr::Basic { .. } => 0,
+            Expr::Compound { expr, builder: _ } => self.compound_expr(expr),
+        }
+    }
+
+    fn compound_expr(&mut self, expr: CompoundExpr<'a, V>) -> usize {
+        let expr_tree = expr.0;
+        let k = expr_tree as *const ExprTree<'_, V>;
+        if let Some(&degree) = self.cache.get(&k) {
+            return degree;
+        }
+
+        let degree = match expr_tree {
+            ExprTree::BinOp {
+                op: BinOp::Add | BinOp::Sub,
+                left,
+                right,
+            } => self.compound_expr(*left).max(self.compound_expr(*right)),
+            ExprTree::BinOp {
+                op: BinOp::Mul,
+                left,
+                right,
+            } => self.compound_expr(*left) + self.compound_expr(*right),
+            ExprTree::UnaOp {
+                op: UnaOp::Neg,
+                expr,
+            } => self.compound_expr(*expr),
+            ExprTree::Literal { .. } => 1,
+            ExprTree::Constant { .. } => 0,
+        };
+        self.cache.insert(k, degree);
+        degree
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,6 +849,100 @@ mod tests {
         assert_eq!(c.evaluator.count(), 10);
     }
 
+    #[test]
+    fn degree() {
+        let eb = ExprBuilder::default();
+        let a = eb.lit(7i64);
+        let b = eb.lit(5i64);
+
+        let mut d = DegreeEvaluator::default();
+        assert_eq!(d.eval(Expr::from(3)), 0);
+        assert_eq!(d.eval(eb.constant(3)), 0);
+        assert_eq!(d.eval(a), 1);
+        assert_eq!(d.eval(a + b), 1);
+        assert_eq!(d.eval(3 * a - 2), 1);
+        assert_eq!(d.eval(a.is_binary()), 2);
+        assert_eq!(d.eval(-(a * b * (a + 1))), 3);
+
+        let mut power = a;
+        for _ in 0..16 {
+            power = power * power;
+        }
+        assert_eq!(d.eval(power), 1 << 16);
+    }
+
+    #[test]
+    fn boolean_operators() {
+        let eb = ExprBuilder::default();
+        let mut p = PureEvaluator::default();
+        for (a, b) in [(0i64, 0), (0, 1), (1, 0), (1, 1)] {
+            let (x, y) = (eb.lit(a), eb.lit(b));
+            assert_eq!(p.eval(x & y), a & b);
+            assert_eq!(p.eval(x | y), a | b);
+            assert_eq!(p.eval(!x), 1 - a);
+            assert_eq!(p.eval((x.is_one() | !y.is_one()).value()), a | (1 - b));
+        }
+    }
+
+    #[test]
+    fn predicates_split_into_constraints() {
+        let eb = ExprBuilder::default();
+        let mut p = PureEvaluator::default();
+        let mut d = DegreeEvaluator::default();
+        let (a, b, c) = (eb.lit(2i64), eb.lit(3i64), eb.lit(5i64));
+
+        // (a & b) | c == [(a | c), (b | c)]
+        let constraints = ((a.is_zero() & b.is_zero()) | c.is_zero()).into_constraints();
+        assert_eq!(constraints.len(), 2);
+        assert_eq!(p.eval(constraints[0]), 10);
+        assert_eq!(p.eval(constraints[1]), 15);
+        assert_eq!(d.eval(constraints[0]), 2);
+
+        // !(a & b) == !a | !b
+        let constraints = (!(a.is_one() & b.is_zero())).into_constraints();
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(p.eval(constraints[0]), 2 * (1 - 3));
+    }
+
+    #[test]
+    fn simplifies_while_building() {
+        let eb = ExprBuilder::default();
+        let mut p = PureEvaluator::default();
+        let (x, y) = (eb.lit(7i64), eb.lit(5i64));
+
+        assert!(matches!(eb.constant(2) * eb.constant(3) - 1, Expr::Basic {
+            value: 5
+        }));
+        assert!(matches!(x * 0, Expr::Basic { value: 0 }));
+        assert!(matches!(x - x, Expr::Basic { value: 0 }));
+        assert!(matches!((x + y) - (y + x), Expr::Basic { value: 0 }));
+        assert!((x * 1 + 0).same_node(&x));
+        assert!((-(-x)).same_node(&x));
+        assert_eq!(p.eval(0 - x), -7);
+
+        without_simplification(|| {
+            let eb = ExprBuilder::default();
+            let x = eb.lit(7i64);
+            assert!(!(x * 1).same_node(&x));
+            assert!(matches!(x - x, Expr::Compound { .. }));
+        });
+    }
+
+    #[test]
+    fn shares_identical_sub_trees() {
+        let eb = ExprBuilder::default();
+        let (x, y) = (eb.lit(7i64), eb.lit(5i64));
+
+        assert!((x * y + 3).same_node(&(3 + y * x)));
+        assert!(!(x - y).same_node(&(y - x)));
+
+        // Two constraints that share a sub-tree only evaluate it once.
+        let mut c = Cached::<i64, Counting<PureEvaluator<_>>>::default();
+        assert_eq!(c.eval((x + y) * x), 84);
+        assert_eq!(c.eval((y + x) * y), 60);
+        assert_eq!(c.evaluator.count(), 3);
+    }
+
     #[test]
     fn avoids_exponential_blowup() {
         let eb = ExprBuilder::default();
@@ -2,9 +2,9 @@
 //! `core::iter::Sum`.
 
 use core::iter::Sum;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, BitAnd, BitOr, Mul, MulAssign, Neg, Not, Sub, SubAssign};
 
-use crate::{BinOp, Expr, UnaOp};
+use crate::{BinOp, Expr, Predicate, UnaOp};
 
 macro_rules! binop_instances {
     ($op: ident, $fun: ident) => {
@@ -97,3 +97,53 @@ where
         iter.fold(Expr::from(0), Add::add)
     }
 }
+
+/// Conjunction of binary-valued expressions.
+impl<'a, V> BitAnd for Expr<'a, V> {
+    type Output = Expr<'a, V>;
+
+    fn bitand(self, rhs: Self) -> Self::Output { self * rhs }
+}
+
+/// Disjunction of binary-valued expressions.
+impl<'a, V> BitOr for Expr<'a, V>
+where
+    V: Copy,
+{
+    type Output = Expr<'a, V>;
+
+    fn bitor(self, rhs: Self) -> Self::Output { self + rhs - self * rhs }
+}
+
+/// Negation of a binary-valued expression.
+impl<'a, V> Not for Expr<'a, V> {
+    type Output = Expr<'a, V>;
+
+    fn not(self) -> Self::Output { 1 - self }
+}
+
+impl<'a, V> BitAnd for Predicate<'a, V> {
+    type Output = Predicate<'a, V>;
+
+    fn bitand(self, rhs: Self) -> Self::Output { Predicate::And(Box::new(self), Box::new(rhs)) }
+}
+
+impl<'a, V> BitOr for Predicate<'a, V> {
+    type Output = Predicate<'a, V>;
+
+    fn bitor(self, rhs: Self) -> Self::Output { Predicate::Or(Box::new(self), Box::new(rhs)) }
+}
+
+/// Negation by De Morgan's laws, down to the expressions, which have to be
+/// binary-valued.
+impl<'a, V> Not for Predicate<'a, V> {
+    type Output = Predicate<'a, V>;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Predicate::Zero(expr) => expr.is_one(),
+            Predicate::And(left, right) => !*left | !*right,
+            Predicate::Or(left, right) => !*left & !*right,
+        }
+    }
+}
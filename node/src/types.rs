@@ -15,9 +15,9 @@ use serde::{Deserialize, Serialize};
 pub struct Attestation {
     /// The ID of the program that this attestation is associated with.
     pub id: ProgramIdentifier,
-    // TODO(bing): Attest to its commitment
     /// Public inputs to the execution of a `MozakVM` program, provided in the
-    /// clear.
+    /// clear.  The recursive proof of the execution commits to them, see
+    /// [`mozak_circuits::stark::recursive_verifier::public_tape_commitment`].
     pub public_tape: Vec<u8>,
     // TODO(bing): Attest to its commitment
     /// Events emitted during the execution of a `MozakVM` program, provided in
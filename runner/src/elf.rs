@@ -7,8 +7,9 @@ use elf::file::Class;
 use elf::segment::{ProgramHeader, SegmentTable};
 use elf::ElfBytes;
 use im::hashmap::HashMap;
-use itertools::{chain, iproduct, Itertools};
+use itertools::{chain, Itertools};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::code::Code;
 
@@ -28,6 +29,11 @@ pub struct Program {
 
     /// Executable code of the ELF, read only
     pub ro_code: Code,
+
+    /// Symbols, sections and metadata of the ELF, if they were retained
+    /// while loading.
+    #[serde(default)]
+    pub elf_info: Option<ElfInfo>,
 }
 
 /// Memory of RISC-V Program
@@ -42,6 +48,208 @@ impl std::ops::Deref for Data {
     fn deref(&self) -> &Self::Target { &self.0 }
 }
 
+/// Name of the section holding the [`MozakMetadata`] note.
+pub const MOZAK_METADATA_SECTION: &str = ".mozak_metadata";
+
+/// Owner name of the [`MozakMetadata`] note.
+pub const MOZAK_NOTE_NAME: &str = "Mozak";
+
+/// Note type of the [`MozakMetadata`] note.
+pub const NT_MOZAK_METADATA: u32 = 1;
+
+/// Symbols, section headers and Mozak metadata of the ELF a [`Program`] was
+/// loaded from.
+///
+/// None of this influences execution or proving; it lets tools show names and
+/// check compatibility.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ElfInfo {
+    /// Named, defined symbols of `.symtab`, sorted by address.
+    pub symbols: Vec<ElfSymbol>,
+    /// Section headers in the order they appear in the ELF.
+    pub sections: Vec<ElfSection>,
+    /// Contents of the `.mozak_metadata` note, if present.
+    pub metadata: Option<MozakMetadata>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ElfSymbol {
+    pub name: String,
+    pub addr: u32,
+    pub size: u32,
+    pub is_function: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ElfSection {
+    pub name: String,
+    pub addr: u32,
+    pub size: u32,
+    /// `sh_type` of the section, e.g. [`elf::abi::SHT_PROGBITS`]
+    pub section_type: u32,
+    /// `sh_flags` of the section, e.g. [`elf::abi::SHF_ALLOC`]
+    pub flags: u32,
+}
+
+/// Metadata a guest declares about itself in its `.mozak_metadata` section.
+///
+/// The section holds an ELF note owned by [`MOZAK_NOTE_NAME`] with type
+/// [`NT_MOZAK_METADATA`].  The note's descriptor is UTF-8 text of
+/// `key=value` lines:
+///
+/// ```text
+/// name=token
+/// sdk_version=0.2.0
+/// tapes=public,private,call,event
+/// ```
+///
+/// Unknown keys are ignored, so that newer SDKs can add entries.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MozakMetadata {
+    pub program_name: String,
+    pub sdk_version: String,
+    /// Tapes the guest declares to read from.
+    pub tapes: Vec<String>,
+}
+
+impl MozakMetadata {
+    /// Parse the descriptor of a [`NT_MOZAK_METADATA`] note.
+    ///
+    /// # Errors
+    /// Errors if the descriptor is not UTF-8 or has a line without `=`.
+    pub fn from_note_desc(desc: &[u8]) -> Result<Self> {
+        let mut metadata = MozakMetadata::default();
+        for line in std::str::from_utf8(desc)?
+            .trim_end_matches('\0')
+            .lines()
+            .filter(|line| !line.is_empty())
+        {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Malformed Mozak metadata entry: {line:?}"))?;
+            match key {
+                "name" => metadata.program_name = value.to_string(),
+                "sdk_version" => metadata.sdk_version = value.to_string(),
+                "tapes" =>
+                    metadata.tapes = value
+                        .split(',')
+                        .filter(|tape| !tape.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                _ => {}
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// Split raw note data into `(name, type, descriptor)` triples.
+///
+/// We parse notes by hand instead of via [`ElfBytes::section_data_as_notes`],
+/// because sections placed via `#[link_section]` end up as `SHT_PROGBITS`
+/// rather than `SHT_NOTE`.
+fn parse_notes(mut data: &[u8]) -> Result<Vec<(&str, u32, &[u8])>> {
+    fn align4(n: usize) -> usize { (n + 3) & !3 }
+    let mut notes = vec![];
+    while !data.is_empty() {
+        ensure!(data.len() >= 12, "Truncated note header");
+        let word = |i: usize| u32::from_le_bytes([0, 1, 2, 3].map(|j| data[4 * i + j]));
+        let name_size = usize::try_from(word(0))?;
+        let desc_size = usize::try_from(word(1))?;
+        let desc_start = 12 + align4(name_size);
+        let desc_end = desc_start + desc_size;
+        ensure!(desc_end <= data.len(), "Truncated note");
+        let name = std::str::from_utf8(&data[12..12 + name_size])?.trim_end_matches('\0');
+        notes.push((name, word(2), &data[desc_start..desc_end]));
+        data = &data[align4(desc_end).min(data.len())..];
+    }
+    Ok(notes)
+}
+
+impl ElfInfo {
+    /// Collect symbols, section headers and Mozak metadata from a parsed ELF.
+    ///
+    /// # Errors
+    /// Errors if the symbol table, section headers or metadata note are
+    /// malformed.
+    pub fn parse(elf: &ElfBytes<LittleEndian>) -> Result<ElfInfo> {
+        let symbols = match elf.symbol_table()? {
+            Some((symbols, strings)) => symbols
+                .iter()
+                .filter(|symbol| !symbol.is_undefined() && symbol.st_name != 0)
+                .map(|symbol| -> Result<_> {
+                    Ok(ElfSymbol {
+                        name: strings.get(usize::try_from(symbol.st_name)?)?.to_string(),
+                        addr: symbol.st_value.try_into()?,
+                        size: symbol.st_size.try_into()?,
+                        is_function: symbol.st_symtype() == elf::abi::STT_FUNC,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .sorted_by(|a, b| (a.addr, &a.name).cmp(&(b.addr, &b.name)))
+                .collect(),
+            None => vec![],
+        };
+
+        let sections: Vec<ElfSection> = match elf.section_headers_with_strtab()? {
+            (Some(headers), strings) => headers
+                .iter()
+                .map(|header| -> Result<_> {
+                    Ok(ElfSection {
+                        name: match strings {
+                            Some(strings) => strings.get(usize::try_from(header.sh_name)?)?,
+                            None => "",
+                        }
+                        .to_string(),
+                        addr: header.sh_addr.try_into()?,
+                        size: header.sh_size.try_into()?,
+                        section_type: header.sh_type,
+                        flags: header.sh_flags.try_into()?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            (None, _) => vec![],
+        };
+
+        let metadata = match elf.section_header_by_name(MOZAK_METADATA_SECTION)? {
+            Some(header) => {
+                let (data, _) = elf.section_data(&header)?;
+                parse_notes(data)?
+                    .into_iter()
+                    .find(|&(name, n_type, _)| {
+                        name == MOZAK_NOTE_NAME && n_type == NT_MOZAK_METADATA
+                    })
+                    .map(|(_, _, desc)| MozakMetadata::from_note_desc(desc))
+                    .transpose()?
+            }
+            None => None,
+        };
+
+        Ok(ElfInfo {
+            symbols,
+            sections,
+            metadata,
+        })
+    }
+
+    /// The function symbol whose range covers `addr`, e.g. to name the
+    /// function a `pc` belongs to.
+    #[must_use]
+    pub fn function_at(&self, addr: u32) -> Option<&ElfSymbol> {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.is_function)
+            .find(|symbol| addr.wrapping_sub(symbol.addr) < symbol.size.max(1))
+    }
+
+    /// Look up a symbol by name.
+    #[must_use]
+    pub fn symbol(&self, name: &str) -> Option<&ElfSymbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+}
+
 impl From<HashMap<u32, u32>> for Program {
     fn from(image: HashMap<u32, u32>) -> Self {
         for (addr, val) in image.iter() {
@@ -56,40 +264,68 @@ impl From<HashMap<u32, u32>> for Program {
             ro_code: Code::from(&image),
             ro_memory: Data::default(),
             rw_memory: Data(image),
+            elf_info: None,
         }
     }
 }
 
-impl From<HashMap<u32, u32>> for Data {
-    fn from(image: HashMap<u32, u32>) -> Self {
-        // Check for overlapping data
-        //
-        // For example, if someone specifies
-        // 0: 0xDEAD_BEEF, 1: 0xDEAD_BEEF
-        // we would have conflicting values for bytes 1, 2, and 3.
-        if image.len() > 1 {
-            for (i, ((key0, val0), (key1, val1))) in
-                iproduct!(0..4, image.iter().sorted().circular_tuple_windows())
-            {
-                assert!(
-                    key0.wrapping_add(i) != *key1,
-                    "Overlapping data: {key0:x}:{val0:x} clashes with {key1:x}:{val1}"
-                );
-            }
+impl TryFrom<HashMap<u32, u32>> for Data {
+    type Error = ElfLoadError;
+
+    /// Lay out words of memory as bytes.
+    ///
+    /// Errors if words overlap, for example if someone specifies
+    /// `0: 0xDEAD_BEEF, 1: 0xDEAD_BEEF` we would have conflicting values for
+    /// bytes 1, 2, and 3.
+    fn try_from(image: HashMap<u32, u32>) -> Result<Self, Self::Error> {
+        if let Some(&addr) = image.keys().find(|&&addr| addr > u32::MAX - 3) {
+            return Err(ElfLoadError::SegmentOutOfAddressSpace {
+                vaddr: addr.into(),
+                mem_size: 4,
+            });
+        }
+        if let Some((&first, &second)) = image
+            .keys()
+            .sorted()
+            .tuple_windows()
+            .find(|&(first, second)| second - first < 4)
+        {
+            return Err(ElfLoadError::OverlappingSegments {
+                first: first.into(),
+                second: second.into(),
+            });
         }
-        Data(
+        Ok(Data(
             image
                 .iter()
-                .flat_map(move |(k, v)| {
-                    (u64::from(*k)..)
-                        .map(|k| u32::try_from(k).unwrap())
-                        .zip(v.to_le_bytes())
-                })
+                .flat_map(|(&addr, value)| (addr..=u32::MAX).zip(value.to_le_bytes()))
                 .collect(),
-        )
+        ))
     }
 }
 
+/// Reasons for rejecting an ELF while loading it into a [`Program`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfLoadError {
+    #[error("Segments starting at {first:#x} and {second:#x} overlap")]
+    OverlappingSegments { first: u64, second: u64 },
+    #[error("Segment at {vaddr:#x} of size {mem_size:#x} does not fit into 32-bit address space")]
+    SegmentOutOfAddressSpace { vaddr: u64, mem_size: u64 },
+    #[error(
+        "Segment data at file offset {offset:#x} of size {file_size:#x} is beyond the end of the \
+         file ({file_len:#x} bytes)"
+    )]
+    FileOffsetOutOfRange {
+        offset: u64,
+        file_size: u64,
+        file_len: usize,
+    },
+    #[error("Segment at {vaddr:#x} is both writable and executable")]
+    WritableExecutableSegment { vaddr: u64 },
+    #[error("No code at entry point {entry_point:#x}")]
+    MissingEntryPointCode { entry_point: u32 },
+}
+
 impl Program {
     /// Vanilla load-elf - NOT expect "_mozak_*" symbols in link. Maybe we
     /// should rename it later, with `vanilla_` prefix
@@ -98,27 +334,32 @@ impl Program {
     /// Same as `Program::internal_load_elf`
     pub fn vanilla_load_elf(input: &[u8]) -> Result<Program> {
         let (_, entry_point, segments) = Program::parse_and_validate_elf(input)?;
-        Ok(Program::internal_load_elf(
-            input,
-            entry_point,
-            segments,
-            |flags, _| {
-                (flags & elf::abi::PF_R == elf::abi::PF_R)
-                    && (flags & elf::abi::PF_W == elf::abi::PF_NONE)
-            },
-        ))
+        Program::internal_load_elf(input, entry_point, segments, |flags, _| {
+            (flags & elf::abi::PF_R == elf::abi::PF_R)
+                && (flags & elf::abi::PF_W == elf::abi::PF_NONE)
+        })
     }
 
     /// Mozak load-elf - expect "_mozak_*" symbols in link
+    ///
+    /// Unlike [`Program::vanilla_load_elf`], this rejects segments that are
+    /// both writable and executable.
+    ///
     /// # Errors
-    /// Same as `Program::internal_load_elf`
-    /// # Panics
-    /// Same as `Program::internal_load_elf`
-    #[must_use]
+    /// Same as `Program::internal_load_elf`, and
+    /// [`ElfLoadError::WritableExecutableSegment`].
     pub fn mozak_load_elf(
         input: &[u8],
         (_elf, entry_point, segments): (ElfBytes<LittleEndian>, u32, SegmentTable<LittleEndian>),
-    ) -> Program {
+    ) -> Result<Program> {
+        if let Some(header) = Program::load_segments(&segments).find(|header| {
+            header.p_flags & (elf::abi::PF_W | elf::abi::PF_X) == elf::abi::PF_W | elf::abi::PF_X
+        }) {
+            return Err(ElfLoadError::WritableExecutableSegment {
+                vaddr: header.p_vaddr,
+            }
+            .into());
+        }
         // Information related to the `check_program_flags`
         // `&& (!mozak_memory.is_mozak_ro_memory_address(ph))` --> this line is used to
         // filter RO-addresses related to the mozak-ROM. Currently we don't
@@ -152,9 +393,61 @@ impl Program {
             .segments()
             .ok_or_else(|| anyhow!("Missing segment table"))?;
         ensure!(segments.len() <= 256, "Too many program headers");
+        Program::validate_segments(input, &segments)?;
         Ok((elf, entry_point, segments))
     }
 
+    /// Segments that get loaded into memory.
+    fn load_segments<'data>(
+        segments: &SegmentTable<'data, LittleEndian>,
+    ) -> impl Iterator<Item = ProgramHeader> + 'data {
+        segments
+            .iter()
+            .filter(|header| header.p_type == elf::abi::PT_LOAD && header.p_memsz > 0)
+    }
+
+    /// Check that every loadable segment fits into the 32-bit address space,
+    /// has its file data within `input`, and doesn't overlap any other.
+    fn validate_segments(
+        input: &[u8],
+        segments: &SegmentTable<LittleEndian>,
+    ) -> Result<(), ElfLoadError> {
+        let mut ranges = vec![];
+        for header in Program::load_segments(segments) {
+            let end = header
+                .p_vaddr
+                .checked_add(header.p_memsz)
+                .filter(|&end| end <= 1 << 32)
+                .ok_or(ElfLoadError::SegmentOutOfAddressSpace {
+                    vaddr: header.p_vaddr,
+                    mem_size: header.p_memsz,
+                })?;
+            let file_size = min(header.p_filesz, header.p_memsz);
+            if header
+                .p_offset
+                .checked_add(file_size)
+                .map_or(true, |file_end| file_end > input.len() as u64)
+            {
+                return Err(ElfLoadError::FileOffsetOutOfRange {
+                    offset: header.p_offset,
+                    file_size,
+                    file_len: input.len(),
+                });
+            }
+            ranges.push((header.p_vaddr, end));
+        }
+        ranges.sort_unstable();
+        match ranges
+            .into_iter()
+            .tuple_windows()
+            .find(|&((_, first_end), (second, _))| second < first_end)
+        {
+            Some(((first, _), (second, _))) =>
+                Err(ElfLoadError::OverlappingSegments { first, second }),
+            None => Ok(()),
+        }
+    }
+
     /// Initialize a RISC Program from a validated ELF file.
     #[allow(clippy::similar_names)]
     fn internal_load_elf(
@@ -162,18 +455,18 @@ impl Program {
         entry_point: u32,
         segments: SegmentTable<LittleEndian>,
         check_program_flags: fn(flags: u32, program_headers: &ProgramHeader) -> bool,
-    ) -> Program {
+    ) -> Result<Program> {
         let ro_memory = Data(Program::extract_elf_data(
             check_program_flags,
             input,
             &segments,
-        ));
+        )?);
 
         let rw_memory = Data(Program::extract_elf_data(
             |flags, _| flags == elf::abi::PF_R | elf::abi::PF_W,
             input,
             &segments,
-        ));
+        )?);
 
         // Because we are implementing a modified Harvard Architecture, we make an
         // independent copy of the executable segments. In practice,
@@ -183,23 +476,26 @@ impl Program {
             |flags, _| flags & elf::abi::PF_X == elf::abi::PF_X,
             input,
             &segments,
-        ));
+        )?);
+        if ro_code.get_instruction(entry_point).is_none() {
+            return Err(ElfLoadError::MissingEntryPointCode { entry_point }.into());
+        }
 
-        Program {
+        Ok(Program {
             entry_point,
             ro_memory,
             rw_memory,
             ro_code,
-        }
+            elf_info: None,
+        })
     }
 
     fn extract_elf_data(
         check_program_flags: fn(flags: u32, program_headers: &ProgramHeader) -> bool,
         input: &[u8],
         segments: &SegmentTable<LittleEndian>,
-    ) -> HashMap<u32, u8> {
-        segments
-            .iter()
+    ) -> Result<HashMap<u32, u8>> {
+        Program::load_segments(segments)
             .filter(|program_header| check_program_flags(program_header.p_flags, program_header))
             .map(|program_header| -> anyhow::Result<_> {
                 let file_size: usize = program_header.p_filesz.try_into()?;
@@ -209,7 +505,7 @@ impl Program {
 
                 let min_size = min(file_size, mem_size);
                 let max_size = max(file_size, mem_size);
-                Ok((vaddr..).zip(
+                Ok((vaddr..=u32::MAX).zip(
                     chain!(&input[offset..][..min_size], repeat(&0u8))
                         .take(max_size)
                         .copied(),
@@ -217,7 +513,6 @@ impl Program {
             })
             .flatten_ok()
             .try_collect()
-            .expect("extract elf data should always succeed")
     }
 
     /// Loads a [`Program`] from static ELF.
@@ -230,9 +525,23 @@ impl Program {
     /// When `Program::load_elf` or index as address is not cast-able to be u32
     /// cast-able
     pub fn mozak_load_program(elf_bytes: &[u8]) -> Result<Program> {
-        let program =
-            Program::mozak_load_elf(elf_bytes, Program::parse_and_validate_elf(elf_bytes)?);
-        Ok(program)
+        Program::mozak_load_elf(elf_bytes, Program::parse_and_validate_elf(elf_bytes)?)
+    }
+
+    /// Loads a [`Program`] from static ELF, like
+    /// [`Program::mozak_load_program`], and also retains the ELF's symbols,
+    /// section headers and Mozak metadata in [`Program::elf_info`].
+    ///
+    /// # Errors
+    /// Will return `Err` if the ELF file is invalid, if the entrypoint is
+    /// invalid, or if its symbols, sections or metadata are malformed.
+    pub fn mozak_load_program_with_elf_info(elf_bytes: &[u8]) -> Result<Program> {
+        let (elf, entry_point, segments) = Program::parse_and_validate_elf(elf_bytes)?;
+        let elf_info = ElfInfo::parse(&elf)?;
+        Ok(Program {
+            elf_info: Some(elf_info),
+            ..Program::mozak_load_elf(elf_bytes, (elf, entry_point, segments))?
+        })
     }
 
     /// Creates a [`Program`] with [`Code`].
@@ -264,4 +573,250 @@ mod test {
     fn test_mozak_load_program_default() {
         Program::mozak_load_program(mozak_examples::EMPTY_ELF).unwrap();
     }
+
+    #[test]
+    fn test_mozak_load_program_with_elf_info() {
+        let program = Program::mozak_load_program_with_elf_info(mozak_examples::EMPTY_ELF).unwrap();
+        let info = program.elf_info.as_ref().unwrap();
+        assert!(info.sections.iter().any(|section| section.name == ".text"));
+        assert_eq!(info.symbol("_start").unwrap().addr, program.entry_point);
+        assert!(info
+            .function_at(info.symbol("__start").unwrap().addr)
+            .is_some());
+        assert_eq!(info.metadata, None);
+
+        // Retaining ELF info must not change what gets executed.
+        assert_eq!(
+            Program {
+                elf_info: None,
+                ..program
+            },
+            Program::mozak_load_program(mozak_examples::EMPTY_ELF).unwrap()
+        );
+    }
+
+    /// A `PT_LOAD` segment of a hand-built test ELF.  `offset` is relative to
+    /// the data following the program headers.
+    #[derive(Clone, Copy)]
+    struct Segment {
+        flags: u32,
+        vaddr: u32,
+        offset: u32,
+        file_size: u32,
+        mem_size: u32,
+    }
+
+    const CODE: Segment = Segment {
+        flags: elf::abi::PF_R | elf::abi::PF_X,
+        vaddr: 0x1000,
+        offset: 0,
+        file_size: 16,
+        mem_size: 16,
+    };
+
+    /// Build a minimal RV32 executable, whose data consists of `NOP`s.
+    fn build_elf(entry_point: u32, segments: &[Segment]) -> Vec<u8> {
+        const EHDR_SIZE: u16 = 52;
+        const PHDR_SIZE: u16 = 32;
+        let phnum = u16::try_from(segments.len()).unwrap();
+        let data_start = u32::from(EHDR_SIZE + PHDR_SIZE * phnum);
+
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1];
+        elf.resize(16, 0);
+        for half in [elf::abi::ET_EXEC, elf::abi::EM_RISCV] {
+            elf.extend(half.to_le_bytes());
+        }
+        for word in [1, entry_point, u32::from(EHDR_SIZE), 0, 0] {
+            elf.extend(word.to_le_bytes());
+        }
+        for half in [EHDR_SIZE, PHDR_SIZE, phnum, 40, 0, 0] {
+            elf.extend(half.to_le_bytes());
+        }
+        for segment in segments {
+            for word in [
+                elf::abi::PT_LOAD,
+                data_start.wrapping_add(segment.offset),
+                segment.vaddr,
+                segment.vaddr,
+                segment.file_size,
+                segment.mem_size,
+                segment.flags,
+                4,
+            ] {
+                elf.extend(word.to_le_bytes());
+            }
+        }
+        elf.extend([0x13, 0, 0, 0].repeat(4));
+        elf
+    }
+
+    fn load_error(elf: &[u8]) -> ElfLoadError {
+        *Program::mozak_load_program(elf)
+            .unwrap_err()
+            .downcast_ref::<ElfLoadError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_load_hand_built_elf() {
+        let program = Program::mozak_load_program(&build_elf(0x1000, &[CODE])).unwrap();
+        assert_eq!(program.ro_code.len(), 4);
+        assert_eq!(program.ro_memory.len(), 16);
+    }
+
+    #[test]
+    fn test_load_segment_at_top_of_address_space() {
+        let top = Segment {
+            vaddr: 0xFFFF_FFF0,
+            ..CODE
+        };
+        let program = Program::mozak_load_program(&build_elf(0xFFFF_FFFC, &[top])).unwrap();
+        assert!(program.ro_memory.contains_key(&u32::MAX));
+    }
+
+    #[test]
+    fn test_load_overlapping_segments() {
+        let data = Segment {
+            flags: elf::abi::PF_R | elf::abi::PF_W,
+            vaddr: 0x1008,
+            ..CODE
+        };
+        assert_eq!(
+            load_error(&build_elf(0x1000, &[data, CODE])),
+            ElfLoadError::OverlappingSegments {
+                first: 0x1000,
+                second: 0x1008
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_segment_beyond_address_space() {
+        let beyond = Segment {
+            vaddr: 0xFFFF_FFF8,
+            ..CODE
+        };
+        assert_eq!(
+            load_error(&build_elf(0x1000, &[CODE, beyond])),
+            ElfLoadError::SegmentOutOfAddressSpace {
+                vaddr: 0xFFFF_FFF8,
+                mem_size: 16
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_file_offset_out_of_range() {
+        let elf = build_elf(0x1000, &[Segment { offset: 8, ..CODE }]);
+        assert!(matches!(
+            load_error(&elf),
+            ElfLoadError::FileOffsetOutOfRange { file_size: 16, file_len, .. }
+                if file_len == elf.len()
+        ));
+    }
+
+    #[test]
+    fn test_load_writable_executable_segment() {
+        let elf = build_elf(0x1000, &[Segment {
+            flags: elf::abi::PF_R | elf::abi::PF_W | elf::abi::PF_X,
+            ..CODE
+        }]);
+        assert_eq!(load_error(&elf), ElfLoadError::WritableExecutableSegment {
+            vaddr: 0x1000
+        });
+        // Vanilla ELFs, like the riscv-tests, commonly come with RWX segments.
+        Program::vanilla_load_elf(&elf).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_entry_point_code() {
+        assert_eq!(
+            load_error(&build_elf(0x2000, &[CODE])),
+            ElfLoadError::MissingEntryPointCode {
+                entry_point: 0x2000
+            }
+        );
+    }
+
+    #[test]
+    fn test_data_from_overlapping_words() {
+        let words = |words: &[(u32, u32)]| words.iter().copied().collect::<HashMap<_, _>>();
+        assert_eq!(
+            Data::try_from(words(&[(0, 0xDEAD_BEEF), (1, 0xDEAD_BEEF)])),
+            Err(ElfLoadError::OverlappingSegments {
+                first: 0,
+                second: 1
+            })
+        );
+        assert!(Data::try_from(words(&[(u32::MAX - 2, 0)])).is_err());
+        let data = Data::try_from(words(&[(0, 0xDEAD_BEEF), (u32::MAX - 3, 0x0102_0304)])).unwrap();
+        assert_eq!(data.get(&0), Some(&0xEF));
+        assert_eq!(data.get(&u32::MAX), Some(&0x01));
+    }
+
+    #[test]
+    fn test_function_at() {
+        let symbol = |name: &str, addr, size, is_function| ElfSymbol {
+            name: name.to_string(),
+            addr,
+            size,
+            is_function,
+        };
+        let info = ElfInfo {
+            symbols: vec![
+                symbol("data", 0x100, 0x10, false),
+                symbol("main", 0x100, 0x20, true),
+                symbol("empty", 0x120, 0, true),
+            ],
+            ..ElfInfo::default()
+        };
+        assert_eq!(info.function_at(0x11c).unwrap().name, "main");
+        assert_eq!(info.function_at(0x120).unwrap().name, "empty");
+        assert_eq!(info.function_at(0x124), None);
+        assert_eq!(info.symbol("data").unwrap().addr, 0x100);
+    }
+
+    fn note(name: &str, n_type: u32, desc: &[u8]) -> Vec<u8> {
+        let pad = |bytes: &mut Vec<u8>| bytes.resize((bytes.len() + 3) & !3, 0);
+        let name = format!("{name}\0");
+        let mut bytes = vec![];
+        for word in [name.len(), desc.len()] {
+            bytes.extend(u32::try_from(word).unwrap().to_le_bytes());
+        }
+        bytes.extend(n_type.to_le_bytes());
+        bytes.extend(name.as_bytes());
+        pad(&mut bytes);
+        bytes.extend(desc);
+        pad(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_mozak_metadata_note() {
+        let desc = b"name=token\nsdk_version=0.2.0\ntapes=public,call\nfuture_key=1\n";
+        let data = [
+            note("GNU", 3, b"abc"),
+            note(MOZAK_NOTE_NAME, NT_MOZAK_METADATA, desc),
+        ]
+        .concat();
+        let notes = parse_notes(&data).unwrap();
+        assert_eq!(notes.len(), 2);
+        let (name, n_type, desc) = notes[1];
+        assert_eq!((name, n_type), (MOZAK_NOTE_NAME, NT_MOZAK_METADATA));
+        assert_eq!(
+            MozakMetadata::from_note_desc(desc).unwrap(),
+            MozakMetadata {
+                program_name: "token".to_string(),
+                sdk_version: "0.2.0".to_string(),
+                tapes: vec!["public".to_string(), "call".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated_note() {
+        let data = note(MOZAK_NOTE_NAME, NT_MOZAK_METADATA, b"name=x");
+        assert!(parse_notes(&data[..data.len() - 8]).is_err());
+        assert!(MozakMetadata::from_note_desc(b"no equals sign").is_err());
+    }
 }
@@ -15,9 +15,12 @@ pub mod ecall;
 pub mod elf;
 pub mod instruction;
 pub mod poseidon2;
+#[cfg(test)]
+mod spec_model;
 pub mod state;
 #[cfg(any(feature = "test", test))]
 pub mod test_utils;
+pub mod transaction;
 pub mod vm;
 
 extern crate alloc;
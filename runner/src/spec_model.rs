@@ -0,0 +1,471 @@
+//! A minimal RV32IM reference interpreter, written directly against the RISC-V
+//! unprivileged specification, and a differential harness that checks
+//! [`State::execute_instruction`] against it.
+//!
+//! The model works on raw instruction words, so it is independent of both
+//! [`decode_instruction`] and of the way the runner re-expresses some
+//! instructions (e.g. `SLLI` as `MUL`, `SRLI` as `DIVU`, `LUI` as `ADD`).
+//!
+//! Like [`State`], the model follows a modified Harvard architecture: code
+//! lives in its own address space and is never read through data memory.
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_possible_wrap)]
+#![allow(clippy::cast_sign_loss)]
+
+use std::collections::BTreeMap;
+
+use plonky2::field::goldilocks_field::GoldilocksField;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::code::Code;
+use crate::decode::decode_instruction;
+use crate::elf::Program;
+use crate::state::{RawTapes, State};
+use crate::test_utils::u32_extra;
+
+/// Upper bound on the number of steps we take per sequence, so that backward
+/// branches can't make a test case loop forever.
+const MAX_STEPS: usize = 64;
+
+/// Architectural state of the reference model.
+#[derive(Debug, Clone, Default)]
+struct SpecState {
+    pc: u32,
+    registers: [u32; 32],
+    memory: BTreeMap<u32, u8>,
+}
+
+/// Bits `hi..=lo` of `word`, shifted down to bit 0.
+fn bits(word: u32, hi: u32, lo: u32) -> u32 { (word >> lo) & ((1 << (hi - lo + 1)) - 1) }
+
+/// Sign extend the lowest `width` bits of `value`.
+fn sign_extend(value: u32, width: u32) -> u32 {
+    let shift = 32 - width;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+/// Register-register and register-immediate arithmetic, keyed by `funct7` and
+/// `funct3`.
+fn alu(funct7: u32, funct3: u32, a: u32, b: u32) -> Option<u32> {
+    let (sa, sb) = (a as i32, b as i32);
+    Some(match (funct7, funct3) {
+        (0b000_0000, 0b000) => a.wrapping_add(b),
+        (0b010_0000, 0b000) => a.wrapping_sub(b),
+        (0b000_0000, 0b001) => a << (b & 0x1F),
+        (0b000_0000, 0b010) => u32::from(sa < sb),
+        (0b000_0000, 0b011) => u32::from(a < b),
+        (0b000_0000, 0b100) => a ^ b,
+        (0b000_0000, 0b101) => a >> (b & 0x1F),
+        (0b010_0000, 0b101) => (sa >> (b & 0x1F)) as u32,
+        (0b000_0000, 0b110) => a | b,
+        (0b000_0000, 0b111) => a & b,
+        (0b000_0001, 0b000) => (u128::from(a) * u128::from(b)) as u32,
+        (0b000_0001, 0b001) => ((i128::from(sa) * i128::from(sb)) >> 32) as u32,
+        (0b000_0001, 0b010) => ((i128::from(sa) * i128::from(b)) >> 32) as u32,
+        (0b000_0001, 0b011) => ((u128::from(a) * u128::from(b)) >> 32) as u32,
+        (0b000_0001, 0b100) => match (sa, sb) {
+            (_, 0) => u32::MAX,
+            (i32::MIN, -1) => a,
+            _ => (sa / sb) as u32,
+        },
+        (0b000_0001, 0b101) => a.checked_div(b).unwrap_or(u32::MAX),
+        (0b000_0001, 0b110) => match (sa, sb) {
+            (_, 0) => a,
+            (i32::MIN, -1) => 0,
+            _ => (sa % sb) as u32,
+        },
+        (0b000_0001, 0b111) => a.checked_rem(b).unwrap_or(a),
+        _ => return None,
+    })
+}
+
+impl SpecState {
+    fn reg(&self, index: u32) -> u32 { self.registers[index as usize] }
+
+    fn set_reg(&mut self, index: u32, value: u32) {
+        if index != 0 {
+            self.registers[index as usize] = value;
+        }
+    }
+
+    fn load(&self, addr: u32, bytes: u32) -> u32 {
+        (0..bytes).rev().fold(0, |acc, i| {
+            (acc << 8) | u32::from(self.load_u8(addr.wrapping_add(i)))
+        })
+    }
+
+    fn load_u8(&self, addr: u32) -> u8 { self.memory.get(&addr).copied().unwrap_or_default() }
+
+    fn store(&mut self, addr: u32, bytes: u32, value: u32) {
+        for i in 0..bytes {
+            self.memory
+                .insert(addr.wrapping_add(i), (value >> (8 * i)) as u8);
+        }
+    }
+
+    /// Execute a single instruction word.
+    ///
+    /// Returns `None` for encodings outside of RV32IM (minus `SYSTEM` and
+    /// `FENCE`), which the model does not cover.
+    fn step(&mut self, word: u32) -> Option<()> {
+        let opcode = bits(word, 6, 0);
+        let rd = bits(word, 11, 7);
+        let funct3 = bits(word, 14, 12);
+        let funct7 = bits(word, 31, 25);
+        let rs1 = self.reg(bits(word, 19, 15));
+        let rs2 = self.reg(bits(word, 24, 20));
+
+        let imm_i = sign_extend(bits(word, 31, 20), 12);
+        let imm_s = sign_extend(bits(word, 31, 25) << 5 | bits(word, 11, 7), 12);
+        let imm_b = sign_extend(
+            bits(word, 31, 31) << 12
+                | bits(word, 7, 7) << 11
+                | bits(word, 30, 25) << 5
+                | bits(word, 11, 8) << 1,
+            13,
+        );
+        let imm_u = word & 0xFFFF_F000;
+        let imm_j = sign_extend(
+            bits(word, 31, 31) << 20
+                | bits(word, 19, 12) << 12
+                | bits(word, 20, 20) << 11
+                | bits(word, 30, 21) << 1,
+            21,
+        );
+
+        let next_pc = self.pc.wrapping_add(4);
+        let mut new_pc = next_pc;
+        match opcode {
+            // OP
+            0b011_0011 => self.set_reg(rd, alu(funct7, funct3, rs1, rs2)?),
+            // OP-IMM
+            0b001_0011 => {
+                let value = match funct3 {
+                    0b001 | 0b101 => alu(funct7, funct3, rs1, bits(word, 24, 20))?,
+                    _ => alu(0, funct3, rs1, imm_i)?,
+                };
+                self.set_reg(rd, value);
+            }
+            // LOAD
+            0b000_0011 => {
+                let addr = rs1.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0b000 => sign_extend(self.load(addr, 1), 8),
+                    0b001 => sign_extend(self.load(addr, 2), 16),
+                    0b010 => self.load(addr, 4),
+                    0b100 => self.load(addr, 1),
+                    0b101 => self.load(addr, 2),
+                    _ => return None,
+                };
+                self.set_reg(rd, value);
+            }
+            // STORE
+            0b010_0011 => {
+                let bytes = match funct3 {
+                    0b000 => 1,
+                    0b001 => 2,
+                    0b010 => 4,
+                    _ => return None,
+                };
+                self.store(rs1.wrapping_add(imm_s), bytes, rs2);
+            }
+            // BRANCH
+            0b110_0011 => {
+                let taken = match funct3 {
+                    0b000 => rs1 == rs2,
+                    0b001 => rs1 != rs2,
+                    0b100 => (rs1 as i32) < (rs2 as i32),
+                    0b101 => (rs1 as i32) >= (rs2 as i32),
+                    0b110 => rs1 < rs2,
+                    0b111 => rs1 >= rs2,
+                    _ => return None,
+                };
+                if taken {
+                    new_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            // LUI
+            0b011_0111 => self.set_reg(rd, imm_u),
+            // AUIPC
+            0b001_0111 => self.set_reg(rd, self.pc.wrapping_add(imm_u)),
+            // JAL
+            0b110_1111 => {
+                new_pc = self.pc.wrapping_add(imm_j);
+                self.set_reg(rd, next_pc);
+            }
+            // JALR
+            0b110_0111 if funct3 == 0 => {
+                new_pc = rs1.wrapping_add(imm_i) & !1;
+                self.set_reg(rd, next_pc);
+            }
+            _ => return None,
+        }
+        self.pc = new_pc;
+        Some(())
+    }
+}
+
+fn encode_r(opcode: u32, funct3: u32, funct7: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    funct7 << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn encode_i(opcode: u32, funct3: u32, rd: u32, rs1: u32, imm: u32) -> u32 {
+    (imm & 0xFFF) << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn encode_s(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    bits(imm, 11, 5) << 25
+        | rs2 << 20
+        | rs1 << 15
+        | funct3 << 12
+        | bits(imm, 4, 0) << 7
+        | 0b010_0011
+}
+
+fn encode_b(funct3: u32, rs1: u32, rs2: u32, imm: u32) -> u32 {
+    bits(imm, 12, 12) << 31
+        | bits(imm, 10, 5) << 25
+        | rs2 << 20
+        | rs1 << 15
+        | funct3 << 12
+        | bits(imm, 4, 1) << 8
+        | bits(imm, 11, 11) << 7
+        | 0b110_0011
+}
+
+fn encode_u(opcode: u32, rd: u32, imm: u32) -> u32 { imm & 0xFFFF_F000 | rd << 7 | opcode }
+
+fn encode_j(rd: u32, imm: u32) -> u32 {
+    bits(imm, 20, 20) << 31
+        | bits(imm, 10, 1) << 21
+        | bits(imm, 11, 11) << 20
+        | bits(imm, 19, 12) << 12
+        | rd << 7
+        | 0b110_1111
+}
+
+/// `(funct7, funct3)` of every RV32IM register-register instruction.
+const OP_FUNCTS: [(u32, u32); 18] = [
+    (0b000_0000, 0b000),
+    (0b010_0000, 0b000),
+    (0b000_0000, 0b001),
+    (0b000_0000, 0b010),
+    (0b000_0000, 0b011),
+    (0b000_0000, 0b100),
+    (0b000_0000, 0b101),
+    (0b010_0000, 0b101),
+    (0b000_0000, 0b110),
+    (0b000_0000, 0b111),
+    (0b000_0001, 0b000),
+    (0b000_0001, 0b001),
+    (0b000_0001, 0b010),
+    (0b000_0001, 0b011),
+    (0b000_0001, 0b100),
+    (0b000_0001, 0b101),
+    (0b000_0001, 0b110),
+    (0b000_0001, 0b111),
+];
+
+fn reg() -> impl Strategy<Value = u32> { 0..32_u32 }
+
+fn imm12() -> impl Strategy<Value = u32> { u32_extra().prop_map(|imm| imm & 0xFFF) }
+
+/// Small, word aligned jump offsets, so that control flow has a fair chance
+/// of staying within the generated program.
+fn offset() -> impl Strategy<Value = u32> { (-8_i32..8).prop_map(|x| (x * 4) as u32) }
+
+/// A random, validly encoded RV32IM instruction word.
+fn instruction() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        (select(&OP_FUNCTS[..]), reg(), reg(), reg()).prop_map(
+            |((funct7, funct3), rd, rs1, rs2)| {
+                encode_r(0b011_0011, funct3, funct7, rd, rs1, rs2)
+            }
+        ),
+        (
+            select(&[0b000, 0b010, 0b011, 0b100, 0b110, 0b111][..]),
+            reg(),
+            reg(),
+            imm12()
+        )
+            .prop_map(|(funct3, rd, rs1, imm)| encode_i(0b001_0011, funct3, rd, rs1, imm)),
+        (
+            select(
+                &[
+                    (0b000_0000, 0b001),
+                    (0b000_0000, 0b101),
+                    (0b010_0000, 0b101)
+                ][..]
+            ),
+            reg(),
+            reg(),
+            0..32_u32
+        )
+            .prop_map(|((funct7, funct3), rd, rs1, shamt)| {
+                encode_r(0b001_0011, funct3, funct7, rd, rs1, shamt)
+            }),
+        (
+            select(&[0b000, 0b001, 0b010, 0b100, 0b101][..]),
+            reg(),
+            reg(),
+            imm12()
+        )
+            .prop_map(|(funct3, rd, rs1, imm)| encode_i(0b000_0011, funct3, rd, rs1, imm)),
+        (select(&[0b000, 0b001, 0b010][..]), reg(), reg(), imm12())
+            .prop_map(|(funct3, rs1, rs2, imm)| encode_s(funct3, rs1, rs2, imm)),
+        (
+            select(&[0b000, 0b001, 0b100, 0b101, 0b110, 0b111][..]),
+            reg(),
+            reg(),
+            offset()
+        )
+            .prop_map(|(funct3, rs1, rs2, imm)| encode_b(funct3, rs1, rs2, imm)),
+        (select(&[0b011_0111, 0b001_0111][..]), reg(), u32_extra())
+            .prop_map(|(opcode, rd, imm)| encode_u(opcode, rd, imm)),
+        (reg(), offset()).prop_map(|(rd, imm)| encode_j(rd, imm)),
+        (reg(), reg(), imm12()).prop_map(|(rd, rs1, imm)| encode_i(0b110_0111, 0, rd, rs1, imm)),
+    ]
+}
+
+/// Register values biased towards small numbers, so that loads and stores
+/// frequently hit initialised memory.
+fn register_value() -> impl Strategy<Value = u32> { prop_oneof![u32_extra(), 0..256_u32] }
+
+/// Runs `words` (placed at address 0 onwards) through both the runner and the
+/// reference model, comparing pc, registers and memory after every step.
+///
+/// Execution stops once control flow leaves the program, or after
+/// [`MAX_STEPS`].
+fn run_differential(
+    words: &[u32],
+    registers: [u32; 32],
+    memory: &[(u32, u8)],
+) -> Result<(), TestCaseError> {
+    let code = Code(
+        (0..)
+            .step_by(4)
+            .zip(words)
+            .map(|(pc, &word)| (pc, decode_instruction(pc, word)))
+            .collect(),
+    );
+    let program = Program::create(&[], memory, code);
+    let mut state: State<GoldilocksField> = (1..32).fold(
+        State::new(program.clone(), RawTapes::default()),
+        |state, i| state.set_register_value(i, registers[usize::from(i)]),
+    );
+    let mut spec = SpecState {
+        pc: 0,
+        registers,
+        memory: memory.iter().copied().collect(),
+    };
+    spec.registers[0] = 0;
+
+    for _ in 0..MAX_STEPS {
+        let pc = spec.pc;
+        let Some(&word) = words.get(pc as usize / 4).filter(|_| pc % 4 == 0) else {
+            break;
+        };
+        state = match state.execute_instruction(&program) {
+            Ok((_, _, state)) => state,
+            Err(e) => return Err(TestCaseError::fail(format!("{word:#010x} at {pc:#x}: {e}"))),
+        };
+        let modelled = spec.step(word);
+        prop_assert!(modelled.is_some(), "{:#010x} is not modelled", word);
+
+        prop_assert_eq!(
+            state.get_pc(),
+            spec.pc,
+            "pc after {:#010x} at {:#x}",
+            word,
+            pc
+        );
+        prop_assert_eq!(
+            state.registers,
+            spec.registers,
+            "registers after {:#010x} at {:#x}",
+            word,
+            pc
+        );
+        for &addr in spec.memory.keys().chain(state.memory.data.keys()) {
+            prop_assert_eq!(
+                state.load_u8(addr),
+                spec.load_u8(addr),
+                "memory at {:#x} after {:#010x} at {:#x}",
+                addr,
+                word,
+                pc
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Register file with `values` placed in `x1` onwards.
+fn registers_with(values: &[u32]) -> [u32; 32] {
+    let mut registers = [0; 32];
+    registers[1..=values.len()].copy_from_slice(values);
+    registers
+}
+
+#[test]
+fn mulhsu_sign_handling() {
+    for (a, b) in [
+        (u32::MAX, u32::MAX),
+        (0x8000_0000, u32::MAX),
+        (0x8000_0000, 1),
+    ] {
+        run_differential(
+            &[encode_r(0b011_0011, 0b010, 0b000_0001, 3, 1, 2)],
+            registers_with(&[a, b]),
+            &[],
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn div_rem_overflow_and_zero() {
+    let words: Vec<u32> = (0b100..=0b111)
+        .map(|funct3| encode_r(0b011_0011, funct3, 0b000_0001, 3, 1, 2))
+        .collect();
+    for (a, b) in [(0x8000_0000, u32::MAX), (0x8000_0000, 0), (7, 0), (0, 0)] {
+        run_differential(&words, registers_with(&[a, b]), &[]).unwrap();
+    }
+}
+
+#[test]
+fn jalr_clears_lowest_bit() {
+    // jalr x1, 1(x2); with x2 = 8 the target 9 has its lowest bit cleared, so we
+    // land on 8.
+    let words = [
+        encode_i(0b110_0111, 0, 1, 2, 1),
+        0,
+        encode_r(0b011_0011, 0, 0, 3, 1, 1),
+    ];
+    run_differential(&words, registers_with(&[0, 8]), &[]).unwrap();
+}
+
+#[test]
+fn misaligned_loads_and_stores() {
+    let words = [
+        encode_s(0b010, 1, 2, 1),
+        encode_i(0b000_0011, 0b001, 3, 1, 3),
+        encode_i(0b000_0011, 0b010, 4, 1, 0xFFF),
+    ];
+    run_differential(&words, registers_with(&[0x11, 0xDEAD_BEEF]), &[(
+        0x10, 0x80,
+    )])
+    .unwrap();
+}
+
+proptest! {
+    #[test]
+    fn random_sequences_agree_with_spec(
+        words in prop::collection::vec(instruction(), 1..32),
+        registers in prop::array::uniform32(register_value()),
+        memory in prop::collection::vec((0..256_u32, any::<u8>()), 0..64),
+    ) {
+        run_differential(&words, registers, &memory)?;
+    }
+}
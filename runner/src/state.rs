@@ -1,6 +1,6 @@
 use std::iter::once;
 use std::marker::PhantomData;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use im::hashmap::HashMap;
@@ -75,9 +75,37 @@ pub struct State<F: RichField> {
     pub events_commitment_tape: CommitmentTape,
     pub cast_list_commitment_tape: CommitmentTape,
     pub self_prog_id_tape: [u8; DIGEST_BYTES],
+    pub alignment_policy: AlignmentPolicy,
     _phantom: PhantomData<F>,
 }
 
+/// How the VM treats halfword and word loads and stores whose address is not
+/// a multiple of the access size.
+///
+/// Byte accesses are always aligned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum AlignmentPolicy {
+    /// Misaligned accesses are carried out byte by byte, just like aligned
+    /// ones.  The memory circuits prove these accesses.
+    #[default]
+    Allow,
+    /// Misaligned accesses stop execution with an error, so such a guest
+    /// never reaches the prover.
+    Trap,
+}
+
+impl AlignmentPolicy {
+    /// Whether an access of `bytes` bytes at `addr` may go ahead under this
+    /// policy.
+    #[must_use]
+    pub fn permits(self, addr: u32, bytes: u32) -> bool {
+        match self {
+            AlignmentPolicy::Allow => true,
+            AlignmentPolicy::Trap => addr % bytes == 0,
+        }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Default)]
 pub struct StateMemory {
@@ -104,7 +132,7 @@ impl StateMemory {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StorageDeviceTape {
-    pub data: Rc<[u8]>,
+    pub data: Arc<[u8]>,
     pub read_index: usize,
 }
 
@@ -122,7 +150,7 @@ impl Default for StorageDeviceTape {
 impl From<Data> for StorageDeviceTape {
     fn from(data: Data) -> Self {
         Self {
-            data: data.0.values().copied().collect::<Rc<[u8]>>(),
+            data: data.0.values().copied().collect::<Arc<[u8]>>(),
             read_index: 0,
         }
     }
@@ -147,6 +175,7 @@ impl<F: RichField> Default for State<F> {
             events_commitment_tape: CommitmentTape([0; DIGEST_BYTES]),
             cast_list_commitment_tape: CommitmentTape([0; DIGEST_BYTES]),
             self_prog_id_tape: [0; 32],
+            alignment_policy: AlignmentPolicy::default(),
             _phantom: PhantomData,
         }
     }
@@ -160,6 +189,7 @@ impl<F: RichField> From<Program> for State<F> {
             rw_memory: Data(rw_memory),
             ro_memory: Data(ro_memory),
             entry_point: pc,
+            ..
         }: Program,
     ) -> Self {
         let state: State<F> = State::default();
@@ -268,6 +298,14 @@ impl<F: RichField> State<F> {
         }
     }
 
+    #[must_use]
+    pub fn with_alignment_policy(self, alignment_policy: AlignmentPolicy) -> Self {
+        Self {
+            alignment_policy,
+            ..self
+        }
+    }
+
     #[must_use]
     pub fn register_op<Fun>(self, data: &Args, op: Fun) -> (Aux<F>, Self)
     where
@@ -0,0 +1,354 @@
+//! Native execution of all the programs taking part in a transaction.
+//!
+//! A transaction is described by a [`SystemTape`].  Every program in its cast
+//! list runs against the [`RawTapes`] derived for it from the system tape, and
+//! has to read exactly the call tape derived for it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, ensure, Result};
+use itertools::izip;
+use log::debug;
+use mozak_sdk::common::merkle::merkleize;
+use mozak_sdk::common::types::{
+    CanonicalOrderedTemporalHints, CrossProgramCall, Poseidon2Hash, ProgramIdentifier, SystemTape,
+};
+use plonky2::hash::hash_types::RichField;
+use plonky2_maybe_rayon::*;
+use rkyv::rancor::{Panic, Strategy};
+use rkyv::ser::AllocSerializer;
+
+use crate::elf::Program;
+use crate::state::{RawTapes, State, StorageDeviceOpcode};
+use crate::vm::{step, ExecutionRecord};
+
+/// Programs called in `system_tape`, in canonical order.
+#[must_use]
+pub fn cast_list(system_tape: &SystemTape) -> Vec<ProgramIdentifier> {
+    system_tape
+        .call_tape
+        .writer
+        .iter()
+        .map(|msg| msg.callee)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn length_prefixed_bytes(data: Vec<u8>, dgb_string: &str) -> Vec<u8> {
+    let data_len = data.len();
+    let mut len_prefix_bytes = Vec::with_capacity(data_len + 4);
+    len_prefix_bytes.extend_from_slice(
+        &(u32::try_from(data.len()))
+            .expect("length of data's max size shouldn't be more than u32")
+            .to_le_bytes(),
+    );
+    len_prefix_bytes.extend(data);
+    debug!(
+        "Length-Prefixed {:<15} of byte len: {:>5}, on-mem bytes: {:>5}",
+        dgb_string,
+        data_len,
+        len_prefix_bytes.len()
+    );
+    len_prefix_bytes
+}
+
+/// The tapes that the program `self_prog_id` sees when it takes part in the
+/// transaction described by `sys`.
+///
+/// # Panics
+///
+/// Panics if the call tape or the events of `self_prog_id` cannot be
+/// serialised.
+#[must_use]
+pub fn raw_tapes_from_system_tape(sys: &SystemTape, self_prog_id: ProgramIdentifier) -> RawTapes {
+    fn serialise<T>(tape: &T, dgb_string: &str) -> Vec<u8>
+    where
+        T: rkyv::Archive + rkyv::Serialize<Strategy<AllocSerializer<256>, Panic>>, {
+        let tape_bytes = rkyv::to_bytes::<_, 256, _>(tape).unwrap().into();
+        length_prefixed_bytes(tape_bytes, dgb_string)
+    }
+
+    let canonical_order_temporal_hints: Vec<CanonicalOrderedTemporalHints> = sys
+        .event_tape
+        .writer
+        .get(&self_prog_id)
+        .cloned()
+        .unwrap_or_default()
+        .get_canonical_order_temporal_hints();
+
+    let events_commitment_tape = merkleize(
+        canonical_order_temporal_hints
+            .iter()
+            .map(|x| {
+                (
+                    // May not be the best idea if
+                    // `addr` > goldilock's prime, cc
+                    // @Kapil
+                    u64::from_le_bytes(x.0.address.inner()),
+                    x.0.canonical_hash(),
+                )
+            })
+            .collect::<Vec<(u64, Poseidon2Hash)>>(),
+    )
+    .0;
+
+    let cast_list_commitment_tape = merkleize(
+        izip!(0.., &cast_list(sys))
+            .map(|(idx, x)| (idx, x.0))
+            .collect(),
+    )
+    .0;
+
+    debug!("Self Prog ID: {self_prog_id:#?}");
+    debug!("Found events: {:#?}", canonical_order_temporal_hints.len());
+
+    RawTapes {
+        private_tape: length_prefixed_bytes(
+            sys.private_input_tape
+                .writer
+                .get(&self_prog_id)
+                .cloned()
+                .unwrap_or_default()
+                .0,
+            "PRIVATE_TAPE",
+        ),
+        public_tape: length_prefixed_bytes(
+            sys.public_input_tape
+                .writer
+                .get(&self_prog_id)
+                .cloned()
+                .unwrap_or_default()
+                .0,
+            "PUBLIC_TAPE",
+        ),
+        call_tape: serialise(&sys.call_tape.writer, "CALL_TAPE"),
+        event_tape: serialise(&canonical_order_temporal_hints, "EVENT_TAPE"),
+        self_prog_id_tape: self_prog_id.0 .0,
+        events_commitment_tape,
+        cast_list_commitment_tape,
+    }
+}
+
+/// Bytes that the program of `record` read from its call tape, in order.
+#[must_use]
+pub fn call_tape_reads<F: RichField>(record: &ExecutionRecord<F>) -> Vec<u8> {
+    record
+        .executed
+        .iter()
+        .filter_map(|row| row.aux.storage_device_entry.as_ref())
+        .filter(|entry| entry.op == StorageDeviceOpcode::StoreCallTape)
+        .flat_map(|entry| entry.data.iter().copied())
+        .collect()
+}
+
+/// Execution records of all the programs in the cast list of a transaction.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default)]
+pub struct TransactionRecord<F: RichField> {
+    pub records: BTreeMap<ProgramIdentifier, ExecutionRecord<F>>,
+}
+
+impl<F: RichField> TransactionRecord<F> {
+    /// Checks that every program read exactly the call tape that
+    /// `system_tape` prescribes for it.
+    ///
+    /// Every program sees the whole call tape, as the cast list is derived
+    /// from it, so a program that read it correctly also saw every
+    /// [`CrossProgramCall`] addressed to it or emitted by it.
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first program whose reads differ from its call tape.
+    pub fn check_call_tape_consistency(&self, system_tape: &SystemTape) -> Result<()> {
+        for (&id, record) in &self.records {
+            let expected = raw_tapes_from_system_tape(system_tape, id).call_tape;
+            let reads = call_tape_reads(record);
+            ensure!(
+                reads == expected,
+                "Program {id:?} disagrees with the system tape on the call tape, with {} calls \
+                 addressed to or emitted by it: read {} of {} bytes, of which the first {} match",
+                calls_of(system_tape, id).count(),
+                reads.len(),
+                expected.len(),
+                izip!(&reads, &expected)
+                    .take_while(|(read, expected)| read == expected)
+                    .count()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Calls of `system_tape` addressed to or emitted by `id`.
+pub fn calls_of(
+    system_tape: &SystemTape,
+    id: ProgramIdentifier,
+) -> impl Iterator<Item = &CrossProgramCall> {
+    system_tape
+        .call_tape
+        .writer
+        .iter()
+        .filter(move |call| call.caller == id || call.callee == id)
+}
+
+/// Executes every program in the cast list of `system_tape`, in parallel, each
+/// with the [`RawTapes`] derived for it.
+///
+/// # Errors
+///
+/// Errors if a program of the cast list is missing from `programs`, if any
+/// execution fails, or if a program disagrees with the system tape on the
+/// call tape.
+#[allow(clippy::module_name_repetitions)]
+pub fn execute_transaction<F: RichField>(
+    system_tape: &SystemTape,
+    programs: &HashMap<ProgramIdentifier, Program>,
+) -> Result<TransactionRecord<F>> {
+    let runs = cast_list(system_tape)
+        .into_iter()
+        .map(|id| {
+            let program = programs
+                .get(&id)
+                .ok_or_else(|| anyhow!("Program {id:?} of the cast list is missing"))?;
+            Ok((id, program, raw_tapes_from_system_tape(system_tape, id)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let records = runs
+        .into_par_iter()
+        .map(|(id, program, raw_tapes)| {
+            let record = step(program, State::new(program.clone(), raw_tapes))
+                .map_err(|err| err.context(format!("Failed to execute program {id:?}")))?;
+            Ok((id, record))
+        })
+        .collect::<Result<BTreeMap<_, _>>>()?;
+    let transaction = TransactionRecord { records };
+    transaction.check_call_tape_consistency(system_tape)?;
+    Ok(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use mozak_sdk::core::ecall;
+    use mozak_sdk::core::reg_abi::{REG_A0, REG_A1, REG_A2};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::code::Code;
+    use crate::decode::ECALL;
+    use crate::instruction::{Args, Instruction, Op};
+
+    /// A program that reads `num_bytes` from its call tape and halts.
+    fn call_tape_reader(num_bytes: u32) -> Program {
+        let set = |rd, imm| {
+            Instruction::new(Op::ADD, Args {
+                rd,
+                imm,
+                ..Args::default()
+            })
+        };
+        let code = [
+            set(REG_A0, ecall::CALL_TAPE),
+            set(REG_A1, 0x1000),
+            set(REG_A2, num_bytes),
+            ECALL,
+            set(REG_A0, ecall::HALT),
+            ECALL,
+        ];
+        Program::create(
+            &[],
+            &[],
+            Code(
+                izip!((0..).step_by(4), code)
+                    .map(|(pc, inst)| (pc, Ok(inst)))
+                    .collect(),
+            ),
+        )
+    }
+
+    fn system_tape(callees: &[ProgramIdentifier]) -> SystemTape {
+        let mut system_tape = SystemTape::default();
+        system_tape.call_tape.writer = callees
+            .iter()
+            .map(|&callee| CrossProgramCall {
+                callee,
+                ..CrossProgramCall::default()
+            })
+            .collect();
+        system_tape
+    }
+
+    #[test]
+    fn executes_every_program_in_cast_list() {
+        let ids = [1, 2].map(ProgramIdentifier::new_from_rand_seed);
+        let system_tape = system_tape(&[ids[0], ids[1], ids[0]]);
+        let call_tape_len = raw_tapes_from_system_tape(&system_tape, ids[0])
+            .call_tape
+            .len();
+        let programs = ids
+            .iter()
+            .map(|&id| (id, call_tape_reader(call_tape_len.try_into().unwrap())))
+            .collect();
+
+        let transaction = execute_transaction::<GoldilocksField>(&system_tape, &programs).unwrap();
+        assert_eq!(
+            transaction.records.keys().copied().collect::<Vec<_>>(),
+            cast_list(&system_tape)
+        );
+        for record in transaction.records.values() {
+            assert!(record.last_state.has_halted());
+            assert_eq!(call_tape_reads(record).len(), call_tape_len);
+        }
+    }
+
+    #[test]
+    fn programs_disagreeing_on_call_tape_are_rejected() {
+        let ids = [1, 2].map(ProgramIdentifier::new_from_rand_seed);
+        let system_tape = system_tape(&ids);
+        let programs = izip!(ids, [call_tape_reader(u32::MAX), call_tape_reader(4)]).collect();
+
+        let err = execute_transaction::<GoldilocksField>(&system_tape, &programs).unwrap_err();
+        assert!(err.to_string().contains("disagrees with the system tape"));
+    }
+
+    #[test]
+    fn program_reading_another_call_tape_is_rejected() {
+        let ids = [1, 2, 3].map(ProgramIdentifier::new_from_rand_seed);
+        let tape = system_tape(&[ids[0], ids[1]]);
+        // Same shape, but one call goes to another program.
+        let other_tape = system_tape(&[ids[0], ids[2]]);
+        let call_tape = raw_tapes_from_system_tape(&tape, ids[0]).call_tape;
+        let other_raw_tapes = raw_tapes_from_system_tape(&other_tape, ids[0]);
+        assert_eq!(call_tape.len(), other_raw_tapes.call_tape.len());
+        assert_ne!(call_tape, other_raw_tapes.call_tape);
+
+        let program = call_tape_reader(call_tape.len().try_into().unwrap());
+        let honest = step(
+            &program,
+            State::new(program.clone(), RawTapes {
+                call_tape,
+                ..other_raw_tapes.clone()
+            }),
+        )
+        .unwrap();
+        let misled = step(&program, State::new(program.clone(), other_raw_tapes)).unwrap();
+
+        let check = |record| {
+            TransactionRecord::<GoldilocksField> {
+                records: [(ids[0], record)].into_iter().collect(),
+            }
+            .check_call_tape_consistency(&tape)
+        };
+        assert!(check(honest).is_ok());
+        let err = check(misled).unwrap_err();
+        assert!(err.to_string().contains("disagrees with the system tape"));
+    }
+
+    #[test]
+    fn missing_program_is_rejected() {
+        let ids = [1, 2].map(ProgramIdentifier::new_from_rand_seed);
+        let programs = [(ids[0], call_tape_reader(0))].into_iter().collect();
+
+        assert!(execute_transaction::<GoldilocksField>(&system_tape(&ids), &programs).is_err());
+    }
+}
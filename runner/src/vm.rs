@@ -1,4 +1,4 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use plonky2::hash::hash_types::RichField;
 
@@ -173,6 +173,22 @@ impl<F: RichField> State<F> {
             rs2_raw.wrapping_add(inst.args.imm)
         };
 
+        let access_size = match inst.op {
+            Op::LH | Op::LHU | Op::SH => Some(2),
+            Op::LW | Op::SW => Some(4),
+            _ => None,
+        };
+        if let Some(bytes) = access_size {
+            // Loads and stores both address memory via `rs2 + imm`.
+            let addr = rs2_raw.wrapping_add(inst.args.imm);
+            ensure!(
+                self.alignment_policy.permits(addr, bytes),
+                "Misaligned {:?} of {bytes} bytes at address {addr:#x} (pc {:#x})",
+                inst.op,
+                self.get_pc()
+            );
+        }
+
         let (aux, state) = match inst.op {
             Op::ADD => rop!(u32::wrapping_add),
             // Only use lower 5 bits of rs2 or imm
@@ -331,13 +347,18 @@ pub fn step<F: RichField>(
 #[allow(clippy::cast_possible_wrap)]
 mod tests {
     use im::HashMap;
+    use itertools::izip;
+    use mozak_sdk::core::ecall;
+    use mozak_sdk::core::reg_abi::REG_A0;
     use plonky2::field::goldilocks_field::GoldilocksField;
     use proptest::prelude::ProptestConfig;
     use proptest::{prop_assume, proptest};
 
     use super::*;
     use crate::code;
+    use crate::code::Code;
     use crate::decode::ECALL;
+    use crate::state::{AlignmentPolicy, RawTapes};
     use crate::test_utils::{i16_extra, i32_extra, i8_extra, reg, u16_extra, u32_extra, u8_extra};
 
     fn simple_test_code(
@@ -1391,6 +1412,72 @@ mod tests {
     #[test]
     fn ecall() { let _ = simple_test_code([ECALL], &[], &[]); }
 
+    fn run_with_alignment_policy(
+        op: Op,
+        addr: u32,
+        policy: AlignmentPolicy,
+    ) -> Result<ExecutionRecord<GoldilocksField>> {
+        let access = Instruction::new(op, Args {
+            rs1: 1,
+            rs2: 2,
+            imm: addr,
+            ..Args::default()
+        });
+        let halt = Instruction::new(Op::ADD, Args {
+            rd: REG_A0,
+            imm: ecall::HALT,
+            ..Args::default()
+        });
+        let program = Program::create(
+            &[],
+            &[],
+            Code(
+                izip!((0..).step_by(4), [access, halt, ECALL])
+                    .map(|(pc, inst)| (pc, Ok(inst)))
+                    .collect(),
+            ),
+        );
+        let state = State::new(program.clone(), RawTapes::default()).with_alignment_policy(policy);
+        step(&program, state)
+    }
+
+    #[test]
+    fn misaligned_access_is_allowed() {
+        for op in [Op::LH, Op::LHU, Op::LW, Op::SH, Op::SW] {
+            let record = run_with_alignment_policy(op, 0x101, AlignmentPolicy::Allow).unwrap();
+            assert!(record.last_state.has_halted());
+        }
+    }
+
+    #[test]
+    fn misaligned_access_traps() {
+        for (op, addr) in [
+            (Op::LH, 0x101),
+            (Op::LHU, 0x103),
+            (Op::SH, 0x101),
+            (Op::LW, 0x102),
+            (Op::SW, 0x103),
+        ] {
+            let err = run_with_alignment_policy(op, addr, AlignmentPolicy::Trap).unwrap_err();
+            assert!(err.to_string().contains("Misaligned"), "{err}");
+        }
+    }
+
+    #[test]
+    fn aligned_access_passes_trap_policy() {
+        for (op, addr) in [
+            (Op::LB, 0x101),
+            (Op::SB, 0x103),
+            (Op::LH, 0x102),
+            (Op::SH, 0x102),
+            (Op::LW, 0x104),
+            (Op::SW, 0x104),
+        ] {
+            let record = run_with_alignment_policy(op, addr, AlignmentPolicy::Trap).unwrap();
+            assert!(record.last_state.has_halted());
+        }
+    }
+
     #[test]
     fn lui() {
         // at 0 address instruction lui